@@ -94,12 +94,17 @@ struct MlsInputReceiver {
     ident: Ident,
     generics: Generics,
     data: ast::Data<MlsVariantReceiver, MlsFieldReceiver>,
+    #[darling(default)]
+    error_context: bool,
 }
 
 impl MlsInputReceiver {
     fn handle_input(&self, operation: Operation) -> TokenStream {
         match self.data {
-            ast::Data::Struct(ref s) => struct_impl(s, operation),
+            ast::Data::Struct(ref s) => {
+                let context = self.error_context.then_some(&self.ident);
+                struct_impl(s, context, operation)
+            }
             ast::Data::Enum(ref e) => enum_impl(&self.ident, &self.attrs, e, operation),
         }
     }
@@ -244,7 +249,20 @@ fn enum_impl(
     }
 }
 
-fn struct_impl(s: &Fields<MlsFieldReceiver>, operation: Operation) -> TokenStream {
+/// Builds the `Struct.field` location attached to decoding errors of structs
+/// marked with `#[mls_codec(error_context)]`.
+fn field_location(struct_name: &Ident, field: &MlsFieldReceiver, index: usize) -> String {
+    match field.ident {
+        Some(ref ident) => format!("{struct_name}.{ident}"),
+        None => format!("{struct_name}.{index}"),
+    }
+}
+
+fn struct_impl(
+    s: &Fields<MlsFieldReceiver>,
+    context: Option<&Ident>,
+    operation: Operation,
+) -> TokenStream {
     let recurse = s.fields.iter().enumerate().map(|(index, field)| {
         let (call_tokens, field_name) = match operation {
             Operation::Size | Operation::Encode => {
@@ -253,7 +271,14 @@ fn struct_impl(s: &Fields<MlsFieldReceiver>, operation: Operation) -> TokenStrea
             Operation::Decode => (quote! {}, field.name(Index::from(index))),
         };
 
-        let handle_error = operation.is_result().then_some(quote! { ? });
+        let handle_error = match (&operation, context) {
+            (Operation::Decode, Some(struct_name)) => {
+                let location = field_location(struct_name, field, index);
+                Some(quote! { .map_err(|e| e.with_context(#location))? })
+            }
+            _ => operation.is_result().then_some(quote! { ? }),
+        };
+
         let path = field.with.clone().unwrap_or(operation.path());
         let call = operation.call();
         let extras = operation.extras();
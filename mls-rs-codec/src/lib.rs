@@ -52,6 +52,33 @@ pub enum Error {
     Utf8,
     #[cfg_attr(feature = "std", error("mls codec error: {0}"))]
     Custom(u8),
+    #[cfg_attr(feature = "std", error("failed to deserialize {location}: {source}"))]
+    Context {
+        location: &'static str,
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Annotate this error with the `Struct.field` location that was being
+    /// decoded when it occurred.
+    ///
+    /// Derived decoders of structs marked with `#[mls_codec(error_context)]`
+    /// call this for every field.
+    pub fn with_context(self, location: &'static str) -> Self {
+        Error::Context {
+            location,
+            source: Box::new(self),
+        }
+    }
+
+    /// The innermost error, with any location context removed.
+    pub fn root_cause(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } => source.root_cause(),
+            e => e,
+        }
+    }
 }
 
 /// Trait that determines the encoded length in MLS encoding.
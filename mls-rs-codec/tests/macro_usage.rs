@@ -147,6 +147,33 @@ fn round_trip_custom_module_enum() {
     assert_eq!(item, decoded)
 }
 
+#[test]
+fn struct_with_error_context() {
+    #[derive(Debug, Clone, MlsSize, MlsEncode, MlsDecode, PartialEq, Eq)]
+    #[mls_codec(error_context)]
+    struct TestType {
+        field_a: u8,
+        field_b: Vec<u8>,
+    }
+
+    let item = TestType {
+        field_a: 42,
+        field_b: vec![1, 2, 3],
+    };
+
+    let serialized = item.mls_encode_to_vec().unwrap();
+    let decoded = TestType::mls_decode(&mut &*serialized).unwrap();
+    assert_eq!(item, decoded);
+
+    let res = TestType::mls_decode(&mut &serialized[..serialized.len() - 1]);
+
+    assert_matches::assert_matches!(
+        res,
+        Err(mls_rs_codec::Error::Context { location: "TestType.field_b", ref source })
+            if matches!(**source, mls_rs_codec::Error::UnexpectedEOF)
+    );
+}
+
 mod test_with {
     use mls_rs_codec::MlsDecode;
 
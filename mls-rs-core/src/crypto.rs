@@ -293,6 +293,19 @@ impl AsRef<[u8]> for SignatureSecretKey {
     }
 }
 
+/// Context in which MLS uses the AEAD of a cipher suite.
+///
+/// See [`CipherSuiteProvider::aead_seal_in`].
+#[cfg(feature = "experimental_aead_separation")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AeadContext {
+    /// Encryption of private messages and of their sender data.
+    Framing,
+    /// Encryption of data sent to new members along with HPKE encrypted
+    /// group secrets, i.e. the group info of welcome messages.
+    Hpke,
+}
+
 /// Provides implementations for several ciphersuites via [`CipherSuiteProvider`].
 pub trait CryptoProvider: Send + Sync {
     type CipherSuiteProvider: CipherSuiteProvider + Clone;
@@ -360,6 +373,65 @@ pub trait CipherSuiteProvider: Send + Sync {
     /// and [aead_open](CipherSuiteProvider::aead_open).
     fn aead_nonce_size(&self) -> usize;
 
+    /// Same as [aead_seal](CipherSuiteProvider::aead_seal), using the AEAD
+    /// selected for `context`.
+    ///
+    /// # Warning
+    ///
+    /// Using an AEAD other than the one of the cipher suite is **not compliant**
+    /// with RFC 9420. All members of a group must use providers selecting the
+    /// same AEADs. By default, the AEAD of the cipher suite is used in all
+    /// contexts.
+    #[cfg(feature = "experimental_aead_separation")]
+    async fn aead_seal_in(
+        &self,
+        context: AeadContext,
+        key: &[u8],
+        data: &[u8],
+        aad: Option<&[u8]>,
+        nonce: &[u8],
+    ) -> Result<Vec<u8>, Self::Error> {
+        let _ = context;
+        self.aead_seal(key, data, aad, nonce).await
+    }
+
+    /// Same as [aead_open](CipherSuiteProvider::aead_open), using the AEAD
+    /// selected for `context`.
+    ///
+    /// See [aead_seal_in](CipherSuiteProvider::aead_seal_in).
+    #[cfg(feature = "experimental_aead_separation")]
+    async fn aead_open_in(
+        &self,
+        context: AeadContext,
+        key: &[u8],
+        ciphertext: &[u8],
+        aad: Option<&[u8]>,
+        nonce: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+        let _ = context;
+        self.aead_open(key, ciphertext, aad, nonce).await
+    }
+
+    /// Same as [aead_key_size](CipherSuiteProvider::aead_key_size), for the
+    /// AEAD selected for `context`.
+    ///
+    /// See [aead_seal_in](CipherSuiteProvider::aead_seal_in).
+    #[cfg(feature = "experimental_aead_separation")]
+    fn aead_key_size_in(&self, context: AeadContext) -> usize {
+        let _ = context;
+        self.aead_key_size()
+    }
+
+    /// Same as [aead_nonce_size](CipherSuiteProvider::aead_nonce_size), for
+    /// the AEAD selected for `context`.
+    ///
+    /// See [aead_seal_in](CipherSuiteProvider::aead_seal_in).
+    #[cfg(feature = "experimental_aead_separation")]
+    fn aead_nonce_size_in(&self, context: AeadContext) -> usize {
+        let _ = context;
+        self.aead_nonce_size()
+    }
+
     /// Generate a pseudo-random key `prk` extracted from the initial key
     /// material `ikm`, using an optional random `salt`. The outputted `prk` should have
     /// [kdf_extract_size](CipherSuiteProvider::kdf_extract_size) bytes. It can be used
@@ -506,4 +578,21 @@ pub trait CipherSuiteProvider: Send + Sync {
         signature: &[u8],
         data: &[u8],
     ) -> Result<(), Self::Error>;
+
+    /// Prefix prepended to the label of every signature created and verified
+    /// by MLS with this cipher suite, i.e. the label `"MLS 1.0 " + Label` of
+    /// `SignWithLabel` becomes `prefix + "MLS 1.0 " + Label`.
+    ///
+    /// This separates signatures of a deployment from the ones of any other
+    /// MLS deployment using the same keys.
+    ///
+    /// # Warning
+    ///
+    /// Using a non-empty prefix is **not compliant** with RFC 9420. All
+    /// members of a group must use providers with the same prefix. By default,
+    /// the prefix is empty.
+    #[cfg(feature = "experimental_sign_label_prefix")]
+    fn sign_label_prefix(&self) -> &[u8] {
+        &[]
+    }
 }
@@ -51,6 +51,12 @@ pub trait IntoAnyError: core::fmt::Debug + Sized {
     fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
         Err(self)
     }
+
+    /// Whether the failure is temporary, such as a timeout, so that the same
+    /// call may succeed if it is made again. Errors are definitive by default.
+    fn is_transient(&self) -> bool {
+        false
+    }
 }
 
 impl IntoAnyError for mls_rs_codec::Error {
@@ -61,3 +67,19 @@ impl IntoAnyError for mls_rs_codec::Error {
 }
 
 impl IntoAnyError for core::convert::Infallible {}
+
+#[cfg(feature = "std")]
+impl IntoAnyError for std::io::Error {
+    fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+        Ok(self.into())
+    }
+
+    fn is_transient(&self) -> bool {
+        matches!(
+            self.kind(),
+            std::io::ErrorKind::Interrupted
+                | std::io::ErrorKind::TimedOut
+                | std::io::ErrorKind::WouldBlock
+        )
+    }
+}
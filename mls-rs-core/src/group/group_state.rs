@@ -61,7 +61,9 @@ impl EpochRecord {
 ///
 /// Group state will not be purged when the local member is removed from the
 /// group. It is up to the implementer of this trait to provide a mechanism
-/// to delete records that can be used by an application.
+/// to delete records that can be used by an application. Implementing
+/// [`delete_group`](GroupStateStorage::delete_group) allows records to be
+/// removed through `Group::delete` in `mls_rs`.
 ///
 
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -104,4 +106,13 @@ pub trait GroupStateStorage: Send + Sync {
     /// The [`EpochRecord::id`] value that is associated with a stored
     /// prior epoch for a particular group.
     async fn max_epoch_id(&self, group_id: &[u8]) -> Result<Option<u64>, Self::Error>;
+
+    /// Delete the current state and all prior epochs stored for a particular group.
+    ///
+    /// Returns `false` if this storage does not support deleting groups. The
+    /// default implementation deletes nothing and returns `false`.
+    async fn delete_group(&mut self, group_id: &[u8]) -> Result<bool, Self::Error> {
+        let _ = group_id;
+        Ok(false)
+    }
 }
@@ -7,7 +7,7 @@ use alloc::vec::Vec;
 use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 
 use crate::{
-    crypto::CipherSuite,
+    crypto::{CipherSuite, HpkePublicKey},
     extension::{ExtensionList, ExtensionType},
     identity::{CredentialType, SigningIdentity},
     protocol_version::ProtocolVersion,
@@ -89,6 +89,23 @@ impl Default for Capabilities {
     }
 }
 
+/// How the leaf node of a [`Member`] was last set in the group tree.
+#[cfg_attr(
+    all(feature = "ffi", not(test)),
+    safer_ffi_gen::ffi_type(clone, opaque)
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LeafNodeSourceType {
+    /// The leaf node comes from the key package used to add the member and
+    /// has not been changed since.
+    KeyPackage,
+    /// The leaf node was set by an update proposal sent by the member.
+    Update,
+    /// The leaf node was set by a commit with a path sent by the member.
+    Commit,
+}
+
 /// A member of a MLS group.
 #[cfg_attr(
     all(feature = "ffi", not(test)),
@@ -108,6 +125,10 @@ pub struct Member {
     pub capabilities: Capabilities,
     /// Current leaf node extensions in use by this member.
     pub extensions: ExtensionList,
+    /// How the current leaf node of this member was set.
+    pub source: LeafNodeSourceType,
+    /// Current HPKE encryption public key of this member.
+    pub hpke_public_key: HpkePublicKey,
 }
 
 #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen)]
@@ -123,6 +144,25 @@ impl Member {
             signing_identity,
             capabilities,
             extensions,
+            source: LeafNodeSourceType::KeyPackage,
+            hpke_public_key: HpkePublicKey::from(vec![]),
+        }
+    }
+
+    /// Set how the current leaf node of this member was set. A member created
+    /// with [`Member::new`] defaults to [`LeafNodeSourceType::KeyPackage`].
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn with_source(self, source: LeafNodeSourceType) -> Self {
+        Self { source, ..self }
+    }
+
+    /// Set the current HPKE encryption public key of this member. A member
+    /// created with [`Member::new`] defaults to an empty key.
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn with_hpke_public_key(self, hpke_public_key: HpkePublicKey) -> Self {
+        Self {
+            hpke_public_key,
+            ..self
         }
     }
 
@@ -152,4 +192,17 @@ impl Member {
     pub fn extensions(&self) -> &ExtensionList {
         &self.extensions
     }
+
+    /// How the current leaf node of this member was set, i.e. whether the
+    /// member is still using the leaf node from its key package or has since
+    /// updated it with an update proposal or a commit.
+    pub fn source(&self) -> LeafNodeSourceType {
+        self.source
+    }
+
+    /// Current HPKE encryption public key of this member, taken from its leaf
+    /// node. The key changes whenever the member updates its leaf node.
+    pub fn hpke_public_key(&self) -> &HpkePublicKey {
+        &self.hpke_public_key
+    }
 }
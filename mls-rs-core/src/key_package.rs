@@ -81,4 +81,13 @@ pub trait KeyPackageStorage: Send + Sync {
     /// `None` should be returned in the event that no key packages are found
     /// that match `id`.
     async fn get(&self, id: &[u8]) -> Result<Option<KeyPackageData>, Self::Error>;
+
+    /// Ids of all stored [`KeyPackageData`].
+    ///
+    /// This is used to find and delete the secrets of expired key packages.
+    /// Returns `None` if this storage can not list its entries, which is the
+    /// default.
+    async fn ids(&self) -> Result<Option<Vec<Vec<u8>>>, Self::Error> {
+        Ok(None)
+    }
 }
@@ -65,3 +65,22 @@ impl MlsTime {
         }
     }
 }
+
+/// Source of the current time used by time dependent checks such as the
+/// validation of key package lifetimes.
+pub trait Clock: Send + Sync {
+    /// Current time.
+    fn now(&self) -> MlsTime;
+}
+
+/// [`Clock`] reading the system time with [`MlsTime::now`].
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> MlsTime {
+        MlsTime::now()
+    }
+}
@@ -210,6 +210,10 @@ impl GroupStateStorage for SqLiteGroupStateStorage {
     async fn epoch(&self, group_id: &[u8], epoch_id: u64) -> Result<Option<Vec<u8>>, Self::Error> {
         self.get_epoch_data(group_id, epoch_id)
     }
+
+    async fn delete_group(&mut self, group_id: &[u8]) -> Result<bool, Self::Error> {
+        SqLiteGroupStateStorage::delete_group(self, group_id).map(|_| true)
+    }
 }
 
 #[cfg(test)]
@@ -94,6 +94,26 @@ impl SqLiteKeyPackageStorage {
             .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))
     }
 
+    /// Ids of all key packages held in storage.
+    pub fn ids(&self) -> Result<Vec<Vec<u8>>, SqLiteDataStorageError> {
+        let connection = self.connection.lock().unwrap();
+
+        let mut statement = connection
+            .prepare("SELECT id FROM key_package")
+            .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?;
+
+        let res = statement
+            .query_map([], |row| row.get(0))
+            .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?
+            .try_fold(Vec::new(), |mut ids, id| {
+                ids.push(id.map_err(|e| SqLiteDataStorageError::DataConversionError(e.into()))?);
+                Ok::<_, SqLiteDataStorageError>(ids)
+            })
+            .map_err(|e| SqLiteDataStorageError::SqlEngineError(e.into()))?;
+
+        Ok(res)
+    }
+
     /// Total number of key packages held in storage.
     pub fn count(&self) -> Result<usize, SqLiteDataStorageError> {
         let connection = self.connection.lock().unwrap();
@@ -137,6 +157,10 @@ impl KeyPackageStorage for SqLiteKeyPackageStorage {
     async fn delete(&mut self, id: &[u8]) -> Result<(), Self::Error> {
         (*self).delete(id)
     }
+
+    async fn ids(&self) -> Result<Option<Vec<Vec<u8>>>, Self::Error> {
+        (*self).ids().map(Some)
+    }
 }
 
 #[cfg(test)]
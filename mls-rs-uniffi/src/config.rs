@@ -54,6 +54,10 @@ impl mls_rs_core::group::GroupStateStorage for ClientGroupStorage {
     async fn max_epoch_id(&self, group_id: &[u8]) -> Result<Option<u64>, Self::Error> {
         self.0.max_epoch_id(group_id.to_vec()).await
     }
+
+    async fn delete_group(&mut self, group_id: &[u8]) -> Result<bool, Self::Error> {
+        self.0.delete_group(group_id.to_vec()).await
+    }
 }
 
 pub type UniFFIConfig = client_builder::WithIdentityProvider<
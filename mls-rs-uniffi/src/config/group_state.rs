@@ -52,6 +52,8 @@ pub trait GroupStateStorage: Send + Sync + Debug {
     ) -> Result<(), Error>;
 
     async fn max_epoch_id(&self, group_id: Vec<u8>) -> Result<Option<u64>, Error>;
+
+    async fn delete_group(&self, group_id: Vec<u8>) -> Result<bool, Error>;
 }
 
 /// Adapt a mls-rs `GroupStateStorage` implementation.
@@ -126,4 +128,12 @@ where
             .await
             .map_err(|err| err.into_any_error().into())
     }
+
+    async fn delete_group(&self, group_id: Vec<u8>) -> Result<bool, Error> {
+        self.inner()
+            .await
+            .delete_group(&group_id)
+            .await
+            .map_err(|err| err.into_any_error().into())
+    }
 }
@@ -866,6 +866,11 @@ mod tests {
                     .and_then(|GroupStateData { epoch_data, .. }| epoch_data.last())
                     .map(|last| last.id))
             }
+
+            fn delete_group(&self, group_id: Vec<u8>) -> Result<bool, Error> {
+                self.lock().remove(&group_id);
+                Ok(true)
+            }
         }
 
         let alice_config = ClientConfig {
@@ -60,6 +60,10 @@ class PythonGroupStateStorage(GroupStateStorage):
 
         return last.id
 
+    def delete_group(self, group_id: bytes):
+        self.groups.pop(group_id.hex(), None)
+        return True
+
 
 group_state_storage = PythonGroupStateStorage()
 client_config = ClientConfig(group_state_storage=group_state_storage,
@@ -5,7 +5,11 @@
 use crate::cipher_suite::CipherSuite;
 use crate::client_builder::{recreate_config, BaseConfig, ClientBuilder, MakeConfig};
 use crate::client_config::ClientConfig;
+#[cfg(feature = "last_resort_key_package_ext")]
+use crate::extension::LastResortKeyPackageExt;
+use crate::extension::RequiredCapabilitiesExt;
 use crate::group::framing::MlsMessage;
+use crate::retry::RetryLayer;
 
 use crate::group::{cipher_suite_provider, validate_group_info_joiner, GroupInfo};
 use crate::group::{
@@ -18,21 +22,28 @@ use crate::group::{
     proposal::{AddProposal, Proposal},
 };
 use crate::identity::SigningIdentity;
+#[cfg(feature = "last_resort_key_package_ext")]
+use crate::key_package::KeyPackage;
 use crate::key_package::{KeyPackageGeneration, KeyPackageGenerator};
 use crate::protocol_version::ProtocolVersion;
+#[cfg(feature = "psk")]
+use crate::psk::ExternalPskId;
+use crate::time::MlsTime;
 use crate::tree_kem::node::NodeIndex;
 use alloc::vec::Vec;
 use mls_rs_codec::MlsDecode;
-use mls_rs_core::crypto::{CryptoProvider, SignatureSecretKey};
+use mls_rs_core::crypto::{CipherSuiteProvider, CryptoProvider, SignatureSecretKey};
 use mls_rs_core::error::{AnyError, IntoAnyError};
+#[cfg(feature = "last_resort_key_package_ext")]
+use mls_rs_core::extension::MlsExtension;
 use mls_rs_core::extension::{ExtensionError, ExtensionList, ExtensionType};
+use mls_rs_core::group::Capabilities;
 use mls_rs_core::group::{GroupStateStorage, ProposalType};
 use mls_rs_core::identity::{CredentialType, IdentityProvider, MemberValidationContext};
 use mls_rs_core::key_package::KeyPackageStorage;
 
 use crate::group::external_commit::ExternalCommitBuilder;
 
-#[cfg(feature = "by_ref_proposal")]
 use alloc::boxed::Box;
 
 #[derive(Debug)]
@@ -62,6 +73,11 @@ pub enum MlsError {
     CommitMissingPath,
     #[cfg_attr(feature = "std", error("plaintext message for incorrect epoch"))]
     InvalidEpoch,
+    #[cfg_attr(
+        feature = "std",
+        error("commit for epoch {commit} received in epoch {current}")
+    )]
+    CommitEpochMismatch { current: u64, commit: u64 },
     #[cfg_attr(feature = "std", error("invalid signature found"))]
     InvalidSignature,
     #[cfg_attr(feature = "std", error("invalid confirmation tag"))]
@@ -143,6 +159,12 @@ pub enum MlsError {
     ReInitExtensionsMismatch,
     #[cfg_attr(feature = "std", error("signer not found for given identity"))]
     SignerNotFound,
+    #[cfg_attr(feature = "std", error("cipher suite not set"))]
+    CipherSuiteNotSet,
+    #[cfg_attr(feature = "std", error("signing identity not set"))]
+    SigningIdentityNotSet,
+    #[cfg_attr(feature = "std", error("signing key not set"))]
+    SigningKeyNotSet,
     #[cfg_attr(feature = "std", error("commit already pending"))]
     ExistingPendingCommit,
     #[cfg_attr(feature = "std", error("pending commit not found"))]
@@ -162,8 +184,20 @@ pub enum MlsError {
     UnexpectedPskId,
     #[cfg_attr(feature = "std", error("invalid sender for content type"))]
     InvalidSender,
+    #[cfg_attr(
+        feature = "std",
+        error("sender at leaf {0} is not a member of the group")
+    )]
+    SenderNotInGroup(u32),
+    #[cfg_attr(
+        feature = "std",
+        error("welcome was signed by an inviter that is not allowed")
+    )]
+    UnauthorizedInviter,
     #[cfg_attr(feature = "std", error("GroupID mismatch"))]
     GroupIdMismatch,
+    #[cfg_attr(feature = "std", error("invalid group id of length {0}"))]
+    InvalidGroupId(usize),
     #[cfg_attr(feature = "std", error("storage retention can not be zero"))]
     NonZeroRetentionRequired,
     #[cfg_attr(feature = "std", error("Too many PSK IDs to compute PSK secret"))]
@@ -199,8 +233,32 @@ pub enum MlsError {
     RequiredProposalNotFound(ProposalType),
     #[cfg_attr(feature = "std", error("required credential not found"))]
     RequiredCredentialNotFound(CredentialType),
+    #[cfg_attr(
+        feature = "std",
+        error("member {leaf_index} does not support required capabilities: {missing}")
+    )]
+    MemberMissingRequiredCapability {
+        leaf_index: u32,
+        missing: Box<MlsError>,
+    },
+    #[cfg_attr(
+        feature = "std",
+        error("key package cipher suite {found:?} does not match group cipher suite {expected:?}")
+    )]
+    KeyPackageCipherSuiteMismatch {
+        expected: CipherSuite,
+        found: CipherSuite,
+    },
     #[cfg_attr(feature = "std", error("capabilities must describe extensions used"))]
     ExtensionNotInCapabilities(ExtensionType),
+    #[cfg_attr(
+        feature = "std",
+        error("leaf node extension {extension_type:?} is invalid: {error}")
+    )]
+    InvalidLeafNodeExtension {
+        extension_type: ExtensionType,
+        error: AnyError,
+    },
     #[cfg_attr(feature = "std", error("expected non-blank node"))]
     ExpectedNode,
     #[cfg_attr(feature = "std", error("node index is out of bounds {0}"))]
@@ -245,12 +303,19 @@ pub enum MlsError {
     PubKeyMismatch,
     #[cfg_attr(feature = "std", error("tree hash mismatch"))]
     TreeHashMismatch,
+    #[cfg_attr(
+        feature = "std",
+        error("imported group state is not internally consistent")
+    )]
+    InconsistentGroupState,
     #[cfg_attr(feature = "std", error("bad update: no suitable secret key"))]
     UpdateErrorNoSecretKey,
     #[cfg_attr(feature = "std", error("invalid lca, not found on direct path"))]
     LcaNotFoundInDirectPath,
     #[cfg_attr(feature = "std", error("update path parent hash mismatch"))]
     ParentHashMismatch,
+    #[cfg_attr(feature = "std", error("invalid parent hash at node {node}"))]
+    InvalidParentHash { node: u32 },
     #[cfg_attr(feature = "std", error("unexpected pattern of unmerged leaves"))]
     UnmergedLeavesMismatch,
     #[cfg_attr(feature = "std", error("empty tree"))]
@@ -300,6 +365,8 @@ pub enum MlsError {
     ExternalCommitWithMoreThanOneRemove,
     #[cfg_attr(feature = "std", error("Duplicate PSK IDs"))]
     DuplicatePskIds,
+    #[cfg_attr(feature = "std", error("commit contains {0} PSKs, more than allowed"))]
+    TooManyPsks(usize),
     #[cfg_attr(
         feature = "std",
         error("Invalid proposal type {0:?} in external commit")
@@ -316,10 +383,38 @@ pub enum MlsError {
     OtherProposalWithReInit,
     #[cfg_attr(feature = "std", error("Unsupported group extension {0:?}"))]
     UnsupportedGroupExtension(ExtensionType),
+    #[cfg_attr(feature = "std", error("Unknown group info extension {0:?}"))]
+    UnknownGroupInfoExtension(ExtensionType),
+    #[cfg_attr(
+        feature = "std",
+        error("update of leaf {0} removes capabilities it advertised")
+    )]
+    CapabilityDowngrade(u32),
+    #[cfg_attr(
+        feature = "std",
+        error("Group extension {extension_type:?} can not be removed as member {leaf_index} relies on it")
+    )]
+    RemovedGroupExtensionInUse {
+        extension_type: ExtensionType,
+        leaf_index: u32,
+    },
+    #[cfg_attr(
+        feature = "std",
+        error("Group extension {extension_type:?} is not supported by member {leaf_index}")
+    )]
+    GroupExtensionNotSupportedByMember {
+        extension_type: ExtensionType,
+        leaf_index: u32,
+    },
     #[cfg_attr(feature = "std", error("Unsupported custom proposal type {0:?}"))]
     UnsupportedCustomProposal(ProposalType),
     #[cfg_attr(feature = "std", error("by-ref proposal not found"))]
     ProposalNotFound,
+    #[cfg_attr(
+        feature = "std",
+        error("no by-value proposal at index {0} in the commit")
+    )]
+    ProposalIndexOutOfRange(usize),
     #[cfg_attr(
         feature = "std",
         error("Removing non-existing member (or removing a member twice)")
@@ -340,6 +435,46 @@ pub enum MlsError {
     InvalidWelcomeMessage,
     #[cfg_attr(feature = "std", error("Exporter deleted"))]
     ExporterDeleted,
+    #[cfg_attr(
+        feature = "std",
+        error("Message exceeds the maximum size of {0} bytes")
+    )]
+    MessageTooLarge(usize),
+    #[cfg_attr(
+        feature = "std",
+        error("{0} unexpected bytes after the end of the message")
+    )]
+    UnexpectedTrailingBytes(usize),
+    #[cfg_attr(
+        feature = "std",
+        error("Embedded ratchet tree of {0} bytes exceeds the configured maximum size")
+    )]
+    RatchetTreeTooLarge(usize),
+    #[cfg_attr(
+        feature = "std",
+        error("Application message of {0} bytes exceeds the configured maximum size")
+    )]
+    ApplicationMessageTooLarge(usize),
+    #[cfg_attr(feature = "std", error("invalid wrapping key length {0}"))]
+    InvalidWrappingKeyLength(usize),
+    #[cfg_attr(feature = "std", error("Unsupported snapshot format {0}"))]
+    UnsupportedSnapshotFormat(u8),
+    #[cfg_attr(feature = "std", error("Unsupported full group state version {0}"))]
+    UnsupportedFullStateVersion(u16),
+    #[cfg_attr(feature = "std", error("Proposal type {0:?} is not allowed"))]
+    DisallowedProposalType(ProposalType),
+    #[cfg_attr(feature = "std", error(transparent))]
+    IoError(AnyError),
+    #[cfg_attr(
+        feature = "std",
+        error("Group state storage does not support deleting groups")
+    )]
+    GroupDeletionUnsupported,
+    #[cfg_attr(
+        feature = "std",
+        error("Key package storage does not support listing key packages")
+    )]
+    KeyPackageListingUnsupported,
 }
 
 impl IntoAnyError for MlsError {
@@ -408,7 +543,11 @@ where
     }
 
     #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
-    pub fn to_builder(&self) -> ClientBuilder<MakeConfig<C>> {
+    pub fn to_builder(&self) -> ClientBuilder<MakeConfig<C>>
+    where
+        C::PskStore: RetryLayer,
+        C::IdentityProvider: RetryLayer,
+    {
         ClientBuilder::from_config(recreate_config(
             self.config.clone(),
             self.signer.clone(),
@@ -417,6 +556,16 @@ where
         ))
     }
 
+    /// Capabilities advertised by leaf nodes generated by this client.
+    ///
+    /// These are derived from the configured crypto provider, identity
+    /// provider and registered extension and proposal types. The cipher
+    /// suites are those the crypto provider reports as supported, so cipher
+    /// suites it can not perform are never advertised.
+    pub fn default_capabilities(&self) -> Capabilities {
+        self.config.capabilities()
+    }
+
     /// Creates a new key package message that can be used to to add this
     /// client to a [Group](crate::group::Group). Each call to this function
     /// will produce a unique value that is signed by `signing_identity`.
@@ -442,13 +591,164 @@ where
             .key_package_message())
     }
 
+    /// Creates `count` new key package messages like
+    /// [`Client::generate_key_package_message`] without extensions, for
+    /// example to publish a pool of key packages to a directory.
+    ///
+    /// Each key package has distinct secret keys, stored in the
+    /// [KeyPackageStorage](crate::KeyPackageStorage), and a distinct
+    /// reference.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn generate_key_package_messages(
+        &self,
+        count: usize,
+    ) -> Result<Vec<MlsMessage>, MlsError> {
+        let (_, cipher_suite) = self.signing_identity()?;
+
+        let key_packages = self
+            .generate_key_packages_for(
+                self.version,
+                cipher_suite,
+                Default::default(),
+                Default::default(),
+                count,
+            )
+            .await?;
+
+        Ok(key_packages
+            .iter()
+            .map(KeyPackageGeneration::key_package_message)
+            .collect())
+    }
+
+    /// Delete the secrets of key packages whose lifetime ended before `now`
+    /// from the [KeyPackageStorage](crate::KeyPackageStorage), returning the
+    /// number of deleted key packages.
+    ///
+    /// Secrets of last resort key packages are never deleted. Returns
+    /// [`MlsError::KeyPackageListingUnsupported`] if the storage does not
+    /// implement [`KeyPackageStorage::ids`].
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn prune_key_package_secrets(&self, now: MlsTime) -> Result<usize, MlsError> {
+        let mut key_package_repo = self.config.key_package_repo();
+
+        let ids = key_package_repo
+            .ids()
+            .await
+            .map_err(|e| MlsError::KeyPackageRepoError(e.into_any_error()))?
+            .ok_or(MlsError::KeyPackageListingUnsupported)?;
+
+        let mut pruned = 0;
+
+        for id in ids {
+            let data = key_package_repo
+                .get(&id)
+                .await
+                .map_err(|e| MlsError::KeyPackageRepoError(e.into_any_error()))?;
+
+            let Some(data) = data else {
+                continue;
+            };
+
+            if data.expiration >= now.seconds_since_epoch() {
+                continue;
+            }
+
+            #[cfg(feature = "last_resort_key_package_ext")]
+            if KeyPackage::mls_decode(&mut &*data.key_package_bytes)?
+                .extensions
+                .has_extension(LastResortKeyPackageExt::extension_type())
+            {
+                continue;
+            }
+
+            key_package_repo
+                .delete(&id)
+                .await
+                .map_err(|e| MlsError::KeyPackageRepoError(e.into_any_error()))?;
+
+            pruned += 1;
+        }
+
+        Ok(pruned)
+    }
+
+    /// Creates a new key package message for joining the group resulting from
+    /// a [`ReInitProposal`](crate::group::proposal::ReInitProposal) that uses
+    /// `new_version` and `new_cipher_suite`, which may differ from the version
+    /// and cipher suite this client was configured with.
+    ///
+    /// The key package is signed by this client's signing identity, which must
+    /// therefore use a signature scheme supported by `new_cipher_suite`. Its
+    /// secret keys are stored in the
+    /// [KeyPackageStorage](crate::KeyPackageStorage) like those generated by
+    /// [`Client::generate_key_package_message`].
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn generate_reinit_key_package(
+        &self,
+        new_version: ProtocolVersion,
+        new_cipher_suite: CipherSuite,
+    ) -> Result<MlsMessage, MlsError> {
+        Ok(self
+            .generate_key_package_for(
+                new_version,
+                new_cipher_suite,
+                Default::default(),
+                Default::default(),
+            )
+            .await?
+            .key_package_message())
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn generate_key_package(
         &self,
         key_package_extensions: ExtensionList,
         leaf_node_extensions: ExtensionList,
     ) -> Result<KeyPackageGeneration, MlsError> {
-        let (signing_identity, cipher_suite) = self.signing_identity()?;
+        let (_, cipher_suite) = self.signing_identity()?;
+
+        self.generate_key_package_for(
+            self.version,
+            cipher_suite,
+            key_package_extensions,
+            leaf_node_extensions,
+        )
+        .await
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn generate_key_package_for(
+        &self,
+        protocol_version: ProtocolVersion,
+        cipher_suite: CipherSuite,
+        key_package_extensions: ExtensionList,
+        leaf_node_extensions: ExtensionList,
+    ) -> Result<KeyPackageGeneration, MlsError> {
+        let mut key_packages = self
+            .generate_key_packages_for(
+                protocol_version,
+                cipher_suite,
+                key_package_extensions,
+                leaf_node_extensions,
+                1,
+            )
+            .await?;
+
+        // Exactly one key package was generated.
+        Ok(key_packages.remove(0))
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn generate_key_packages_for(
+        &self,
+        protocol_version: ProtocolVersion,
+        cipher_suite: CipherSuite,
+        key_package_extensions: ExtensionList,
+        leaf_node_extensions: ExtensionList,
+        count: usize,
+    ) -> Result<Vec<KeyPackageGeneration>, MlsError> {
+        let (signing_identity, _) = self.signing_identity()?;
 
         let cipher_suite_provider = self
             .config
@@ -457,30 +757,38 @@ where
             .ok_or(MlsError::UnsupportedCipherSuite(cipher_suite))?;
 
         let key_package_generator = KeyPackageGenerator {
-            protocol_version: self.version,
+            protocol_version,
             cipher_suite_provider: &cipher_suite_provider,
             signing_key: self.signer()?,
             signing_identity,
         };
 
-        let key_pkg_gen = key_package_generator
-            .generate(
-                self.config.lifetime(),
-                self.config.capabilities(),
-                key_package_extensions,
-                leaf_node_extensions,
-            )
-            .await?;
-
-        let (id, key_package_data) = key_pkg_gen.to_storage()?;
+        let lifetime = self.config.lifetime();
+        let capabilities = self.default_capabilities();
+        let mut key_package_repo = self.config.key_package_repo();
+        let mut key_packages = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let key_pkg_gen = key_package_generator
+                .generate(
+                    lifetime.clone(),
+                    capabilities.clone(),
+                    key_package_extensions.clone(),
+                    leaf_node_extensions.clone(),
+                )
+                .await?;
+
+            let (id, key_package_data) = key_pkg_gen.to_storage()?;
+
+            key_package_repo
+                .insert(id, key_package_data)
+                .await
+                .map_err(|e| MlsError::KeyPackageRepoError(e.into_any_error()))?;
 
-        self.config
-            .key_package_repo()
-            .insert(id, key_package_data)
-            .await
-            .map_err(|e| MlsError::KeyPackageRepoError(e.into_any_error()))?;
+            key_packages.push(key_pkg_gen);
+        }
 
-        Ok(key_pkg_gen)
+        Ok(key_packages)
     }
 
     /// Create a group with a specific group_id.
@@ -494,6 +802,9 @@ where
     /// It is recommended to use [create_group](Client::create_group)
     /// instead of this function because it guarantees that group_id values
     /// are globally unique.
+    ///
+    /// Returns [`MlsError::InvalidGroupId`] if `group_id` is empty or longer
+    /// than [`ClientBuilder::max_group_id_length`](crate::client_builder::ClientBuilder::max_group_id_length).
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn create_group_with_id(
         &self,
@@ -501,6 +812,65 @@ where
         group_context_extensions: ExtensionList,
         leaf_node_extensions: ExtensionList,
     ) -> Result<Group<C>, MlsError> {
+        self.validate_group_id(&group_id)?;
+
+        let (signing_identity, cipher_suite) = self.signing_identity()?;
+
+        Group::new(
+            self.config.clone(),
+            Some(group_id),
+            cipher_suite,
+            self.version,
+            signing_identity.clone(),
+            group_context_extensions,
+            leaf_node_extensions,
+            self.signer()?.clone(),
+            #[cfg(feature = "psk")]
+            Vec::new(),
+        )
+        .await
+    }
+
+    /// Create a group with a specific group_id whose group context requires,
+    /// from epoch 0, all members to support the extensions, proposals and
+    /// credentials listed in `required`.
+    ///
+    /// This function behaves the same way as
+    /// [create_group_with_id](Client::create_group_with_id) with `required`
+    /// as the only group context extension. Key packages that do not satisfy
+    /// `required` are rejected when adding members.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn create_group_with_required_capabilities(
+        &self,
+        group_id: Vec<u8>,
+        required: RequiredCapabilitiesExt,
+    ) -> Result<Group<C>, MlsError> {
+        let mut group_context_extensions = ExtensionList::new();
+        group_context_extensions.set_from(required)?;
+
+        self.create_group_with_id(group_id, group_context_extensions, Default::default())
+            .await
+    }
+
+    /// Create a group with a specific group_id whose initial epoch is bound
+    /// to the external PSKs identified by `psks`.
+    ///
+    /// The PSKs are resolved using the [PreSharedKeyStorage](crate::PreSharedKeyStorage)
+    /// of this client and injected into the key schedule of epoch 0, so that
+    /// the group secrets cannot be derived without knowledge of the PSKs.
+    /// If `psks` is empty, this function behaves the same way as
+    /// [create_group_with_id](Client::create_group_with_id).
+    #[cfg(feature = "psk")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn create_group_with_psk(
+        &self,
+        group_id: Vec<u8>,
+        group_context_extensions: ExtensionList,
+        leaf_node_extensions: ExtensionList,
+        psks: Vec<ExternalPskId>,
+    ) -> Result<Group<C>, MlsError> {
+        self.validate_group_id(&group_id)?;
+
         let (signing_identity, cipher_suite) = self.signing_identity()?;
 
         Group::new(
@@ -512,6 +882,7 @@ where
             group_context_extensions,
             leaf_node_extensions,
             self.signer()?.clone(),
+            psks,
         )
         .await
     }
@@ -538,10 +909,86 @@ where
             group_context_extensions,
             leaf_node_extensions,
             self.signer()?.clone(),
+            #[cfg(feature = "psk")]
+            Vec::new(),
         )
         .await
     }
 
+    /// Create a MLS group with a random 32 byte group_id generated by the
+    /// [CipherSuiteProvider](crate::CipherSuiteProvider) of the client.
+    ///
+    /// This function behaves the same way as
+    /// [create_group](Client::create_group) except that the length of the
+    /// group_id does not depend on the cipher suite.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn create_group_with_random_id(
+        &self,
+        group_context_extensions: ExtensionList,
+        leaf_node_extensions: ExtensionList,
+    ) -> Result<Group<C>, MlsError> {
+        let (_, cipher_suite) = self.signing_identity()?;
+
+        let group_id = self
+            .config
+            .crypto_provider()
+            .cipher_suite_provider(cipher_suite)
+            .ok_or(MlsError::UnsupportedCipherSuite(cipher_suite))?
+            .random_bytes_vec(32)
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
+
+        self.create_group_with_id(group_id, group_context_extensions, leaf_node_extensions)
+            .await
+    }
+
+    /// Check whether this client could create a group using `cipher_suite`.
+    ///
+    /// Returns `false` if the crypto provider does not support `cipher_suite`,
+    /// if no signing identity is configured or if the configured signing key
+    /// does not produce signatures that verify under `cipher_suite` with the
+    /// public key of the configured signing identity.
+    ///
+    /// Note that [create_group](Client::create_group) always uses the cipher
+    /// suite the signing identity was configured with.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn can_create_group(&self, cipher_suite: CipherSuite) -> bool {
+        let Some(cs) = self
+            .config
+            .crypto_provider()
+            .cipher_suite_provider(cipher_suite)
+        else {
+            return false;
+        };
+
+        let (Ok((signing_identity, _)), Ok(signer)) = (self.signing_identity(), self.signer())
+        else {
+            return false;
+        };
+
+        let data = b"can_create_group";
+
+        let Ok(signature) = cs.sign(signer, data).await else {
+            return false;
+        };
+
+        cs.verify(&signing_identity.signature_key, &signature, data)
+            .await
+            .is_ok()
+    }
+
+    fn validate_group_id(&self, group_id: &[u8]) -> Result<(), MlsError> {
+        let too_long = matches!(
+            self.config.max_group_id_length(),
+            Some(max) if group_id.len() > max
+        );
+
+        if group_id.is_empty() || too_long {
+            return Err(MlsError::InvalidGroupId(group_id.len()));
+        }
+
+        Ok(())
+    }
+
     /// Join a MLS group via a welcome message created by a
     /// [Commit](crate::group::CommitOutput).
     ///
@@ -576,6 +1023,56 @@ where
         Group::decrypt_group_info(welcome_message, &self.config).await
     }
 
+    /// Group id of the group that `message` invites this client to, or `None`
+    /// if `message` is not a welcome message.
+    ///
+    /// The group id is part of the encrypted group info, so reading it requires
+    /// the key package this client used to join, as with
+    /// [`Client::examine_welcome_message`]. This allows routing a welcome to the
+    /// right application context before joining.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn welcome_group_id(
+        &self,
+        message: &MlsMessage,
+    ) -> Result<Option<Vec<u8>>, MlsError> {
+        if !matches!(message.payload, MlsMessagePayload::Welcome(_)) {
+            return Ok(None);
+        }
+
+        let group_info = self.examine_welcome_message(message).await?;
+
+        Ok(Some(group_info.group_context.group_id))
+    }
+
+    /// Check whether `welcome_message` contains group secrets encrypted to a key
+    /// package held by this client.
+    ///
+    /// Only the key package references of the welcome are looked up in the
+    /// [`KeyPackageStorage`]. No decryption is attempted and no key package is
+    /// consumed, so this can be used to cheaply decide whether to call
+    /// [`Client::join_group`].
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn welcome_targets_me(&self, welcome_message: &MlsMessage) -> Result<bool, MlsError> {
+        let MlsMessagePayload::Welcome(welcome) = &welcome_message.payload else {
+            return Err(MlsError::UnexpectedMessageType);
+        };
+
+        let key_package_repo = self.config.key_package_repo();
+
+        for secrets in &welcome.secrets {
+            let key_package = key_package_repo
+                .get(&secrets.new_member)
+                .await
+                .map_err(|e| MlsError::KeyPackageRepoError(e.into_any_error()))?;
+
+            if key_package.is_some() {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
     /// Validate GroupInfo message. This does NOT validate the ratchet tree in case
     /// it is provided in the extension. It validates the signature, identity of the
     /// signer, identities of external senders and cipher suite.
@@ -664,6 +1161,31 @@ where
         ))
     }
 
+    /// Import a group exported with [`Group::export_full_state`] on another
+    /// device of the same member, including all retained prior epochs.
+    ///
+    /// The tree hash and transcript hash of the imported state are checked for
+    /// consistency before the group is written to the
+    /// [GroupStateStorage](crate::GroupStateStorage) that this client was
+    /// configured to use.
+    #[cfg(feature = "prior_epoch")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn import_full_state(&self, data: &[u8]) -> Result<Group<C>, MlsError> {
+        Group::from_full_state(self.config.clone(), data).await
+    }
+
+    /// Load a group from a snapshot exported with [`Group::export_snapshot`].
+    ///
+    /// The [`SnapshotFormat`](crate::group::SnapshotFormat) of `data` is detected
+    /// automatically. The group is not written to the
+    /// [GroupStateStorage](crate::GroupStateStorage) that this client was
+    /// configured to use until [`Group::write_to_storage`] is called.
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn import_snapshot(&self, data: &[u8]) -> Result<Group<C>, MlsError> {
+        Group::from_exported_snapshot(self.config.clone(), data).await
+    }
+
     /// Load an existing group state into this client using the
     /// [GroupStateStorage](crate::GroupStateStorage) that
     /// this client was configured to use.
@@ -745,6 +1267,7 @@ where
             protocol_version,
             group_info,
             tree_data,
+            self.config.max_ratchet_tree_size(),
             &self.config.identity_provider(),
             &cipher_suite_provider,
         )
@@ -796,6 +1319,24 @@ where
             .ok_or(MlsError::SignerNotFound)
     }
 
+    /// Extension types registered with this client, in addition to the
+    /// default extension types required by MLS.
+    ///
+    /// These are advertised in the capabilities of generated key packages.
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn registered_extension_types(&self) -> Vec<ExtensionType> {
+        self.config.supported_extensions()
+    }
+
+    /// Custom proposal types registered with this client, in addition to
+    /// the default proposal types required by MLS.
+    ///
+    /// These are advertised in the capabilities of generated key packages.
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn registered_proposal_types(&self) -> Vec<ProposalType> {
+        self.config.supported_custom_proposals()
+    }
+
     /// The [KeyPackageStorage] that this client was configured to use.
     #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
     pub fn key_package_store(&self) -> <C as ClientConfig>::KeyPackageRepository {
@@ -889,9 +1430,11 @@ mod tests {
     use crate::{
         crypto::test_utils::TestCryptoProvider,
         identity::test_utils::{get_test_basic_credential, get_test_signing_identity},
+        storage_provider::in_memory::InMemoryKeyPackageStorage,
         tree_kem::leaf_node::LeafNodeSource,
     };
     use assert_matches::assert_matches;
+    use mls_rs_core::key_package::KeyPackageData;
 
     #[cfg(feature = "by_ref_proposal")]
     use crate::group::message_processor::ProposalMessageDescription;
@@ -906,6 +1449,32 @@ mod tests {
     use crate::psk::{ExternalPskId, PreSharedKey};
     use alloc::vec;
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn can_create_group_checks_suite_and_signing_key() {
+        let (client, _) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+
+        let supported = client.can_create_group(TEST_CIPHER_SUITE).await;
+        assert!(supported);
+        let unsupported = client.can_create_group(CipherSuite::from(0xFF00)).await;
+        assert!(!unsupported);
+
+        let (identity, _) = get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+        let (_, other_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let mismatched = TestClientBuilder::new_for_test()
+            .signing_identity(identity, other_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let mismatched_key = mismatched.can_create_group(TEST_CIPHER_SUITE).await;
+        assert!(!mismatched_key);
+
+        let no_identity = TestClientBuilder::new_for_test().build();
+
+        let without_identity = no_identity.can_create_group(TEST_CIPHER_SUITE).await;
+        assert!(!without_identity);
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_keygen() {
         // This is meant to test the inputs to the internal key package generator
@@ -948,12 +1517,209 @@ mod tests {
         }
     }
 
-    #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn new_member_add_proposal_adds_to_group() {
-        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+    async fn group_requires_capabilities_from_creation() {
+        let extension_type = ExtensionType::new(65002);
 
-        let (bob_identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+        let (alice_identity, alice_secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let alice = TestClientBuilder::new_for_test()
+            .extension_type(extension_type)
+            .signing_identity(alice_identity, alice_secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let required = RequiredCapabilitiesExt {
+            extensions: vec![extension_type],
+            ..Default::default()
+        };
+
+        let mut group = alice
+            .create_group_with_required_capabilities(b"group".to_vec(), required.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(group.context().extensions.get_as().unwrap(), Some(required));
+
+        let (_, bob_key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let res = group
+            .commit_builder()
+            .add_member(bob_key_package)
+            .unwrap()
+            .build()
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::RequiredExtensionNotFound(t)) if t == extension_type);
+
+        let (_, carol_key_package) = test_client_with_key_pkg_custom(
+            TEST_PROTOCOL_VERSION,
+            TEST_CIPHER_SUITE,
+            "carol",
+            Default::default(),
+            Default::default(),
+            |c| c.0.settings.extension_types.push(extension_type),
+        )
+        .await;
+
+        group
+            .commit_builder()
+            .add_member(carol_key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn generated_key_packages_are_distinct_and_stored() {
+        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"foo").await;
+
+        let client = TestClientBuilder::new_for_test()
+            .signing_identity(identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let messages = client.generate_key_package_messages(5).await.unwrap();
+        assert_eq!(messages.len(), 5);
+
+        let cs = TestCryptoProvider::new()
+            .cipher_suite_provider(TEST_CIPHER_SUITE)
+            .unwrap();
+
+        let mut references = Vec::new();
+        let mut init_keys = Vec::new();
+
+        for message in messages {
+            let key_package = message.into_key_package().unwrap();
+            let reference = key_package.to_reference(&cs).await.unwrap();
+
+            assert!(client.key_package_store().get(&reference).is_some());
+            assert!(!references.contains(&reference));
+            assert!(!init_keys.contains(&key_package.hpke_init_key));
+
+            references.push(reference);
+            init_keys.push(key_package.hpke_init_key);
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn expired_key_package_secrets_are_pruned() {
+        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"foo").await;
+
+        let client = TestClientBuilder::new_for_test()
+            .signing_identity(identity, secret_key, TEST_CIPHER_SUITE)
+            .key_package_lifetime(10)
+            .build();
+
+        client.generate_key_package_messages(3).await.unwrap();
+
+        #[cfg(feature = "last_resort_key_package_ext")]
+        client
+            .generate_key_package_message(
+                vec![LastResortKeyPackageExt.into_extension().unwrap()].into(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+
+        let stored = client.key_package_store().key_packages().len();
+        let now = MlsTime::now();
+
+        let pruned = client.prune_key_package_secrets(now).await.unwrap();
+        assert_eq!(pruned, 0);
+        assert_eq!(client.key_package_store().key_packages().len(), stored);
+
+        let later = MlsTime::from(now.seconds_since_epoch() + 3600);
+
+        let pruned = client.prune_key_package_secrets(later).await.unwrap();
+        assert_eq!(pruned, 3);
+        assert_eq!(client.key_package_store().key_packages().len(), stored - 3);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn pruning_without_listing_support_fails() {
+        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"foo").await;
+
+        let client = TestClientBuilder::new_for_test()
+            .signing_identity(identity, secret_key, TEST_CIPHER_SUITE)
+            .key_package_repo(NoListingStorage(InMemoryKeyPackageStorage::new()))
+            .build();
+
+        client
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let res = client.prune_key_package_secrets(MlsTime::now()).await;
+
+        assert_matches!(res, Err(MlsError::KeyPackageListingUnsupported));
+    }
+
+    #[derive(Clone)]
+    struct NoListingStorage(InMemoryKeyPackageStorage);
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl KeyPackageStorage for NoListingStorage {
+        type Error = <InMemoryKeyPackageStorage as KeyPackageStorage>::Error;
+
+        async fn delete(&mut self, id: &[u8]) -> Result<(), Self::Error> {
+            self.0.delete(id);
+            Ok(())
+        }
+
+        async fn insert(&mut self, id: Vec<u8>, pkg: KeyPackageData) -> Result<(), Self::Error> {
+            self.0.insert(id, pkg);
+            Ok(())
+        }
+
+        async fn get(&self, id: &[u8]) -> Result<Option<KeyPackageData>, Self::Error> {
+            Ok(self.0.get(id))
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn default_capabilities_only_list_provider_suites() {
+        let crypto_provider =
+            TestCryptoProvider::with_enabled_cipher_suites(vec![TEST_CIPHER_SUITE]);
+
+        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"foo").await;
+
+        let client = TestClientBuilder::new_for_test()
+            .crypto_provider(crypto_provider)
+            .signing_identity(identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let capabilities = client.default_capabilities();
+        assert_eq!(capabilities.cipher_suites, vec![TEST_CIPHER_SUITE]);
+
+        let key_package = client
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap()
+            .into_key_package()
+            .unwrap();
+
+        assert_eq!(key_package.leaf_node.ungreased_capabilities(), capabilities);
+
+        let group = client
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let member = group.member_at_index(group.current_member_index()).unwrap();
+        assert_eq!(member.capabilities, capabilities);
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn new_member_add_proposal_adds_to_group() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let (bob_identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
 
         let bob = TestClientBuilder::new_for_test()
             .signing_identity(bob_identity.clone(), secret_key, TEST_CIPHER_SUITE)
@@ -1202,6 +1968,141 @@ mod tests {
         assert_eq!(expected_group_info, group_info);
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn welcome_group_id_matches_inviting_group() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE)
+            .await
+            .group;
+
+        let (bob, kp) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let commit = alice
+            .commit_builder()
+            .add_member(kp)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let group_id = bob
+            .welcome_group_id(&commit.welcome_messages[0])
+            .await
+            .unwrap();
+
+        assert_eq!(group_id.as_deref(), Some(alice.group_id()));
+
+        let group_id = bob.welcome_group_id(&commit.commit_message).await.unwrap();
+        assert_eq!(group_id, None);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn welcome_targets_me_checks_held_key_packages() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE)
+            .await
+            .group;
+
+        let (bob, kp) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let (carol, _) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
+
+        let commit = alice
+            .commit_builder()
+            .add_member(kp)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let welcome = &commit.welcome_messages[0];
+
+        let bob_targeted = bob.welcome_targets_me(welcome).await.unwrap();
+        assert!(bob_targeted);
+
+        let carol_targeted = carol.welcome_targets_me(welcome).await.unwrap();
+        assert!(!carol_targeted);
+
+        let res = bob.welcome_targets_me(&commit.commit_message).await;
+        assert_matches!(res, Err(MlsError::UnexpectedMessageType));
+
+        // The key package was not consumed by the check.
+        alice.apply_pending_commit().await.unwrap();
+        bob.join_group(None, welcome).await.unwrap();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn reinit_key_package_uses_target_cipher_suite() {
+        // Both cipher suites sign with Ed25519
+        let new_cipher_suite = CipherSuite::CURVE25519_CHACHA;
+
+        let (bob, _) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, CipherSuite::CURVE25519_AES128, "bob")
+                .await;
+
+        let key_package = bob
+            .generate_reinit_key_package(TEST_PROTOCOL_VERSION, new_cipher_suite)
+            .await
+            .unwrap();
+
+        assert_eq!(key_package.cipher_suite(), Some(new_cipher_suite));
+
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, new_cipher_suite)
+            .await
+            .group;
+
+        let commit = alice
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let (bob_group, _) = bob
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap();
+
+        assert_eq!(bob_group.cipher_suite(), new_cipher_suite);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn registered_types_are_advertised_in_key_package() {
+        let extension_type = ExtensionType::new(65002);
+
+        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let client = TestClientBuilder::new_for_test()
+            .extension_type(extension_type)
+            .custom_proposal_type(TEST_CUSTOM_PROPOSAL_TYPE)
+            .signing_identity(identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        assert_eq!(client.registered_extension_types(), vec![extension_type]);
+
+        assert_eq!(
+            client.registered_proposal_types(),
+            vec![TEST_CUSTOM_PROPOSAL_TYPE]
+        );
+
+        let key_package = client
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap()
+            .into_key_package()
+            .unwrap();
+
+        let capabilities = &key_package.leaf_node.capabilities;
+
+        assert!(capabilities.extensions.contains(&extension_type));
+        assert!(capabilities.proposals.contains(&TEST_CUSTOM_PROPOSAL_TYPE));
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn validate_group_info() {
         let alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE)
@@ -1226,4 +2127,244 @@ mod tests {
         let res = bob.validate_group_info(&group_info, &other_signer).await;
         assert_matches!(res, Err(MlsError::InvalidSignature));
     }
+
+    #[cfg(feature = "psk")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn create_group_with_psk() {
+        let psk = PreSharedKey::from(b"psk".to_vec());
+        let psk_id = ExternalPskId::new(b"psk id".to_vec());
+
+        let (alice_identity, secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let alice = TestClientBuilder::new_for_test()
+            .psk(psk_id.clone(), psk.clone())
+            .signing_identity(alice_identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let mut alice_group = alice
+            .create_group_with_psk(
+                b"group".to_vec(),
+                Default::default(),
+                Default::default(),
+                vec![psk_id.clone()],
+            )
+            .await
+            .unwrap();
+
+        let (bob, bob_key_package) = test_client_with_key_pkg_custom(
+            TEST_PROTOCOL_VERSION,
+            TEST_CIPHER_SUITE,
+            "bob",
+            Default::default(),
+            Default::default(),
+            |c| {
+                c.0.psk_store.insert(psk_id.clone(), psk.clone());
+            },
+        )
+        .await;
+
+        let commit = alice_group
+            .commit_builder()
+            .add_member(bob_key_package)
+            .unwrap()
+            .add_external_psk(psk_id.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice_group.apply_pending_commit().await.unwrap();
+
+        let (bob_group, _) = bob
+            .join_group(commit.ratchet_tree, &commit.welcome_messages[0])
+            .await
+            .unwrap();
+
+        assert_eq!(alice_group.current_epoch(), bob_group.current_epoch());
+
+        assert_eq!(
+            alice_group.epoch_authenticator().unwrap(),
+            bob_group.epoch_authenticator().unwrap()
+        );
+
+        let alice_secret = alice_group
+            .export_secret(b"label", b"context", 32)
+            .await
+            .unwrap();
+
+        let bob_secret = bob_group
+            .export_secret(b"label", b"context", 32)
+            .await
+            .unwrap();
+
+        assert_eq!(alice_secret, bob_secret);
+    }
+
+    #[cfg(feature = "psk")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn create_group_with_psk_binds_epoch_zero() {
+        use crate::{
+            crypto::test_utils::ModifiedCryptoProvider,
+            group::key_schedule::{InitSecret, KeySchedule},
+            psk::{
+                secret::{PskSecret, PskSecretInput},
+                JustPreSharedKeyID, PreSharedKeyID,
+            },
+            tree_kem::path_secret::PathSecret,
+        };
+
+        // Fixed randomness makes the initial init secret and the PSK nonce predictable
+        let crypto_provider = ModifiedCryptoProvider {
+            fixed_random: Some(0x42),
+            #[cfg(mls_build_async)]
+            yield_after_hashes: None,
+            #[cfg(feature = "experimental_aead_separation")]
+            hpke_aead: None,
+            #[cfg(feature = "experimental_sign_label_prefix")]
+            sign_label_prefix: &[],
+        };
+
+        let cs = crypto_provider
+            .cipher_suite_provider(TEST_CIPHER_SUITE)
+            .unwrap();
+
+        let psk = PreSharedKey::from(b"psk".to_vec());
+        let psk_id = ExternalPskId::new(b"psk id".to_vec());
+
+        let (alice_identity, secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let alice = TestClientBuilder::new_for_test()
+            .crypto_provider(crypto_provider)
+            .psk(psk_id.clone(), psk.clone())
+            .signing_identity(alice_identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let alice_group = alice
+            .create_group_with_psk(
+                b"group".to_vec(),
+                Default::default(),
+                Default::default(),
+                vec![psk_id.clone()],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(alice_group.current_epoch(), 0);
+
+        let psk_input = PskSecretInput {
+            id: PreSharedKeyID::new(JustPreSharedKeyID::External(psk_id), &cs).unwrap(),
+            psk,
+        };
+
+        let init_schedule = KeySchedule::new(InitSecret::new(vec![0x42; cs.kdf_extract_size()]));
+
+        let mut authenticators = vec![];
+
+        for psk_secret in [
+            PskSecret::calculate(&[psk_input], &cs).await.unwrap(),
+            PskSecret::new(&cs),
+        ] {
+            let derived = KeySchedule::from_key_schedule(
+                &init_schedule,
+                &PathSecret::empty(&cs),
+                alice_group.context(),
+                #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+                1,
+                &psk_secret,
+                &cs,
+            )
+            .await
+            .unwrap();
+
+            authenticators.push(derived.key_schedule.authentication_secret);
+        }
+
+        let epoch_authenticator = alice_group.epoch_authenticator().unwrap();
+
+        // The epoch 0 secrets are derived from the PSK and differ from a derivation without it
+        assert_eq!(epoch_authenticator.as_bytes(), &authenticators[0][..]);
+        assert_ne!(epoch_authenticator.as_bytes(), &authenticators[1][..]);
+    }
+
+    #[cfg(feature = "psk")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn create_group_with_unknown_psk_fails() {
+        let (alice_identity, secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let alice = TestClientBuilder::new_for_test()
+            .signing_identity(alice_identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let res = alice
+            .create_group_with_psk(
+                b"group".to_vec(),
+                Default::default(),
+                Default::default(),
+                vec![ExternalPskId::new(b"psk id".to_vec())],
+            )
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::MissingRequiredPsk));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn create_group_with_empty_id_fails() {
+        let alice = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let res = alice
+            .create_group_with_id(Vec::new(), Default::default(), Default::default())
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::InvalidGroupId(0)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn create_group_with_oversized_id_fails() {
+        let alice = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .max_group_id_length(16)
+            .build();
+
+        let res = alice
+            .create_group_with_id(vec![0u8; 17], Default::default(), Default::default())
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::InvalidGroupId(17)));
+
+        alice
+            .create_group_with_id(vec![0u8; 16], Default::default(), Default::default())
+            .await
+            .unwrap();
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn create_group_with_random_id_uses_32_bytes() {
+        let alice = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let group = alice
+            .create_group_with_random_id(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let other = alice
+            .create_group_with_random_id(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        assert_eq!(group.group_id().len(), 32);
+        assert_ne!(group.group_id(), other.group_id());
+    }
 }
@@ -9,8 +9,8 @@
 use crate::{
     cipher_suite::CipherSuite,
     client::Client,
-    client_config::ClientConfig,
-    extension::ExtensionType,
+    client_config::{ClientConfig, ClockRef},
+    extension::{ExtensionType, MlsExtension},
     group::{
         mls_rules::{DefaultMlsRules, MlsRules},
         proposal::ProposalType,
@@ -19,15 +19,23 @@ use crate::{
     identity::SigningIdentity,
     protocol_version::ProtocolVersion,
     psk::{ExternalPskId, PreSharedKey},
+    retry::{RetryLayer, RetryPolicy, RetryPolicyRef, RetryingIdentityProvider, RetryingPskStore},
     storage_provider::in_memory::{
         InMemoryGroupStateStorage, InMemoryKeyPackageStorage, InMemoryPreSharedKeyStorage,
     },
-    tree_kem::{Capabilities, Lifetime},
+    tree_kem::{
+        leaf_node_validator::{LeafNodeExtensionValidators, LeafNodeValidationError},
+        Capabilities, Lifetime,
+    },
     Sealed,
 };
 
-#[cfg(feature = "std")]
-use crate::time::MlsTime;
+use crate::time::{Clock, MlsTime};
+
+#[cfg(feature = "by_ref_proposal")]
+use crate::group::proposal_ref::PROPOSAL_REF_LABEL;
+
+use crate::group::WELCOME_HPKE_LABEL;
 
 use alloc::vec::Vec;
 
@@ -276,6 +284,129 @@ impl<C: IntoConfig> ClientBuilder<C> {
         ClientBuilder(c)
     }
 
+    /// Add proposal types that are never allowed in a commit.
+    ///
+    /// Creating a commit that includes a disallowed proposal by value fails, and disallowed
+    /// proposals received by reference are left out of the commit. Commits received from other
+    /// members that include a disallowed proposal are rejected.
+    pub fn disallowed_proposal_types<I>(self, types: I) -> ClientBuilder<IntoConfigOutput<C>>
+    where
+        I: IntoIterator<Item = ProposalType>,
+    {
+        let mut c = self.0.into_config();
+        c.0.settings.disallowed_proposal_types.extend(types);
+        ClientBuilder(c)
+    }
+
+    /// Reject welcome messages whose group info carries an extension that is
+    /// neither required by MLS nor registered with
+    /// [`ClientBuilder::extension_type`].
+    ///
+    /// When enabled, joining such a group fails with
+    /// [`MlsError::UnknownGroupInfoExtension`](crate::client::MlsError::UnknownGroupInfoExtension).
+    /// Disabled by default, in which case unknown group info extensions are ignored.
+    pub fn reject_unknown_group_info_extensions(
+        self,
+        enabled: bool,
+    ) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.reject_unknown_group_info_extensions = enabled;
+        ClientBuilder(c)
+    }
+
+    /// Verify the confirmation tag of received commits. Enabled by default.
+    ///
+    /// # Warning
+    ///
+    /// The confirmation tag proves that the committer and this member derived
+    /// the same secrets for the new epoch. When disabled, a commit with an
+    /// invalid confirmation tag is applied and this member may silently end
+    /// up in an epoch whose secrets differ from those of the other members.
+    /// Only disable verification for a trusted observer of a known-good
+    /// message stream.
+    pub fn verify_confirmation_tag(self, enabled: bool) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.verify_confirmation_tag = enabled;
+        ClientBuilder(c)
+    }
+
+    /// Add identities of members that are allowed to invite this client to a group.
+    ///
+    /// Once at least one inviter is allowed, joining a group via
+    /// [`Client::join_group`](crate::Client::join_group) fails with
+    /// [`MlsError::UnauthorizedInviter`](crate::client::MlsError::UnauthorizedInviter)
+    /// unless the identity of the member that signed the welcome's group info, as
+    /// reported by the [`IdentityProvider`], is allowed. By default, welcomes from
+    /// any inviter are accepted.
+    pub fn allowed_inviters<I>(self, identities: I) -> ClientBuilder<IntoConfigOutput<C>>
+    where
+        I: IntoIterator<Item = Vec<u8>>,
+    {
+        let mut c = self.0.into_config();
+
+        c.0.settings
+            .allowed_inviters
+            .get_or_insert_with(Vec::new)
+            .extend(identities);
+
+        ClientBuilder(c)
+    }
+
+    /// Set the [`Clock`] used for time dependent checks such as validating
+    /// the lifetime of key packages, and for computing the lifetime of
+    /// generated key packages.
+    ///
+    /// The system clock is used by default.
+    pub fn clock<T>(self, clock: T) -> ClientBuilder<IntoConfigOutput<C>>
+    where
+        T: Clock + Clone + 'static,
+    {
+        let mut c = self.0.into_config();
+        c.0.settings.clock = Some(ClockRef::new(clock));
+        ClientBuilder(c)
+    }
+
+    /// Register a validator for leaf nodes containing an extension of type `E`.
+    ///
+    /// The validator is called with the decoded extension when validating
+    /// key packages and the leaf nodes of added or updated members, after
+    /// the standard checks. A leaf node whose extension can not be decoded
+    /// as `E` or is rejected by the validator is invalid.
+    pub fn leaf_node_extension_validator<E, F>(
+        self,
+        validator: F,
+    ) -> ClientBuilder<IntoConfigOutput<C>>
+    where
+        E: MlsExtension,
+        F: Fn(&E) -> Result<(), LeafNodeValidationError> + Clone + Send + Sync + 'static,
+    {
+        let mut c = self.0.into_config();
+        c.0.settings.leaf_node_extension_validators.push(validator);
+        ClientBuilder(c)
+    }
+
+    /// Declare that members whose leaf node contains an extension of type `L`
+    /// rely on the group context extension of type `G`.
+    ///
+    /// Creating a commit that removes `G` from the group context while such a
+    /// member is in the group fails with
+    /// [`MlsError::RemovedGroupExtensionInUse`](crate::client::MlsError::RemovedGroupExtensionInUse).
+    /// Received commits are not affected since RFC 9420 does not forbid
+    /// removing group context extensions.
+    pub fn leaf_node_extension_dependency<L, G>(self) -> ClientBuilder<IntoConfigOutput<C>>
+    where
+        L: MlsExtension,
+        G: MlsExtension,
+    {
+        let mut c = self.0.into_config();
+
+        c.0.settings
+            .leaf_node_extension_validators
+            .push_group_extension_dependency(L::extension_type(), G::extension_type());
+
+        ClientBuilder(c)
+    }
+
     /// Add a protocol version to the list of protocol versions supported by the client.
     ///
     /// If no protocol version is explicitly added, the client will support all protocol versions
@@ -400,6 +531,25 @@ impl<C: IntoConfig> ClientBuilder<C> {
         }))
     }
 
+    /// Retry failed calls to the identity provider and the PSK store
+    /// according to `retry_policy`.
+    ///
+    /// Every call to the identity provider and every PSK lookup is retried
+    /// while `retry_policy` accepts the failure, which is classified with
+    /// [`IntoAnyError::is_transient`](mls_rs_core::error::IntoAnyError::is_transient).
+    /// When retries are exhausted, the error of the last attempt is returned.
+    /// The policy applies to the identity provider and PSK store of the built
+    /// client, regardless of the order in which they are set. By default,
+    /// failed calls are not retried.
+    pub fn retry_policy<R>(self, retry_policy: R) -> ClientBuilder<IntoConfigOutput<C>>
+    where
+        R: RetryPolicy + 'static,
+    {
+        let mut c = self.0.into_config();
+        c.0.settings.retry_policy = RetryPolicyRef::new(retry_policy);
+        ClientBuilder(c)
+    }
+
     /// Set the crypto provider to be used by the client.
     pub fn crypto_provider<Cp>(
         self,
@@ -482,6 +632,116 @@ impl<C: IntoConfig> ClientBuilder<C> {
         ClientBuilder(c)
     }
 
+    /// Set the label used to compute proposal references.
+    ///
+    /// By default, the label `"MLS 1.0 Proposal Reference"` defined by RFC 9420 is used.
+    /// Changing it is **not compliant** with RFC 9420 and is only meant for deliberately
+    /// interoperating with nonstandard peers. All members of a group must be configured with
+    /// the same label, otherwise commits referencing proposals will fail to process.
+    #[cfg(feature = "by_ref_proposal")]
+    pub fn proposal_ref_label(self, label: Vec<u8>) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.proposal_ref_label = label;
+        ClientBuilder(c)
+    }
+
+    /// Set the label used as part of the HPKE info when encrypting and
+    /// decrypting the group secrets of welcome messages.
+    ///
+    /// By default, the label `"MLS 1.0 Welcome"` defined by RFC 9420 is used.
+    /// Changing it is **not compliant** with RFC 9420 and is only meant for deliberately
+    /// interoperating with nonstandard peers. The creator of a welcome message and the
+    /// new members must be configured with the same label, otherwise joining will fail.
+    pub fn welcome_hpke_label(self, label: Vec<u8>) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.welcome_hpke_label = label;
+        ClientBuilder(c)
+    }
+
+    /// Set the maximum size in bytes of a ratchet tree embedded in a group info
+    /// when joining a group.
+    ///
+    /// Larger trees are rejected before being parsed. By default, there is no limit.
+    pub fn max_ratchet_tree_size(self, max_size: usize) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.max_ratchet_tree_size = Some(max_size);
+        ClientBuilder(c)
+    }
+
+    /// Set the maximum length in bytes of a group id accepted by
+    /// [`Client::create_group_with_id`](crate::Client::create_group_with_id).
+    ///
+    /// Empty group ids are always rejected. By default, there is no maximum length.
+    pub fn max_group_id_length(self, max_length: usize) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.max_group_id_length = Some(max_length);
+        ClientBuilder(c)
+    }
+
+    /// Set the maximum number of prior epochs retained by each group for
+    /// decrypting late application messages.
+    ///
+    /// Epochs older than the most recent `max_epochs` prior epochs are evicted
+    /// and messages from them are rejected with
+    /// [`MlsError::InvalidEpoch`](crate::client::MlsError::InvalidEpoch). By
+    /// default, retention is only bounded by the
+    /// [`GroupStateStorage`](crate::GroupStateStorage) in use.
+    #[cfg(feature = "prior_epoch")]
+    pub fn max_retained_epochs(self, max_epochs: usize) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.max_retained_epochs = Some(max_epochs);
+        ClientBuilder(c)
+    }
+
+    /// Set the maximum length in bytes of application message plaintexts.
+    ///
+    /// Longer messages are rejected with
+    /// [`MlsError::ApplicationMessageTooLarge`](crate::client::MlsError::ApplicationMessageTooLarge)
+    /// by [`Group::encrypt_application_message`](crate::group::Group::encrypt_application_message)
+    /// and when received. Received messages whose ciphertext is too large to hold a message
+    /// of at most `max_length` bytes are rejected before being decrypted. By default, there
+    /// is no maximum length.
+    #[cfg(feature = "private_message")]
+    pub fn max_application_message_len(
+        self,
+        max_length: usize,
+    ) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.max_application_message_len = Some(max_length);
+        ClientBuilder(c)
+    }
+
+    /// Set the maximum number of [`PreSharedKeyProposal`](crate::group::proposal::PreSharedKeyProposal)s
+    /// in a commit.
+    ///
+    /// Commits with more PSKs are rejected with
+    /// [`MlsError::TooManyPsks`](crate::client::MlsError::TooManyPsks), when
+    /// created and when received, before any PSK is looked up. By default,
+    /// there is no maximum.
+    #[cfg(feature = "psk")]
+    pub fn max_psks_per_commit(self, max_psks: usize) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.max_psks_per_commit = Some(max_psks);
+        ClientBuilder(c)
+    }
+
+    /// Report proposals that the local [`MlsRules`] would have filtered out of a
+    /// received commit instead of applying only the filtered proposals.
+    ///
+    /// When enabled, the proposals committed by another member are applied as
+    /// committed, and the ones removed by
+    /// [`MlsRules::filter_proposals`] are listed in
+    /// [`NewEpoch::rejected_proposals`](crate::group::NewEpoch::rejected_proposals).
+    /// Errors returned by the rules still reject the commit. Disabled by default.
+    pub fn warn_on_foreign_rejected_proposals(
+        self,
+        enabled: bool,
+    ) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.warn_on_foreign_rejected_proposals = enabled;
+        ClientBuilder(c)
+    }
+
     #[cfg(any(test, feature = "test_util"))]
     pub(crate) fn key_package_not_before(
         self,
@@ -497,8 +757,10 @@ impl<C: IntoConfig> ClientBuilder<C>
 where
     C::KeyPackageRepository: KeyPackageStorage + Clone,
     C::PskStore: PreSharedKeyStorage + Clone,
+    <C::PskStore as PreSharedKeyStorage>::Error: Send,
     C::GroupStateStorage: GroupStateStorage + Clone,
     C::IdentityProvider: IdentityProvider + Clone,
+    <C::IdentityProvider as IdentityProvider>::Error: Send,
     C::MlsRules: MlsRules + Clone,
     C::CryptoProvider: CryptoProvider + Clone,
 {
@@ -628,9 +890,9 @@ pub type IntoConfigOutput<C> = Config<
 /// Helper alias to make a `Config` from a `ClientConfig`
 pub type MakeConfig<C> = Config<
     <C as ClientConfig>::KeyPackageRepository,
-    <C as ClientConfig>::PskStore,
+    <<C as ClientConfig>::PskStore as RetryLayer>::Inner,
     <C as ClientConfig>::GroupStateStorage,
-    <C as ClientConfig>::IdentityProvider,
+    <<C as ClientConfig>::IdentityProvider as RetryLayer>::Inner,
     <C as ClientConfig>::MlsRules,
     <C as ClientConfig>::CryptoProvider,
 >;
@@ -639,15 +901,17 @@ impl<Kpr, Ps, Gss, Ip, Pr, Cp> ClientConfig for ConfigInner<Kpr, Ps, Gss, Ip, Pr
 where
     Kpr: KeyPackageStorage + Clone,
     Ps: PreSharedKeyStorage + Clone,
+    Ps::Error: Send,
     Gss: GroupStateStorage + Clone,
     Ip: IdentityProvider + Clone,
+    Ip::Error: Send,
     Pr: MlsRules + Clone,
     Cp: CryptoProvider + Clone,
 {
     type KeyPackageRepository = Kpr;
-    type PskStore = Ps;
+    type PskStore = RetryingPskStore<Ps>;
     type GroupStateStorage = Gss;
-    type IdentityProvider = Ip;
+    type IdentityProvider = RetryingIdentityProvider<Ip>;
     type MlsRules = Pr;
     type CryptoProvider = Cp;
 
@@ -668,7 +932,7 @@ where
     }
 
     fn secret_store(&self) -> Self::PskStore {
-        self.psk_store.clone()
+        RetryingPskStore::new(self.psk_store.clone(), self.settings.retry_policy.clone())
     }
 
     fn group_state_storage(&self) -> Self::GroupStateStorage {
@@ -676,7 +940,10 @@ where
     }
 
     fn identity_provider(&self) -> Self::IdentityProvider {
-        self.identity_provider.clone()
+        RetryingIdentityProvider::new(
+            self.identity_provider.clone(),
+            self.settings.retry_policy.clone(),
+        )
     }
 
     fn crypto_provider(&self) -> Self::CryptoProvider {
@@ -684,11 +951,9 @@ where
     }
 
     fn lifetime(&self) -> Lifetime {
-        #[cfg(feature = "std")]
-        let now_timestamp = MlsTime::now().seconds_since_epoch();
-
-        #[cfg(not(feature = "std"))]
-        let now_timestamp = 0;
+        let now_timestamp = self
+            .current_time()
+            .map_or(0, |time| time.seconds_since_epoch());
 
         #[cfg(test)]
         let now_timestamp = self
@@ -705,6 +970,66 @@ where
     fn supported_custom_proposals(&self) -> Vec<crate::group::proposal::ProposalType> {
         self.settings.custom_proposal_types.clone()
     }
+
+    #[cfg(feature = "by_ref_proposal")]
+    fn proposal_ref_label(&self) -> Vec<u8> {
+        self.settings.proposal_ref_label.clone()
+    }
+
+    fn max_ratchet_tree_size(&self) -> Option<usize> {
+        self.settings.max_ratchet_tree_size
+    }
+
+    fn max_group_id_length(&self) -> Option<usize> {
+        self.settings.max_group_id_length
+    }
+
+    #[cfg(feature = "prior_epoch")]
+    fn max_retained_epochs(&self) -> Option<usize> {
+        self.settings.max_retained_epochs
+    }
+
+    #[cfg(feature = "private_message")]
+    fn max_application_message_len(&self) -> Option<usize> {
+        self.settings.max_application_message_len
+    }
+
+    #[cfg(feature = "psk")]
+    fn max_psks_per_commit(&self) -> Option<usize> {
+        self.settings.max_psks_per_commit
+    }
+
+    fn disallowed_proposal_types(&self) -> Vec<ProposalType> {
+        self.settings.disallowed_proposal_types.clone()
+    }
+
+    fn warn_on_foreign_rejected_proposals(&self) -> bool {
+        self.settings.warn_on_foreign_rejected_proposals
+    }
+
+    fn allowed_inviters(&self) -> Option<Vec<Vec<u8>>> {
+        self.settings.allowed_inviters.clone()
+    }
+
+    fn reject_unknown_group_info_extensions(&self) -> bool {
+        self.settings.reject_unknown_group_info_extensions
+    }
+
+    fn verify_confirmation_tag(&self) -> bool {
+        self.settings.verify_confirmation_tag
+    }
+
+    fn clock(&self) -> Option<ClockRef> {
+        self.settings.clock.clone()
+    }
+
+    fn leaf_node_extension_validators(&self) -> LeafNodeExtensionValidators {
+        self.settings.leaf_node_extension_validators.clone()
+    }
+
+    fn welcome_hpke_label(&self) -> Vec<u8> {
+        self.settings.welcome_hpke_label.clone()
+    }
 }
 
 impl<Kpr, Ps, Gss, Ip, Pr, Cp> Sealed for Config<Kpr, Ps, Gss, Ip, Pr, Cp> {}
@@ -714,8 +1039,10 @@ where
     Kpr: KeyPackageStorage + Clone,
 
     Ps: PreSharedKeyStorage + Clone,
+    Ps::Error: Send,
     Gss: GroupStateStorage + Clone,
     Ip: IdentityProvider + Clone,
+    Ip::Error: Send,
     Pr: MlsRules + Clone,
     Cp: CryptoProvider + Clone,
 {
@@ -797,6 +1124,70 @@ impl<T: MlsConfig> ClientConfig for T {
     fn supported_credential_types(&self) -> Vec<CredentialType> {
         self.get().supported_credential_types()
     }
+
+    #[cfg(feature = "by_ref_proposal")]
+    fn proposal_ref_label(&self) -> Vec<u8> {
+        self.get().proposal_ref_label()
+    }
+
+    fn max_ratchet_tree_size(&self) -> Option<usize> {
+        self.get().max_ratchet_tree_size()
+    }
+
+    fn max_group_id_length(&self) -> Option<usize> {
+        self.get().max_group_id_length()
+    }
+
+    #[cfg(feature = "prior_epoch")]
+    fn max_retained_epochs(&self) -> Option<usize> {
+        self.get().max_retained_epochs()
+    }
+
+    #[cfg(feature = "private_message")]
+    fn max_application_message_len(&self) -> Option<usize> {
+        self.get().max_application_message_len()
+    }
+
+    #[cfg(feature = "psk")]
+    fn max_psks_per_commit(&self) -> Option<usize> {
+        self.get().max_psks_per_commit()
+    }
+
+    fn disallowed_proposal_types(&self) -> Vec<ProposalType> {
+        self.get().disallowed_proposal_types()
+    }
+
+    fn warn_on_foreign_rejected_proposals(&self) -> bool {
+        self.get().warn_on_foreign_rejected_proposals()
+    }
+
+    fn allowed_inviters(&self) -> Option<Vec<Vec<u8>>> {
+        self.get().allowed_inviters()
+    }
+
+    fn reject_unknown_group_info_extensions(&self) -> bool {
+        self.get().reject_unknown_group_info_extensions()
+    }
+
+    fn verify_confirmation_tag(&self) -> bool {
+        self.get().verify_confirmation_tag()
+    }
+
+    fn clock(&self) -> Option<ClockRef> {
+        self.get().clock()
+    }
+
+    fn leaf_node_extension_validators(&self) -> LeafNodeExtensionValidators {
+        self.get().leaf_node_extension_validators()
+    }
+
+    fn current_time(&self) -> Option<MlsTime> {
+        self.get().current_time()
+    }
+
+    fn welcome_hpke_label(&self) -> Vec<u8> {
+        self.get().welcome_hpke_label()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -805,6 +1196,25 @@ pub(crate) struct Settings {
     pub(crate) protocol_versions: Vec<ProtocolVersion>,
     pub(crate) custom_proposal_types: Vec<ProposalType>,
     pub(crate) lifetime_in_s: u64,
+    #[cfg(feature = "by_ref_proposal")]
+    pub(crate) proposal_ref_label: Vec<u8>,
+    pub(crate) max_ratchet_tree_size: Option<usize>,
+    pub(crate) max_group_id_length: Option<usize>,
+    #[cfg(feature = "prior_epoch")]
+    pub(crate) max_retained_epochs: Option<usize>,
+    #[cfg(feature = "private_message")]
+    pub(crate) max_application_message_len: Option<usize>,
+    #[cfg(feature = "psk")]
+    pub(crate) max_psks_per_commit: Option<usize>,
+    pub(crate) disallowed_proposal_types: Vec<ProposalType>,
+    pub(crate) warn_on_foreign_rejected_proposals: bool,
+    pub(crate) allowed_inviters: Option<Vec<Vec<u8>>>,
+    pub(crate) reject_unknown_group_info_extensions: bool,
+    pub(crate) verify_confirmation_tag: bool,
+    pub(crate) clock: Option<ClockRef>,
+    pub(crate) welcome_hpke_label: Vec<u8>,
+    pub(crate) leaf_node_extension_validators: LeafNodeExtensionValidators,
+    pub(crate) retry_policy: RetryPolicyRef,
     #[cfg(any(test, feature = "test_util"))]
     pub(crate) key_package_not_before: Option<u64>,
 }
@@ -816,18 +1226,44 @@ impl Default for Settings {
             protocol_versions: Default::default(),
             lifetime_in_s: 365 * 24 * 3600,
             custom_proposal_types: Default::default(),
+            #[cfg(feature = "by_ref_proposal")]
+            proposal_ref_label: PROPOSAL_REF_LABEL.to_vec(),
+            max_ratchet_tree_size: None,
+            max_group_id_length: None,
+            #[cfg(feature = "prior_epoch")]
+            max_retained_epochs: None,
+            #[cfg(feature = "private_message")]
+            max_application_message_len: None,
+            #[cfg(feature = "psk")]
+            max_psks_per_commit: None,
+            disallowed_proposal_types: Default::default(),
+            warn_on_foreign_rejected_proposals: false,
+            allowed_inviters: None,
+            reject_unknown_group_info_extensions: false,
+            verify_confirmation_tag: true,
+            clock: None,
+            welcome_hpke_label: WELCOME_HPKE_LABEL.to_vec(),
+            leaf_node_extension_validators: Default::default(),
+            retry_policy: Default::default(),
             #[cfg(any(test, feature = "test_util"))]
             key_package_not_before: None,
         }
     }
 }
 
-pub(crate) fn recreate_config<T: ClientConfig>(
+pub(crate) fn recreate_config<T>(
     c: T,
     signer: Option<SignatureSecretKey>,
     signing_identity: Option<(SigningIdentity, CipherSuite)>,
     version: ProtocolVersion,
-) -> MakeConfig<T> {
+) -> MakeConfig<T>
+where
+    T: ClientConfig,
+    T::PskStore: RetryLayer,
+    T::IdentityProvider: RetryLayer,
+{
+    let identity_provider = c.identity_provider();
+
     Config(ConfigInner {
         settings: Settings {
             extension_types: c.supported_extensions(),
@@ -837,13 +1273,32 @@ pub(crate) fn recreate_config<T: ClientConfig>(
                 let l = c.lifetime();
                 l.not_after - l.not_before
             },
+            #[cfg(feature = "by_ref_proposal")]
+            proposal_ref_label: c.proposal_ref_label(),
+            max_ratchet_tree_size: c.max_ratchet_tree_size(),
+            max_group_id_length: c.max_group_id_length(),
+            #[cfg(feature = "prior_epoch")]
+            max_retained_epochs: c.max_retained_epochs(),
+            #[cfg(feature = "private_message")]
+            max_application_message_len: c.max_application_message_len(),
+            #[cfg(feature = "psk")]
+            max_psks_per_commit: c.max_psks_per_commit(),
+            disallowed_proposal_types: c.disallowed_proposal_types(),
+            warn_on_foreign_rejected_proposals: c.warn_on_foreign_rejected_proposals(),
+            allowed_inviters: c.allowed_inviters(),
+            reject_unknown_group_info_extensions: c.reject_unknown_group_info_extensions(),
+            verify_confirmation_tag: c.verify_confirmation_tag(),
+            clock: c.clock(),
+            welcome_hpke_label: c.welcome_hpke_label(),
+            leaf_node_extension_validators: c.leaf_node_extension_validators(),
+            retry_policy: identity_provider.policy(),
             #[cfg(any(test, feature = "test_util"))]
             key_package_not_before: None,
         },
         key_package_repo: c.key_package_repo(),
-        psk_store: c.secret_store(),
+        psk_store: c.secret_store().into_inner(),
         group_state_storage: c.group_state_storage(),
-        identity_provider: c.identity_provider(),
+        identity_provider: identity_provider.into_inner(),
         mls_rules: c.mls_rules(),
         crypto_provider: c.crypto_provider(),
         signer,
@@ -7,13 +7,21 @@ use crate::{
     group::{mls_rules::MlsRules, proposal::ProposalType},
     identity::CredentialType,
     protocol_version::ProtocolVersion,
-    tree_kem::{leaf_node::ConfigProperties, Capabilities, Lifetime},
+    tree_kem::{
+        leaf_node::ConfigProperties, leaf_node_validator::LeafNodeExtensionValidators,
+        Capabilities, Lifetime,
+    },
     ExtensionList,
 };
-use alloc::vec::Vec;
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt::{self, Debug};
 use mls_rs_core::{
-    crypto::CryptoProvider, group::GroupStateStorage, identity::IdentityProvider,
-    key_package::KeyPackageStorage, psk::PreSharedKeyStorage,
+    crypto::CryptoProvider,
+    group::GroupStateStorage,
+    identity::IdentityProvider,
+    key_package::KeyPackageStorage,
+    psk::PreSharedKeyStorage,
+    time::{Clock, MlsTime},
 };
 
 pub trait ClientConfig: Send + Sync + Clone {
@@ -39,6 +47,72 @@ pub trait ClientConfig: Send + Sync + Clone {
 
     fn lifetime(&self) -> Lifetime;
 
+    #[cfg(feature = "by_ref_proposal")]
+    fn proposal_ref_label(&self) -> Vec<u8>;
+
+    fn max_ratchet_tree_size(&self) -> Option<usize> {
+        None
+    }
+
+    fn max_group_id_length(&self) -> Option<usize> {
+        None
+    }
+
+    #[cfg(feature = "prior_epoch")]
+    fn max_retained_epochs(&self) -> Option<usize> {
+        None
+    }
+
+    #[cfg(feature = "private_message")]
+    fn max_application_message_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[cfg(feature = "psk")]
+    fn max_psks_per_commit(&self) -> Option<usize> {
+        None
+    }
+
+    fn disallowed_proposal_types(&self) -> Vec<ProposalType> {
+        Vec::new()
+    }
+
+    fn warn_on_foreign_rejected_proposals(&self) -> bool {
+        false
+    }
+
+    fn allowed_inviters(&self) -> Option<Vec<Vec<u8>>> {
+        None
+    }
+
+    fn reject_unknown_group_info_extensions(&self) -> bool {
+        false
+    }
+
+    fn verify_confirmation_tag(&self) -> bool {
+        true
+    }
+
+    fn clock(&self) -> Option<ClockRef> {
+        None
+    }
+
+    fn leaf_node_extension_validators(&self) -> LeafNodeExtensionValidators {
+        Default::default()
+    }
+
+    fn welcome_hpke_label(&self) -> Vec<u8> {
+        crate::group::WELCOME_HPKE_LABEL.to_vec()
+    }
+
+    /// Time used for time dependent checks, read from the configured
+    /// [`Clock`] or from the system clock if none is configured.
+    fn current_time(&self) -> Option<MlsTime> {
+        self.clock()
+            .map(|clock| clock.now())
+            .or_else(crate::time::system_time)
+    }
+
     fn capabilities(&self) -> Capabilities {
         Capabilities {
             protocol_versions: self.supported_protocol_versions(),
@@ -64,3 +138,39 @@ pub trait ClientConfig: Send + Sync + Clone {
         }
     }
 }
+
+trait CloneableClock: Clock {
+    fn clone_box(&self) -> Box<dyn CloneableClock>;
+}
+
+impl<T: Clock + Clone + 'static> CloneableClock for T {
+    fn clone_box(&self) -> Box<dyn CloneableClock> {
+        Box::new(self.clone())
+    }
+}
+
+/// Type-erased [`Clock`] stored in the client configuration, set with
+/// [`ClientBuilder::clock`](crate::client_builder::ClientBuilder::clock).
+pub struct ClockRef(Box<dyn CloneableClock>);
+
+impl ClockRef {
+    pub(crate) fn new<T: Clock + Clone + 'static>(clock: T) -> Self {
+        Self(Box::new(clock))
+    }
+
+    pub(crate) fn now(&self) -> MlsTime {
+        self.0.now()
+    }
+}
+
+impl Clone for ClockRef {
+    fn clone(&self) -> Self {
+        Self(self.0.clone_box())
+    }
+}
+
+impl Debug for ClockRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClockRef").finish_non_exhaustive()
+    }
+}
@@ -11,10 +11,117 @@ pub use mls_rs_core::crypto::{
 
 pub use mls_rs_core::secret::Secret;
 
+#[cfg(feature = "experimental_aead_separation")]
+pub use mls_rs_core::crypto::AeadContext;
+
+#[cfg(not(feature = "experimental_aead_separation"))]
+#[cfg_attr(not(feature = "private_message"), allow(dead_code))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum AeadContext {
+    Framing,
+    Hpke,
+}
+
+use alloc::vec::Vec;
+use zeroize::Zeroizing;
+
+#[cfg(feature = "experimental_aead_separation")]
+pub(crate) fn aead_key_size<P: CipherSuiteProvider>(cs: &P, context: AeadContext) -> usize {
+    cs.aead_key_size_in(context)
+}
+
+#[cfg(not(feature = "experimental_aead_separation"))]
+pub(crate) fn aead_key_size<P: CipherSuiteProvider>(cs: &P, _context: AeadContext) -> usize {
+    cs.aead_key_size()
+}
+
+#[cfg(feature = "experimental_aead_separation")]
+pub(crate) fn aead_nonce_size<P: CipherSuiteProvider>(cs: &P, context: AeadContext) -> usize {
+    cs.aead_nonce_size_in(context)
+}
+
+#[cfg(not(feature = "experimental_aead_separation"))]
+pub(crate) fn aead_nonce_size<P: CipherSuiteProvider>(cs: &P, _context: AeadContext) -> usize {
+    cs.aead_nonce_size()
+}
+
+#[cfg(feature = "experimental_aead_separation")]
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+pub(crate) async fn aead_seal<P: CipherSuiteProvider>(
+    cs: &P,
+    context: AeadContext,
+    key: &[u8],
+    data: &[u8],
+    aad: Option<&[u8]>,
+    nonce: &[u8],
+) -> Result<Vec<u8>, P::Error> {
+    cs.aead_seal_in(context, key, data, aad, nonce).await
+}
+
+#[cfg(not(feature = "experimental_aead_separation"))]
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+pub(crate) async fn aead_seal<P: CipherSuiteProvider>(
+    cs: &P,
+    _context: AeadContext,
+    key: &[u8],
+    data: &[u8],
+    aad: Option<&[u8]>,
+    nonce: &[u8],
+) -> Result<Vec<u8>, P::Error> {
+    cs.aead_seal(key, data, aad, nonce).await
+}
+
+#[cfg(feature = "experimental_aead_separation")]
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+pub(crate) async fn aead_open<P: CipherSuiteProvider>(
+    cs: &P,
+    context: AeadContext,
+    key: &[u8],
+    ciphertext: &[u8],
+    aad: Option<&[u8]>,
+    nonce: &[u8],
+) -> Result<Zeroizing<Vec<u8>>, P::Error> {
+    cs.aead_open_in(context, key, ciphertext, aad, nonce).await
+}
+
+#[cfg(not(feature = "experimental_aead_separation"))]
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+pub(crate) async fn aead_open<P: CipherSuiteProvider>(
+    cs: &P,
+    _context: AeadContext,
+    key: &[u8],
+    ciphertext: &[u8],
+    aad: Option<&[u8]>,
+    nonce: &[u8],
+) -> Result<Zeroizing<Vec<u8>>, P::Error> {
+    cs.aead_open(key, ciphertext, aad, nonce).await
+}
+
+#[cfg(feature = "experimental_sign_label_prefix")]
+pub(crate) fn sign_label_prefix<P: CipherSuiteProvider>(cs: &P) -> &[u8] {
+    cs.sign_label_prefix()
+}
+
+#[cfg(not(feature = "experimental_sign_label_prefix"))]
+pub(crate) fn sign_label_prefix<P: CipherSuiteProvider>(_cs: &P) -> &[u8] {
+    &[]
+}
+
 #[cfg(test)]
 pub(crate) mod test_utils {
+    use alloc::vec::Vec;
     use cfg_if::cfg_if;
-    use mls_rs_core::crypto::CryptoProvider;
+    use mls_rs_core::crypto::{
+        CipherSuiteProvider, CryptoProvider, HpkeCiphertext, HpkePublicKey, HpkeSecretKey,
+        SignaturePublicKey, SignatureSecretKey,
+    };
+    use zeroize::Zeroizing;
+
+    #[cfg(mls_build_async)]
+    use alloc::boxed::Box;
+
+    #[cfg(feature = "experimental_aead_separation")]
+    use mls_rs_core::crypto::AeadContext;
 
     cfg_if! {
         if #[cfg(target_arch = "wasm32")] {
@@ -40,4 +147,476 @@ pub(crate) mod test_utils {
     ) -> Option<<TestCryptoProvider as CryptoProvider>::CipherSuiteProvider> {
         TestCryptoProvider::new().cipher_suite_provider(CipherSuite::from(cipher_suite))
     }
+
+    type TestCipherSuite = <TestCryptoProvider as CryptoProvider>::CipherSuiteProvider;
+
+    /// [`TestCryptoProvider`] with parts of its behavior modified, for tests
+    /// depending on a specific cipher suite provider behavior.
+    #[derive(Clone, Debug, Default)]
+    pub struct ModifiedCryptoProvider {
+        /// If set, random bytes are all equal to this value.
+        pub fixed_random: Option<u8>,
+        /// If set, the number of hashes computed before a hash yields once
+        /// instead of completing immediately, allowing to drop a future at
+        /// that point.
+        #[cfg(mls_build_async)]
+        pub yield_after_hashes: Option<alloc::sync::Arc<core::sync::atomic::AtomicUsize>>,
+        /// If set, the AEAD of this cipher suite is used in the
+        /// [`AeadContext::Hpke`] context instead of the AEAD of the cipher suite.
+        #[cfg(feature = "experimental_aead_separation")]
+        pub hpke_aead: Option<CipherSuite>,
+        /// Prefix added to the labels of signed MLS structures.
+        #[cfg(feature = "experimental_sign_label_prefix")]
+        pub sign_label_prefix: &'static [u8],
+    }
+
+    impl CryptoProvider for ModifiedCryptoProvider {
+        type CipherSuiteProvider = ModifiedCipherSuite;
+
+        fn supported_cipher_suites(&self) -> Vec<CipherSuite> {
+            TestCryptoProvider::new().supported_cipher_suites()
+        }
+
+        fn cipher_suite_provider(
+            &self,
+            cipher_suite: CipherSuite,
+        ) -> Option<Self::CipherSuiteProvider> {
+            let crypto_provider = TestCryptoProvider::new();
+
+            Some(ModifiedCipherSuite {
+                inner: crypto_provider.cipher_suite_provider(cipher_suite)?,
+                #[cfg(feature = "experimental_aead_separation")]
+                hpke_aead: match self.hpke_aead {
+                    Some(cipher_suite) => {
+                        Some(crypto_provider.cipher_suite_provider(cipher_suite)?)
+                    }
+                    None => None,
+                },
+                provider: self.clone(),
+            })
+        }
+    }
+
+    #[derive(Clone)]
+    pub struct ModifiedCipherSuite {
+        inner: TestCipherSuite,
+        #[cfg(feature = "experimental_aead_separation")]
+        hpke_aead: Option<TestCipherSuite>,
+        provider: ModifiedCryptoProvider,
+    }
+
+    #[cfg(feature = "experimental_aead_separation")]
+    impl ModifiedCipherSuite {
+        fn aead(&self, context: AeadContext) -> &TestCipherSuite {
+            match (context, &self.hpke_aead) {
+                (AeadContext::Hpke, Some(hpke_aead)) => hpke_aead,
+                _ => &self.inner,
+            }
+        }
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+    #[cfg_attr(
+        all(not(target_arch = "wasm32"), mls_build_async),
+        maybe_async::must_be_async
+    )]
+    impl CipherSuiteProvider for ModifiedCipherSuite {
+        type Error = <TestCipherSuite as CipherSuiteProvider>::Error;
+        type HpkeContextS = <TestCipherSuite as CipherSuiteProvider>::HpkeContextS;
+        type HpkeContextR = <TestCipherSuite as CipherSuiteProvider>::HpkeContextR;
+
+        fn cipher_suite(&self) -> CipherSuite {
+            self.inner.cipher_suite()
+        }
+
+        async fn hash(&self, data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+            #[cfg(mls_build_async)]
+            if let Some(remaining) = &self.provider.yield_after_hashes {
+                // Wraps around after yielding, so that only one hash yields
+                if remaining.fetch_sub(1, core::sync::atomic::Ordering::SeqCst) == 0 {
+                    let mut yielded = false;
+
+                    futures::future::poll_fn(|cx| {
+                        if yielded {
+                            core::task::Poll::Ready(())
+                        } else {
+                            yielded = true;
+                            cx.waker().wake_by_ref();
+                            core::task::Poll::Pending
+                        }
+                    })
+                    .await;
+                }
+            }
+
+            self.inner.hash(data).await
+        }
+
+        async fn mac(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+            self.inner.mac(key, data).await
+        }
+
+        async fn aead_seal(
+            &self,
+            key: &[u8],
+            data: &[u8],
+            aad: Option<&[u8]>,
+            nonce: &[u8],
+        ) -> Result<Vec<u8>, Self::Error> {
+            self.inner.aead_seal(key, data, aad, nonce).await
+        }
+
+        async fn aead_open(
+            &self,
+            key: &[u8],
+            ciphertext: &[u8],
+            aad: Option<&[u8]>,
+            nonce: &[u8],
+        ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+            self.inner.aead_open(key, ciphertext, aad, nonce).await
+        }
+
+        fn aead_key_size(&self) -> usize {
+            self.inner.aead_key_size()
+        }
+
+        fn aead_nonce_size(&self) -> usize {
+            self.inner.aead_nonce_size()
+        }
+
+        #[cfg(feature = "experimental_aead_separation")]
+        async fn aead_seal_in(
+            &self,
+            context: AeadContext,
+            key: &[u8],
+            data: &[u8],
+            aad: Option<&[u8]>,
+            nonce: &[u8],
+        ) -> Result<Vec<u8>, Self::Error> {
+            self.aead(context).aead_seal(key, data, aad, nonce).await
+        }
+
+        #[cfg(feature = "experimental_aead_separation")]
+        async fn aead_open_in(
+            &self,
+            context: AeadContext,
+            key: &[u8],
+            ciphertext: &[u8],
+            aad: Option<&[u8]>,
+            nonce: &[u8],
+        ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+            self.aead(context)
+                .aead_open(key, ciphertext, aad, nonce)
+                .await
+        }
+
+        #[cfg(feature = "experimental_aead_separation")]
+        fn aead_key_size_in(&self, context: AeadContext) -> usize {
+            self.aead(context).aead_key_size()
+        }
+
+        #[cfg(feature = "experimental_aead_separation")]
+        fn aead_nonce_size_in(&self, context: AeadContext) -> usize {
+            self.aead(context).aead_nonce_size()
+        }
+
+        async fn kdf_extract(
+            &self,
+            salt: &[u8],
+            ikm: &[u8],
+        ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+            self.inner.kdf_extract(salt, ikm).await
+        }
+
+        async fn kdf_expand(
+            &self,
+            prk: &[u8],
+            info: &[u8],
+            len: usize,
+        ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+            self.inner.kdf_expand(prk, info, len).await
+        }
+
+        fn kdf_extract_size(&self) -> usize {
+            self.inner.kdf_extract_size()
+        }
+
+        async fn hpke_seal(
+            &self,
+            remote_key: &HpkePublicKey,
+            info: &[u8],
+            aad: Option<&[u8]>,
+            pt: &[u8],
+        ) -> Result<HpkeCiphertext, Self::Error> {
+            self.inner.hpke_seal(remote_key, info, aad, pt).await
+        }
+
+        async fn hpke_open(
+            &self,
+            ciphertext: &HpkeCiphertext,
+            local_secret: &HpkeSecretKey,
+            local_public: &HpkePublicKey,
+            info: &[u8],
+            aad: Option<&[u8]>,
+        ) -> Result<Vec<u8>, Self::Error> {
+            self.inner
+                .hpke_open(ciphertext, local_secret, local_public, info, aad)
+                .await
+        }
+
+        async fn hpke_setup_s(
+            &self,
+            remote_key: &HpkePublicKey,
+            info: &[u8],
+        ) -> Result<(Vec<u8>, Self::HpkeContextS), Self::Error> {
+            self.inner.hpke_setup_s(remote_key, info).await
+        }
+
+        async fn hpke_setup_r(
+            &self,
+            kem_output: &[u8],
+            local_secret: &HpkeSecretKey,
+            local_public: &HpkePublicKey,
+            info: &[u8],
+        ) -> Result<Self::HpkeContextR, Self::Error> {
+            self.inner
+                .hpke_setup_r(kem_output, local_secret, local_public, info)
+                .await
+        }
+
+        async fn kem_derive(
+            &self,
+            ikm: &[u8],
+        ) -> Result<(HpkeSecretKey, HpkePublicKey), Self::Error> {
+            self.inner.kem_derive(ikm).await
+        }
+
+        async fn kem_generate(&self) -> Result<(HpkeSecretKey, HpkePublicKey), Self::Error> {
+            self.inner.kem_generate().await
+        }
+
+        fn kem_public_key_validate(&self, key: &HpkePublicKey) -> Result<(), Self::Error> {
+            self.inner.kem_public_key_validate(key)
+        }
+
+        fn random_bytes(&self, out: &mut [u8]) -> Result<(), Self::Error> {
+            match self.provider.fixed_random {
+                Some(byte) => {
+                    out.fill(byte);
+                    Ok(())
+                }
+                None => self.inner.random_bytes(out),
+            }
+        }
+
+        async fn signature_key_generate(
+            &self,
+        ) -> Result<(SignatureSecretKey, SignaturePublicKey), Self::Error> {
+            self.inner.signature_key_generate().await
+        }
+
+        async fn signature_key_derive_public(
+            &self,
+            secret_key: &SignatureSecretKey,
+        ) -> Result<SignaturePublicKey, Self::Error> {
+            self.inner.signature_key_derive_public(secret_key).await
+        }
+
+        async fn sign(
+            &self,
+            secret_key: &SignatureSecretKey,
+            data: &[u8],
+        ) -> Result<Vec<u8>, Self::Error> {
+            self.inner.sign(secret_key, data).await
+        }
+
+        async fn verify(
+            &self,
+            public_key: &SignaturePublicKey,
+            signature: &[u8],
+            data: &[u8],
+        ) -> Result<(), Self::Error> {
+            self.inner.verify(public_key, signature, data).await
+        }
+
+        #[cfg(feature = "experimental_sign_label_prefix")]
+        fn sign_label_prefix(&self) -> &[u8] {
+            self.provider.sign_label_prefix
+        }
+    }
+}
+
+#[cfg(all(
+    test,
+    any(
+        feature = "experimental_aead_separation",
+        feature = "experimental_sign_label_prefix"
+    ),
+    not(target_arch = "wasm32")
+))]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use crate::{
+        client::{
+            test_utils::{TestClientBuilder, TEST_CIPHER_SUITE},
+            MlsError,
+        },
+        identity::test_utils::get_test_signing_identity,
+    };
+
+    use super::test_utils::ModifiedCryptoProvider;
+
+    #[cfg(feature = "experimental_aead_separation")]
+    use mls_rs_core::crypto::CipherSuite;
+
+    #[cfg(feature = "experimental_aead_separation")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn separate_aead_clients_interoperate_only_with_each_other() {
+        let crypto_provider = ModifiedCryptoProvider {
+            hpke_aead: Some(CipherSuite::P384_AES256),
+            ..Default::default()
+        };
+
+        let (alice_id, alice_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+        let (bob_id, bob_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let alice = TestClientBuilder::new_for_test()
+            .crypto_provider(crypto_provider.clone())
+            .signing_identity(alice_id, alice_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let bob = TestClientBuilder::new_for_test()
+            .crypto_provider(crypto_provider)
+            .signing_identity(bob_id, bob_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let carol = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("carol", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let mut alice_group = alice
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let bob_key_package = bob
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let commit = alice_group
+            .commit_builder()
+            .add_member(bob_key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice_group.apply_pending_commit().await.unwrap();
+
+        let (bob_group, _) = bob
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            bob_group.epoch_authenticator().unwrap(),
+            alice_group.epoch_authenticator().unwrap()
+        );
+
+        let carol_key_package = carol
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let commit = alice_group
+            .commit_builder()
+            .add_member(carol_key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let res = carol
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::CryptoProviderError(_)));
+    }
+
+    #[cfg(feature = "experimental_sign_label_prefix")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn prefixed_clients_verify_only_each_other() {
+        let crypto_provider = ModifiedCryptoProvider {
+            sign_label_prefix: b"example.com ",
+            ..Default::default()
+        };
+
+        let (alice_id, alice_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+        let (bob_id, bob_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let alice = TestClientBuilder::new_for_test()
+            .crypto_provider(crypto_provider.clone())
+            .signing_identity(alice_id, alice_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let bob = TestClientBuilder::new_for_test()
+            .crypto_provider(crypto_provider)
+            .signing_identity(bob_id, bob_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let carol = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("carol", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let mut alice_group = alice
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let bob_key_package = bob
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let commit = alice_group
+            .commit_builder()
+            .add_member(bob_key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice_group.apply_pending_commit().await.unwrap();
+
+        let (bob_group, _) = bob
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            bob_group.epoch_authenticator().unwrap(),
+            alice_group.epoch_authenticator().unwrap()
+        );
+
+        let carol_key_package = carol
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let res = alice_group
+            .commit_builder()
+            .add_member(carol_key_package)
+            .unwrap()
+            .build()
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+    }
 }
@@ -26,6 +26,7 @@ pub(crate) mod test_utils {
     use super::*;
 
     pub const TEST_EXTENSION_TYPE: u16 = 42;
+    pub const TEST_LEAF_EXTENSION_TYPE: u16 = 44;
 
     #[derive(MlsSize, MlsEncode, MlsDecode, Clone, Debug, PartialEq)]
     pub(crate) struct TestExtension {
@@ -55,4 +56,15 @@ pub(crate) mod test_utils {
             Ok(TestExtension { foo: data[0] })
         }
     }
+
+    #[derive(MlsSize, MlsEncode, MlsDecode, Clone, Debug, PartialEq)]
+    pub(crate) struct TestLeafExtension {
+        pub(crate) bar: u8,
+    }
+
+    impl MlsCodecExtension for TestLeafExtension {
+        fn extension_type() -> ExtensionType {
+            ExtensionType::from(TEST_LEAF_EXTENSION_TYPE)
+        }
+    }
 }
@@ -4,7 +4,18 @@
 
 use crate::{
     client::MlsError,
-    group::{framing::MlsMessage, message_processor::validate_key_package, ExportedTree},
+    extension::ExtensionType,
+    group::{
+        cipher_suite_provider,
+        framing::MlsMessage,
+        message_processor::{validate_key_package, validate_key_package_crypto_only},
+        validate_tree_and_info_joiner, ExportedTree,
+    },
+    tree_kem::{
+        leaf_node::LeafNode,
+        leaf_node_validator::LeafNodeValidator,
+        node::{LeafIndex, NodeVec},
+    },
     KeyPackage,
 };
 
@@ -12,16 +23,65 @@ pub mod builder;
 mod config;
 mod group;
 
+use alloc::vec::Vec;
 pub(crate) use config::ExternalClientConfig;
+use mls_rs_codec::MlsDecode;
 use mls_rs_core::{
-    crypto::{CryptoProvider, SignatureSecretKey},
-    identity::SigningIdentity,
+    crypto::{CipherSuite, CryptoProvider, SignatureSecretKey},
+    identity::{MemberValidationContext, SigningIdentity},
+    protocol_version::ProtocolVersion,
 };
 
 use builder::{ExternalBaseConfig, ExternalClientBuilder};
 
 pub use group::{ExternalGroup, ExternalReceivedMessage, ExternalSnapshot};
 
+/// Summary of whether a GroupInfo message allows a new member to join its
+/// group with an external commit, see
+/// [`ExternalClient::external_commit_eligibility`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ExternalCommitEligibility {
+    /// The GroupInfo contains the
+    /// [`ExternalPubExt`](crate::extension::built_in::ExternalPubExt) that is
+    /// required to create an external commit.
+    pub has_external_pub: bool,
+    /// The group context contains the
+    /// [`ExternalSendersExt`](crate::extension::built_in::ExternalSendersExt),
+    /// i.e. external senders are permitted to send proposals to the group.
+    pub external_senders_allowed: bool,
+    /// Cipher suite of the group.
+    pub cipher_suite: CipherSuite,
+    /// Protocol version of the group.
+    pub protocol_version: ProtocolVersion,
+    /// The cipher suite of the group is supported by the crypto provider of
+    /// this client.
+    pub cipher_suite_supported: bool,
+    /// The protocol version of the group is supported by this client.
+    pub protocol_version_supported: bool,
+}
+
+impl ExternalCommitEligibility {
+    /// Returns true if an external commit to the group can be attempted.
+    pub fn is_eligible(&self) -> bool {
+        self.has_external_pub && self.cipher_suite_supported && self.protocol_version_supported
+    }
+}
+
+/// Result of [`ExternalClient::validate_key_package_crypto_only`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct CryptoValidatedKeyPackage {
+    /// The key package whose signatures, cipher suite, protocol version and
+    /// lifetime were verified.
+    pub key_package: KeyPackage,
+    /// Whether the credential of the key package was validated by the
+    /// [`IdentityProvider`](crate::IdentityProvider). Always `false` for a
+    /// crypto-only validation, [`ExternalClient::validate_key_package`] must
+    /// still be used before trusting the identity of the key package.
+    pub identity_validated: bool,
+}
+
 /// A client capable of observing a group's state without having
 /// private keys required to read content.
 ///
@@ -86,6 +146,141 @@ where
         .await
     }
 
+    /// Verify that a GroupInfo message was signed by a current member of
+    /// the group it describes, and return the signing identity of that
+    /// member.
+    ///
+    /// The ratchet tree, taken from `tree_data` or from the `ratchet_tree`
+    /// extension of the GroupInfo, is validated along with the identity of
+    /// every member using the configured
+    /// [`IdentityProvider`](crate::IdentityProvider).
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn validate_group_info(
+        &self,
+        group_info: MlsMessage,
+        tree_data: Option<ExportedTree<'_>>,
+    ) -> Result<SigningIdentity, MlsError> {
+        let protocol_version = group_info.version;
+
+        if !self.config.version_supported(protocol_version) {
+            return Err(MlsError::UnsupportedProtocolVersion(protocol_version));
+        }
+
+        let group_info = group_info
+            .into_group_info()
+            .ok_or(MlsError::UnexpectedMessageType)?;
+
+        let cs = cipher_suite_provider(
+            self.config.crypto_provider(),
+            group_info.group_context.cipher_suite,
+        )?;
+
+        let public_tree = validate_tree_and_info_joiner(
+            protocol_version,
+            &group_info,
+            tree_data,
+            self.config.max_ratchet_tree_size(),
+            &self.config.identity_provider(),
+            &cs,
+        )
+        .await?;
+
+        Ok(public_tree
+            .get_leaf_node(group_info.signer)?
+            .signing_identity
+            .clone())
+    }
+
+    /// Insert leaf nodes exported by members using
+    /// [`Group::export_member_leaf`](crate::group::Group::export_member_leaf)
+    /// into `tree_data`, a ratchet tree of the group described by
+    /// `group_info` that is missing some of its leaves.
+    ///
+    /// Each leaf is validated on its own, checking its signature for the
+    /// given index and the identity of its member using the configured
+    /// [`IdentityProvider`](crate::IdentityProvider). The tree hash is not
+    /// checked as the tree may still be incomplete. Once all leaves are
+    /// imported, the returned tree can be used with
+    /// [`ExternalClient::observe_group`] which verifies it against the group.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn import_member_leaves(
+        &self,
+        group_info: &MlsMessage,
+        tree_data: ExportedTree<'_>,
+        leaves: Vec<(u32, Vec<u8>)>,
+    ) -> Result<ExportedTree<'static>, MlsError> {
+        let group_info = group_info
+            .as_group_info()
+            .ok_or(MlsError::UnexpectedMessageType)?;
+
+        let context = &group_info.group_context;
+        let cs = cipher_suite_provider(self.config.crypto_provider(), context.cipher_suite)?;
+        let identity_provider = self.config.identity_provider();
+
+        let validator = LeafNodeValidator::new(
+            &cs,
+            &identity_provider,
+            MemberValidationContext::ForNewGroup {
+                current_context: context,
+            },
+        );
+
+        let mut nodes = NodeVec::from(tree_data);
+        let leaf_count = nodes.total_leaf_count();
+
+        for (index, leaf_data) in leaves {
+            if index >= leaf_count {
+                return Err(MlsError::LeafNotFound(index));
+            }
+
+            let leaf_node = LeafNode::mls_decode(&mut &*leaf_data)?;
+
+            validator
+                .revalidate(&leaf_node, &context.group_id, index)
+                .await?;
+
+            nodes.insert_leaf(LeafIndex(index), leaf_node);
+        }
+
+        Ok(ExportedTree::new(nodes))
+    }
+
+    /// Report whether the GroupInfo message `group_info` allows joining its
+    /// group with an external commit, e.g. using
+    /// [`Client::external_commit_builder`](crate::Client::external_commit_builder).
+    ///
+    /// This only inspects the content of `group_info`, use
+    /// [`ExternalClient::validate_group_info`] to authenticate it.
+    pub fn external_commit_eligibility(
+        &self,
+        group_info: &MlsMessage,
+    ) -> Result<ExternalCommitEligibility, MlsError> {
+        let protocol_version = group_info.version;
+
+        let group_info = group_info
+            .as_group_info()
+            .ok_or(MlsError::UnexpectedMessageType)?;
+
+        let context = &group_info.group_context;
+
+        Ok(ExternalCommitEligibility {
+            has_external_pub: group_info
+                .extensions
+                .has_extension(ExtensionType::EXTERNAL_PUB),
+            external_senders_allowed: context
+                .extensions
+                .has_extension(ExtensionType::EXTERNAL_SENDERS),
+            cipher_suite: context.cipher_suite,
+            protocol_version,
+            cipher_suite_supported: self
+                .config
+                .crypto_provider()
+                .supported_cipher_suites()
+                .contains(&context.cipher_suite),
+            protocol_version_supported: self.config.version_supported(protocol_version),
+        })
+    }
+
     /// Load an existing observed group by loading a snapshot that was
     /// generated by
     /// [ExternalGroup::snapshot](self::ExternalGroup::snapshot).
@@ -131,11 +326,58 @@ where
 
         let id = self.config.identity_provider();
 
-        validate_key_package(&key_package, version, &cs, &id).await?;
+        validate_key_package(
+            &key_package,
+            version,
+            &cs,
+            &id,
+            &Default::default(),
+            self.config.current_time(),
+        )
+        .await?;
 
         Ok(key_package)
     }
 
+    /// Validate a key package like [`ExternalClient::validate_key_package`]
+    /// except that the credential is not validated by the
+    /// [`IdentityProvider`](crate::IdentityProvider).
+    ///
+    /// This allows cheap triage of key packages, for example when the identity
+    /// provider is not reachable. Signatures, the cipher suite, the protocol
+    /// version and the lifetime of the key package are still verified.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn validate_key_package_crypto_only(
+        &self,
+        key_package: MlsMessage,
+    ) -> Result<CryptoValidatedKeyPackage, MlsError> {
+        let version = key_package.version;
+
+        let key_package = key_package
+            .into_key_package()
+            .ok_or(MlsError::UnexpectedMessageType)?;
+
+        let cs = self
+            .config
+            .crypto_provider()
+            .cipher_suite_provider(key_package.cipher_suite)
+            .ok_or(MlsError::UnsupportedCipherSuite(key_package.cipher_suite))?;
+
+        validate_key_package_crypto_only(
+            &key_package,
+            version,
+            &cs,
+            &self.config.identity_provider(),
+            self.config.current_time(),
+        )
+        .await?;
+
+        Ok(CryptoValidatedKeyPackage {
+            key_package,
+            identity_validated: false,
+        })
+    }
+
     /// The [IdentityProvider](crate::IdentityProvider) that this client was configured to use.
     pub fn identity_provider(&self) -> <C as ExternalClientConfig>::IdentityProvider {
         self.config.identity_provider()
@@ -144,13 +386,34 @@ where
 
 #[cfg(test)]
 pub(crate) mod tests_utils {
+    use assert_matches::assert_matches;
+
     use crate::{
-        client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        client::{
+            test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+            MlsError,
+        },
+        crypto::test_utils::test_cipher_suite_provider,
+        group::{
+            framing::MlsMessagePayload,
+            test_utils::{test_group, test_n_member_group},
+            ExportedTree, GroupInfo,
+        },
+        identity::test_utils::get_test_signing_identity,
         key_package::test_utils::test_key_package_message,
+        signer::Signable,
+        time::{Clock, MlsTime},
+        tree_kem::node::{LeafIndex, NodeVec},
+        MlsMessage,
     };
 
     pub use super::builder::test_utils::*;
 
+    use super::builder::ExternalClientBuilder;
+    use crate::crypto::test_utils::TestCryptoProvider;
+    use crate::tree_kem::leaf_node_validator::test_utils::FailureIdentityProvider;
+    use alloc::vec;
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn external_client_can_validate_key_package() {
         let kp = test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "john").await;
@@ -159,4 +422,260 @@ pub(crate) mod tests_utils {
 
         assert_eq!(kp.into_key_package().unwrap(), validated_kp);
     }
+
+    #[derive(Clone)]
+    struct FixedClock(MlsTime);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> MlsTime {
+            self.0
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_client_validates_key_package_lifetime_with_clock() {
+        let kp = test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "john").await;
+
+        let far_future = MlsTime::now().seconds_since_epoch() + 10 * 365 * 24 * 3600;
+
+        let server = TestExternalClientBuilder::new_for_test()
+            .clock(FixedClock(MlsTime::from(far_future)))
+            .build();
+
+        let res = server.validate_key_package(kp.clone()).await.map(|_| ());
+        assert_matches!(res, Err(MlsError::InvalidLifetime));
+
+        let res = server
+            .validate_key_package_crypto_only(kp)
+            .await
+            .map(|_| ());
+        assert_matches!(res, Err(MlsError::InvalidLifetime));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn crypto_only_validation_skips_identity_provider() {
+        let kp = test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "john").await;
+
+        let server = ExternalClientBuilder::new()
+            .crypto_provider(TestCryptoProvider::new())
+            .identity_provider(FailureIdentityProvider)
+            .build();
+
+        let res = server.validate_key_package(kp.clone()).await.map(|_| ());
+        assert_matches!(res, Err(MlsError::IdentityProviderError(_)));
+
+        let validated = server
+            .validate_key_package_crypto_only(kp.clone())
+            .await
+            .unwrap();
+
+        assert!(!validated.identity_validated);
+        assert_eq!(
+            validated.key_package,
+            kp.clone().into_key_package().unwrap()
+        );
+
+        let mut tampered = kp.into_key_package().unwrap();
+        tampered.signature = vec![0; tampered.signature.len()];
+
+        let res = server
+            .validate_key_package_crypto_only(MlsMessage::new(
+                TEST_PROTOCOL_VERSION,
+                MlsMessagePayload::KeyPackage(tampered),
+            ))
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_client_can_validate_group_info() {
+        let alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let group_info = alice.group_info_message(true).await.unwrap();
+        let server = TestExternalClientBuilder::new_for_test().build();
+
+        let signer = server.validate_group_info(group_info, None).await.unwrap();
+
+        assert_eq!(&signer, alice.current_member_signing_identity().unwrap());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_client_rejects_group_info_from_non_member() {
+        let alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let server = TestExternalClientBuilder::new_for_test().build();
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        let mut group_info = alice
+            .group_info_message(true)
+            .await
+            .unwrap()
+            .into_group_info()
+            .unwrap();
+
+        // Sign as leaf 0 with a key that does not belong to the member at leaf 0
+        let (_, mallory_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"mallory").await;
+        group_info.sign(&cs, &mallory_key, &()).await.unwrap();
+
+        let res = server
+            .validate_group_info(group_info_message(group_info.clone()), None)
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+
+        // Claim to be signed by a leaf that is not in the tree
+        group_info.signer = LeafIndex(3);
+        group_info.sign(&cs, &mallory_key, &()).await.unwrap();
+
+        let res = server
+            .validate_group_info(group_info_message(group_info), None)
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::InvalidNodeIndex(6)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_commit_eligibility_requires_external_pub() {
+        let alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let server = TestExternalClientBuilder::new_for_test().build();
+
+        let group_info = alice
+            .group_info_message_allowing_ext_commit(true)
+            .await
+            .unwrap();
+
+        let eligibility = server.external_commit_eligibility(&group_info).unwrap();
+
+        assert!(eligibility.has_external_pub);
+        assert!(!eligibility.external_senders_allowed);
+        assert_eq!(eligibility.cipher_suite, TEST_CIPHER_SUITE);
+        assert_eq!(eligibility.protocol_version, TEST_PROTOCOL_VERSION);
+        assert!(eligibility.is_eligible());
+
+        let group_info = alice.group_info_message(true).await.unwrap();
+        let eligibility = server.external_commit_eligibility(&group_info).unwrap();
+
+        assert!(!eligibility.has_external_pub);
+        assert!(!eligibility.is_eligible());
+
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "john").await;
+
+        let res = server.external_commit_eligibility(&key_package);
+        assert_matches!(res, Err(MlsError::UnexpectedMessageType));
+    }
+
+    fn group_info_message(group_info: GroupInfo) -> MlsMessage {
+        MlsMessage::new(
+            TEST_PROTOCOL_VERSION,
+            MlsMessagePayload::GroupInfo(group_info),
+        )
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn observer_missing_leaves_can_catch_up() {
+        let groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+        let alice = &groups[0];
+
+        let group_info = alice.group_info_message(false).await.unwrap();
+
+        let mut partial_tree = NodeVec::from(alice.export_tree().into_owned());
+        partial_tree.blank_leaf_node(LeafIndex(1)).unwrap();
+        partial_tree.blank_leaf_node(LeafIndex(2)).unwrap();
+        let partial_tree = ExportedTree::new(partial_tree);
+
+        let server = TestExternalClientBuilder::new_for_test().build();
+
+        let res = server
+            .observe_group(group_info.clone(), Some(partial_tree.clone()))
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::TreeHashMismatch));
+
+        // Leaves arriving separately are imported into the partial tree.
+        let tree = server
+            .import_member_leaves(
+                &group_info,
+                partial_tree.clone(),
+                vec![(1, alice.export_member_leaf(1).unwrap())],
+            )
+            .await
+            .unwrap();
+
+        let res = server
+            .observe_group(group_info.clone(), Some(tree.clone()))
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::TreeHashMismatch));
+
+        let tree = server
+            .import_member_leaves(
+                &group_info,
+                tree,
+                vec![(2, alice.export_member_leaf(2).unwrap())],
+            )
+            .await
+            .unwrap();
+
+        let observer = server.observe_group(group_info, Some(tree)).await.unwrap();
+
+        assert_eq!(
+            observer.export_tree().unwrap(),
+            alice.export_tree().to_bytes().unwrap()
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn imported_member_leaves_are_validated() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        // Alice's leaf is signed for her own index after she commits.
+        groups[0].commit(vec![]).await.unwrap();
+        groups[0].apply_pending_commit().await.unwrap();
+
+        let alice = &groups[0];
+
+        let group_info = alice.group_info_message(false).await.unwrap();
+        let tree = alice.export_tree().into_owned();
+        let server = TestExternalClientBuilder::new_for_test().build();
+
+        let res = server
+            .import_member_leaves(
+                &group_info,
+                tree.clone(),
+                vec![(1, alice.export_member_leaf(0).unwrap())],
+            )
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+
+        let res = server
+            .import_member_leaves(
+                &group_info,
+                tree.clone(),
+                vec![(4, alice.export_member_leaf(1).unwrap())],
+            )
+            .await;
+
+        assert_matches!(res, Err(MlsError::LeafNotFound(4)));
+
+        let server = ExternalClientBuilder::new()
+            .crypto_provider(TestCryptoProvider::new())
+            .identity_provider(FailureIdentityProvider)
+            .build();
+
+        let res = server
+            .import_member_leaves(
+                &group_info,
+                tree,
+                vec![(1, alice.export_member_leaf(1).unwrap())],
+            )
+            .await;
+
+        assert_matches!(res, Err(MlsError::IdentityProviderError(_)));
+    }
 }
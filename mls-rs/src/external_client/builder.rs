@@ -6,7 +6,10 @@
 //!
 //! See [`ExternalClientBuilder`].
 
+#[cfg(feature = "by_ref_proposal")]
+use crate::group::proposal_ref::PROPOSAL_REF_LABEL;
 use crate::{
+    client_config::ClockRef,
     crypto::SignaturePublicKey,
     extension::ExtensionType,
     external_client::{ExternalClient, ExternalClientConfig},
@@ -16,9 +19,11 @@ use crate::{
     },
     identity::CredentialType,
     protocol_version::ProtocolVersion,
+    time::{Clock, MlsTime},
     tree_kem::Capabilities,
     CryptoProvider, Sealed,
 };
+
 use std::{
     collections::HashMap,
     fmt::{self, Debug},
@@ -198,6 +203,19 @@ impl<C: IntoConfig> ExternalClientBuilder<C> {
         ExternalClientBuilder(c)
     }
 
+    /// Set the maximum size in bytes of a ratchet tree embedded in a group info
+    /// when observing a group.
+    ///
+    /// Larger trees are rejected before being parsed. By default, there is no limit.
+    pub fn max_ratchet_tree_size(
+        self,
+        max_size: usize,
+    ) -> ExternalClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.max_ratchet_tree_size = Some(max_size);
+        ExternalClientBuilder(c)
+    }
+
     /// Specify whether processed proposals should be cached by the external group. In case they
     /// are not cached by the group, they should be cached externally and inserted using
     /// `ExternalGroup::insert_proposal` before processing the next commit.
@@ -210,6 +228,18 @@ impl<C: IntoConfig> ExternalClientBuilder<C> {
         ExternalClientBuilder(c)
     }
 
+    /// Set the label used to compute proposal references.
+    ///
+    /// By default, the label `"MLS 1.0 Proposal Reference"` defined by RFC 9420 is used.
+    /// Changing it is **not compliant** with RFC 9420 and must match the label configured
+    /// on the members of the observed group.
+    #[cfg(feature = "by_ref_proposal")]
+    pub fn proposal_ref_label(self, label: Vec<u8>) -> ExternalClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.proposal_ref_label = label;
+        ExternalClientBuilder(c)
+    }
+
     /// Set the identity validator to be used by the client.
     pub fn identity_provider<I>(
         self,
@@ -269,6 +299,19 @@ impl<C: IntoConfig> ExternalClientBuilder<C> {
         }))
     }
 
+    /// Set the [`Clock`] used for time dependent checks such as validating
+    /// the lifetime of key packages.
+    ///
+    /// The system clock is used by default.
+    pub fn clock<T>(self, clock: T) -> ExternalClientBuilder<IntoConfigOutput<C>>
+    where
+        T: Clock + Clone + 'static,
+    {
+        let mut c = self.0.into_config();
+        c.0.settings.clock = Some(ClockRef::new(clock));
+        ExternalClientBuilder(c)
+    }
+
     /// Set the signature secret key used by the client to send external proposals.
     pub fn signer(
         self,
@@ -378,10 +421,23 @@ where
         self.settings.max_epoch_jitter
     }
 
+    fn max_ratchet_tree_size(&self) -> Option<usize> {
+        self.settings.max_ratchet_tree_size
+    }
+
     fn cache_proposals(&self) -> bool {
         self.settings.cache_proposals
     }
 
+    fn clock(&self) -> Option<ClockRef> {
+        self.settings.clock.clone()
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    fn proposal_ref_label(&self) -> Vec<u8> {
+        self.settings.proposal_ref_label.clone()
+    }
+
     fn supported_custom_proposals(&self) -> Vec<ProposalType> {
         self.settings.custom_proposal_types.clone()
     }
@@ -453,10 +509,27 @@ impl<T: MlsConfig> ExternalClientConfig for T {
         self.get().cache_proposals()
     }
 
+    #[cfg(feature = "by_ref_proposal")]
+    fn proposal_ref_label(&self) -> Vec<u8> {
+        self.get().proposal_ref_label()
+    }
+
     fn max_epoch_jitter(&self) -> Option<u64> {
         self.get().max_epoch_jitter()
     }
 
+    fn max_ratchet_tree_size(&self) -> Option<usize> {
+        self.get().max_ratchet_tree_size()
+    }
+
+    fn clock(&self) -> Option<ClockRef> {
+        self.get().clock()
+    }
+
+    fn current_time(&self) -> Option<MlsTime> {
+        self.get().current_time()
+    }
+
     fn capabilities(&self) -> Capabilities {
         self.get().capabilities()
     }
@@ -477,12 +550,18 @@ pub(crate) struct Settings {
     pub(crate) protocol_versions: Vec<ProtocolVersion>,
     pub(crate) external_signing_keys: HashMap<Vec<u8>, SignaturePublicKey>,
     pub(crate) max_epoch_jitter: Option<u64>,
+    pub(crate) max_ratchet_tree_size: Option<usize>,
     pub(crate) cache_proposals: bool,
+    #[cfg(feature = "by_ref_proposal")]
+    pub(crate) proposal_ref_label: Vec<u8>,
+    pub(crate) clock: Option<ClockRef>,
 }
 
 impl Debug for Settings {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Settings")
+        let mut settings = f.debug_struct("Settings");
+
+        settings
             .field("extension_types", &self.extension_types)
             .field("custom_proposal_types", &self.custom_proposal_types)
             .field("protocol_versions", &self.protocol_versions)
@@ -499,8 +578,17 @@ impl Debug for Settings {
                 }),
             )
             .field("max_epoch_jitter", &self.max_epoch_jitter)
+            .field("max_ratchet_tree_size", &self.max_ratchet_tree_size)
             .field("cache_proposals", &self.cache_proposals)
-            .finish()
+            .field("clock", &self.clock);
+
+        #[cfg(feature = "by_ref_proposal")]
+        settings.field(
+            "proposal_ref_label",
+            &mls_rs_core::debug::pretty_bytes(&self.proposal_ref_label),
+        );
+
+        settings.finish()
     }
 }
 
@@ -512,7 +600,11 @@ impl Default for Settings {
             protocol_versions: vec![],
             external_signing_keys: Default::default(),
             max_epoch_jitter: None,
+            max_ratchet_tree_size: None,
             custom_proposal_types: vec![],
+            #[cfg(feature = "by_ref_proposal")]
+            proposal_ref_label: PROPOSAL_REF_LABEL.to_vec(),
+            clock: None,
         }
     }
 }
@@ -5,11 +5,13 @@
 use mls_rs_core::identity::IdentityProvider;
 
 use crate::{
+    client_config::ClockRef,
     crypto::SignaturePublicKey,
     extension::ExtensionType,
     group::{mls_rules::MlsRules, proposal::ProposalType},
     identity::CredentialType,
     protocol_version::ProtocolVersion,
+    time::MlsTime,
     tree_kem::Capabilities,
     CryptoProvider,
 };
@@ -30,10 +32,30 @@ pub trait ExternalClientConfig: Send + Sync + Clone {
 
     fn cache_proposals(&self) -> bool;
 
+    #[cfg(feature = "by_ref_proposal")]
+    fn proposal_ref_label(&self) -> Vec<u8>;
+
     fn max_epoch_jitter(&self) -> Option<u64> {
         None
     }
 
+    fn max_ratchet_tree_size(&self) -> Option<usize> {
+        None
+    }
+
+    fn clock(&self) -> Option<ClockRef> {
+        None
+    }
+
+    /// Time used for time dependent checks, read from the configured
+    /// [`Clock`](crate::time::Clock) or from the system clock if none is
+    /// configured.
+    fn current_time(&self) -> Option<MlsTime> {
+        self.clock()
+            .map(|clock| clock.now())
+            .or_else(crate::time::system_time)
+    }
+
     fn capabilities(&self) -> Capabilities {
         Capabilities {
             protocol_versions: self.supported_protocol_versions(),
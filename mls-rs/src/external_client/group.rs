@@ -15,7 +15,7 @@ use crate::{
     group::{
         cipher_suite_provider,
         confirmation_tag::ConfirmationTag,
-        framing::PublicMessage,
+        framing::{Content, MlsMessagePayload, PublicMessage},
         member_from_leaf_node,
         message_processor::{
             ApplicationMessageDescription, CommitMessageDescription, EventOrContent,
@@ -32,6 +32,7 @@ use crate::{
     identity::SigningIdentity,
     protocol_version::ProtocolVersion,
     psk::AlwaysFoundPskStorage,
+    time::MlsTime,
     tree_kem::{node::LeafIndex, path_secret::PathSecret, TreeKemPrivate},
     CryptoProvider, KeyPackage, MlsMessage,
 };
@@ -39,12 +40,8 @@ use crate::{
 #[cfg(feature = "by_ref_proposal")]
 use crate::{
     group::{
-        framing::{Content, MlsMessagePayload},
-        message_processor::CachedProposal,
-        message_signature::AuthenticatedContent,
-        proposal::Proposal,
-        proposal_ref::ProposalRef,
-        Sender,
+        message_processor::CachedProposal, message_signature::AuthenticatedContent,
+        proposal::Proposal, proposal_ref::ProposalRef, Sender,
     },
     WireFormat,
 };
@@ -133,6 +130,7 @@ impl<C: ExternalClientConfig + Clone> ExternalGroup<C> {
             protocol_version,
             &group_info,
             tree_data,
+            config.max_ratchet_tree_size(),
             &config.identity_provider(),
             &cipher_suite_provider,
         )
@@ -191,6 +189,53 @@ impl<C: ExternalClientConfig + Clone> ExternalGroup<C> {
         .await
     }
 
+    /// Verify that a plaintext commit message is well formed for the current
+    /// epoch without applying it to the group state.
+    ///
+    /// This checks the signature of the commit against the leaf of its sender,
+    /// resolves the proposals it references and validates its update path if
+    /// present. The confirmation tag is not verified since it requires the
+    /// group secrets, so passing this check does not guarantee that members
+    /// will accept the commit.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn verify_commit_structure(&self, message: MlsMessage) -> Result<(), MlsError> {
+        self.check_metadata(&message)?;
+
+        let plaintext = match message.payload {
+            MlsMessagePayload::Plain(plaintext)
+                if plaintext.content.content_type() == ContentType::Commit =>
+            {
+                Ok(plaintext)
+            }
+            _ => Err(MlsError::UnexpectedMessageType),
+        }?;
+
+        if self.state.pending_reinit.is_some() {
+            return Err(MlsError::GroupUsedAfterReInit);
+        }
+
+        let auth_content = crate::group::message_verifier::verify_plaintext_authentication(
+            &self.cipher_suite_provider,
+            plaintext,
+            None,
+            &self.state,
+        )
+        .await?;
+
+        #[cfg(any(feature = "private_message", feature = "by_ref_proposal"))]
+        let commit = match auth_content.content.content {
+            Content::Commit(commit) => Ok(commit),
+            _ => Err(MlsError::UnexpectedMessageType),
+        }?;
+
+        #[cfg(not(any(feature = "private_message", feature = "by_ref_proposal")))]
+        let Content::Commit(commit) = auth_content.content.content;
+
+        self.validate_commit(auth_content.content.sender, *commit, None)
+            .await
+            .map(|_| ())
+    }
+
     /// Replay a proposal message into the group skipping all validation steps.
     #[cfg(feature = "by_ref_proposal")]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -205,10 +250,15 @@ impl<C: ExternalClientConfig + Clone> ExternalGroup<C> {
 
         let auth_content: AuthenticatedContent = ptxt.into();
 
-        let proposal_ref =
-            ProposalRef::from_content(&self.cipher_suite_provider, &auth_content).await?;
+        let proposal_ref = ProposalRef::from_content_with_label(
+            &self.cipher_suite_provider,
+            &auth_content,
+            &self.config.proposal_ref_label(),
+        )
+        .await?;
 
         let sender = auth_content.content.sender;
+        let authenticated_data = auth_content.content.authenticated_data;
 
         let proposal = match auth_content.content.content {
             Content::Proposal(p) => Ok(*p),
@@ -217,7 +267,7 @@ impl<C: ExternalClientConfig + Clone> ExternalGroup<C> {
 
         self.group_state_mut()
             .proposals
-            .insert(proposal_ref, proposal, sender);
+            .insert(proposal_ref, proposal, sender, authenticated_data);
 
         Ok(())
     }
@@ -230,6 +280,7 @@ impl<C: ExternalClientConfig + Clone> ExternalGroup<C> {
             proposal.proposal_ref,
             proposal.proposal,
             proposal.sender,
+            proposal.authenticated_data,
         )
     }
 
@@ -457,8 +508,14 @@ impl<C: ExternalClientConfig + Clone> ExternalGroup<C> {
         )
         .await?;
 
-        let proposal_ref =
-            ProposalRef::from_content(&self.cipher_suite_provider, &auth_content).await?;
+        let proposal_ref = ProposalRef::from_content_with_label(
+            &self.cipher_suite_provider,
+            &auth_content,
+            &self.config.proposal_ref_label(),
+        )
+        .await?;
+
+        let authenticated_data = auth_content.content.authenticated_data.clone();
 
         let plaintext = PublicMessage {
             content: auth_content.content,
@@ -471,7 +528,9 @@ impl<C: ExternalClientConfig + Clone> ExternalGroup<C> {
             MlsMessagePayload::Plain(plaintext),
         );
 
-        self.state.proposals.insert(proposal_ref, proposal, sender);
+        self.state
+            .proposals
+            .insert(proposal_ref, proposal, sender, authenticated_data);
 
         Ok(message)
     }
@@ -632,6 +691,15 @@ where
         AlwaysFoundPskStorage
     }
 
+    #[cfg(feature = "by_ref_proposal")]
+    fn proposal_ref_label(&self) -> Vec<u8> {
+        self.config.proposal_ref_label()
+    }
+
+    fn current_time(&self) -> Option<MlsTime> {
+        self.config.current_time()
+    }
+
     fn group_state(&self) -> &GroupState {
         &self.state
     }
@@ -660,13 +728,26 @@ where
 }
 
 /// Serializable snapshot of an [ExternalGroup](ExternalGroup) state.
-#[derive(Debug, MlsEncode, MlsSize, MlsDecode, PartialEq, Clone)]
+#[derive(Debug, MlsEncode, MlsSize, PartialEq, Clone)]
 pub struct ExternalSnapshot {
     version: u16,
+    // Starting with version 2, cached proposals include their authenticated data
     pub(crate) state: RawGroupState,
     signing_data: Option<(SignatureSecretKey, SigningIdentity)>,
 }
 
+impl MlsDecode for ExternalSnapshot {
+    fn mls_decode(reader: &mut &[u8]) -> Result<Self, mls_rs_codec::Error> {
+        let version = u16::mls_decode(reader)?;
+
+        Ok(Self {
+            version,
+            state: RawGroupState::mls_decode_versioned(reader, version < 2)?,
+            signing_data: MlsDecode::mls_decode(reader)?,
+        })
+    }
+}
+
 impl ExternalSnapshot {
     /// Serialize the snapshot
     pub fn to_bytes(&self) -> Result<Vec<u8>, MlsError> {
@@ -682,6 +763,14 @@ impl ExternalSnapshot {
     pub fn context(&self) -> &GroupContext {
         &self.state.context
     }
+
+    pub(crate) fn from_raw_state(state: RawGroupState) -> Self {
+        Self {
+            version: 2,
+            state,
+            signing_data: None,
+        }
+    }
 }
 
 impl<C> ExternalGroup<C>
@@ -692,7 +781,7 @@ where
     pub fn snapshot(&self) -> ExternalSnapshot {
         ExternalSnapshot {
             state: RawGroupState::export(self.group_state()),
-            version: 1,
+            version: 2,
             signing_data: self.signing_data.clone(),
         }
     }
@@ -705,7 +794,7 @@ where
 
         let snapshot = ExternalSnapshot {
             state: RawGroupState::export(&self.state),
-            version: 1,
+            version: 2,
             signing_data: self.signing_data.clone(),
         };
 
@@ -840,7 +929,7 @@ mod tests {
             message_processor::CommitEffect,
             proposal::{AddProposal, Proposal, ProposalOrRef},
             proposal_ref::ProposalRef,
-            test_utils::{test_group, TestGroup},
+            test_utils::{test_group, test_group_custom_config, TestGroup},
             CommitMessageDescription, ExportedTree, ProposalMessageDescription,
         },
         identity::{test_utils::get_test_signing_identity, SigningIdentity},
@@ -916,6 +1005,32 @@ mod tests {
         assert_eq!(alice.state, server.state);
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_group_can_be_loaded_from_member_snapshot() {
+        let mut alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let snapshot = alice.to_external_snapshot();
+        let snapshot = ExternalSnapshot::from_bytes(&snapshot.to_bytes().unwrap()).unwrap();
+
+        assert!(snapshot.signing_data.is_none());
+
+        let mut server = TestExternalClientBuilder::new_for_test()
+            .build()
+            .load_group(snapshot)
+            .await
+            .unwrap();
+
+        let commit_output = alice.commit(Vec::new()).await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
+
+        server
+            .process_incoming_message(commit_output.commit_message)
+            .await
+            .unwrap();
+
+        assert_eq!(alice.state, server.state);
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn external_group_can_process_proposals_by_reference() {
         let mut alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
@@ -962,6 +1077,56 @@ mod tests {
         assert_eq!(alice.state, server.state);
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_group_uses_custom_proposal_ref_label() {
+        let label = b"custom proposal reference".to_vec();
+
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.proposal_ref_label(label.clone())
+        })
+        .await;
+
+        let config = TestExternalClientBuilder::new_for_test()
+            .proposal_ref_label(label.clone())
+            .build_config();
+
+        let mut server = make_external_group_with_config(&alice, config).await;
+
+        let bob_key_package =
+            test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let packet = alice
+            .propose(Proposal::Add(Box::new(AddProposal {
+                key_package: bob_key_package,
+            })))
+            .await;
+
+        let expected_ref = packet
+            .clone()
+            .into_proposal_reference_with_label(&server.cipher_suite_provider, &label)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let ExternalReceivedMessage::Proposal(proposal_desc) =
+            server.process_incoming_message(packet).await.unwrap()
+        else {
+            panic!("expected proposal message")
+        };
+
+        assert_eq!(proposal_desc.proposal_ref.to_vec(), expected_ref);
+
+        let commit_output = alice.commit(vec![]).await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
+
+        server
+            .process_incoming_message(commit_output.commit_message)
+            .await
+            .unwrap();
+
+        assert_eq!(alice.state, server.state);
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn external_group_can_process_commit_adding_member() {
         let mut alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
@@ -1008,7 +1173,13 @@ mod tests {
             .process_incoming_message(commit_output.commit_message)
             .await;
 
-        assert_matches!(res, Err(MlsError::InvalidEpoch));
+        assert_matches!(
+            res,
+            Err(MlsError::CommitEpochMismatch {
+                current: 1,
+                commit: 0
+            })
+        );
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
@@ -1035,6 +1206,37 @@ mod tests {
         assert_matches!(res, Err(MlsError::InvalidSignature));
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_group_can_verify_commit_structure() {
+        let mut alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let mut server = make_external_group(&alice).await;
+
+        let commit_output = alice.commit(Vec::new()).await.unwrap();
+        let state_before = server.state.clone();
+
+        server
+            .verify_commit_structure(commit_output.commit_message.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(server.state, state_before);
+
+        let mut bad_commit = commit_output.commit_message.clone();
+
+        match bad_commit.payload {
+            MlsMessagePayload::Plain(ref mut plain) => plain.auth.signature = Vec::new().into(),
+            _ => panic!("Unexpected non-plaintext data"),
+        };
+
+        let res = server.verify_commit_structure(bad_commit).await;
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+
+        server
+            .process_incoming_message(commit_output.commit_message)
+            .await
+            .unwrap();
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn external_group_rejects_unencrypted_application_message() {
         let mut alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
@@ -1183,6 +1385,37 @@ mod tests {
         test_external_proposal(&mut server, &mut alice, external_proposal).await
     }
 
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_sender_added_by_commit_can_propose() {
+        let (server_identity, server_key, mut alice) = setup_extern_proposal_test(false).await;
+
+        assert!(alice.external_senders().unwrap().is_empty());
+
+        alice
+            .commit_builder()
+            .set_external_senders(vec![server_identity.clone()])
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.process_pending_commit().await.unwrap();
+
+        assert_eq!(
+            alice.external_senders().unwrap(),
+            vec![server_identity.clone()]
+        );
+
+        let mut server = make_external_group(&alice).await;
+
+        server.signing_data = Some((server_key, server_identity));
+
+        let external_proposal = server.propose_remove(1, vec![]).await.unwrap();
+
+        test_external_proposal(&mut server, &mut alice, external_proposal).await
+    }
+
     #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn external_group_can_propose_remove() {
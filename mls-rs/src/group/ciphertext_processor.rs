@@ -39,6 +39,54 @@ pub(crate) trait GroupStateProvider {
     fn self_index(&self) -> LeafIndex;
     fn epoch_secrets_mut(&mut self) -> &mut EpochSecrets;
     fn epoch_secrets(&self) -> &EpochSecrets;
+
+    #[cfg(any(test, feature = "test_util"))]
+    fn encryption_modifiers(&self) -> EncryptionModifiers {
+        Default::default()
+    }
+}
+
+/// Hooks used to tamper with messages right before they are encrypted, in order
+/// to test that receivers reject them.
+#[cfg(any(test, feature = "test_util"))]
+#[derive(Copy, Clone, Debug)]
+pub struct EncryptionModifiers {
+    pub modify_nonce: fn(&mut [u8]),
+}
+
+#[cfg(any(test, feature = "test_util"))]
+impl Default for EncryptionModifiers {
+    fn default() -> Self {
+        Self {
+            modify_nonce: |_| (),
+        }
+    }
+}
+
+/// Copy of the current epoch secrets that can be used to decrypt messages
+/// without modifying the state of the group it was taken from.
+pub(crate) struct DetachedGroupState<'a> {
+    pub(crate) context: &'a GroupContext,
+    pub(crate) self_index: LeafIndex,
+    pub(crate) secrets: EpochSecrets,
+}
+
+impl GroupStateProvider for DetachedGroupState<'_> {
+    fn group_context(&self) -> &GroupContext {
+        self.context
+    }
+
+    fn self_index(&self) -> LeafIndex {
+        self.self_index
+    }
+
+    fn epoch_secrets_mut(&mut self) -> &mut EpochSecrets {
+        &mut self.secrets
+    }
+
+    fn epoch_secrets(&self) -> &EpochSecrets {
+        &self.secrets
+    }
 }
 
 pub(crate) struct CiphertextProcessor<'a, GS, CP>
@@ -146,6 +194,13 @@ where
         let key_data = self.next_encryption_key(key_type).await?;
         let generation = key_data.generation;
 
+        #[cfg(any(test, feature = "test_util"))]
+        let key_data = {
+            let mut key_data = key_data;
+            (self.group_state.encryption_modifiers().modify_nonce)(&mut key_data.nonce);
+            key_data
+        };
+
         let ciphertext = MessageKey::new(key_data)
             .encrypt(
                 &self.cipher_suite_provider,
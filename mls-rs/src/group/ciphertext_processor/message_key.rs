@@ -5,7 +5,10 @@
 use alloc::vec::Vec;
 use zeroize::Zeroizing;
 
-use crate::{crypto::CipherSuiteProvider, group::secret_tree::MessageKeyData};
+use crate::{
+    crypto::{aead_open, aead_seal, AeadContext, CipherSuiteProvider},
+    group::secret_tree::MessageKeyData,
+};
 
 use super::reuse_guard::ReuseGuard;
 
@@ -25,14 +28,15 @@ impl MessageKey {
         aad: &[u8],
         reuse_guard: &ReuseGuard,
     ) -> Result<Vec<u8>, P::Error> {
-        provider
-            .aead_seal(
-                &self.0.key,
-                data,
-                Some(aad),
-                &reuse_guard.apply(&self.0.nonce),
-            )
-            .await
+        aead_seal(
+            provider,
+            AeadContext::Framing,
+            &self.0.key,
+            data,
+            Some(aad),
+            &reuse_guard.apply(&self.0.nonce),
+        )
+        .await
     }
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -43,14 +47,15 @@ impl MessageKey {
         aad: &[u8],
         reuse_guard: &ReuseGuard,
     ) -> Result<Zeroizing<Vec<u8>>, P::Error> {
-        provider
-            .aead_open(
-                &self.0.key,
-                data,
-                Some(aad),
-                &reuse_guard.apply(&self.0.nonce),
-            )
-            .await
+        aead_open(
+            provider,
+            AeadContext::Framing,
+            &self.0.key,
+            data,
+            Some(aad),
+            &reuse_guard.apply(&self.0.nonce),
+        )
+        .await
     }
 }
 
@@ -10,7 +10,9 @@ use zeroize::Zeroizing;
 
 use crate::{
     client::MlsError,
-    crypto::CipherSuiteProvider,
+    crypto::{
+        aead_key_size, aead_nonce_size, aead_open, aead_seal, AeadContext, CipherSuiteProvider,
+    },
     group::{epoch::SenderDataSecret, framing::ContentType, key_schedule::kdf_expand_with_label},
     tree_kem::node::LeafIndex,
 };
@@ -80,7 +82,7 @@ impl<'a, CP: CipherSuiteProvider> SenderDataKey<'a, CP> {
             sender_data_secret,
             b"key",
             ciphertext_sample,
-            Some(cipher_suite_provider.aead_key_size()),
+            Some(aead_key_size(cipher_suite_provider, AeadContext::Framing)),
         )
         .await?;
 
@@ -89,7 +91,7 @@ impl<'a, CP: CipherSuiteProvider> SenderDataKey<'a, CP> {
             sender_data_secret,
             b"nonce",
             ciphertext_sample,
-            Some(cipher_suite_provider.aead_nonce_size()),
+            Some(aead_nonce_size(cipher_suite_provider, AeadContext::Framing)),
         )
         .await?;
 
@@ -106,15 +108,16 @@ impl<'a, CP: CipherSuiteProvider> SenderDataKey<'a, CP> {
         sender_data: &SenderData,
         aad: &SenderDataAAD,
     ) -> Result<Vec<u8>, MlsError> {
-        self.cipher_suite_provider
-            .aead_seal(
-                &self.key,
-                &sender_data.mls_encode_to_vec()?,
-                Some(&aad.mls_encode_to_vec()?),
-                &self.nonce,
-            )
-            .await
-            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
+        aead_seal(
+            self.cipher_suite_provider,
+            AeadContext::Framing,
+            &self.key,
+            &sender_data.mls_encode_to_vec()?,
+            Some(&aad.mls_encode_to_vec()?),
+            &self.nonce,
+        )
+        .await
+        .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
     }
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -123,16 +126,17 @@ impl<'a, CP: CipherSuiteProvider> SenderDataKey<'a, CP> {
         sender_data: &[u8],
         aad: &SenderDataAAD,
     ) -> Result<SenderData, MlsError> {
-        self.cipher_suite_provider
-            .aead_open(
-                &self.key,
-                sender_data,
-                Some(&aad.mls_encode_to_vec()?),
-                &self.nonce,
-            )
-            .await
-            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
-            .and_then(|data| SenderData::mls_decode(&mut &**data).map_err(From::from))
+        aead_open(
+            self.cipher_suite_provider,
+            AeadContext::Framing,
+            &self.key,
+            sender_data,
+            Some(&aad.mls_encode_to_vec()?),
+            &self.nonce,
+        )
+        .await
+        .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
+        .and_then(|data| SenderData::mls_decode(&mut &**data).map_err(From::from))
     }
 }
 
@@ -57,7 +57,11 @@ use super::proposal_cache::prepare_commit;
 #[cfg(feature = "custom_proposal")]
 use super::proposal::CustomProposal;
 
+#[cfg(feature = "by_ref_proposal")]
+use crate::extension::{ExtensionType, ExternalSendersExt};
+
 #[derive(Clone, Debug, PartialEq, MlsSize, MlsEncode, MlsDecode)]
+#[mls_codec(error_context)]
 #[cfg_attr(feature = "arbitrary", derive(mls_rs_core::arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct Commit {
@@ -129,6 +133,22 @@ pub struct CommitOutput {
     pub unused_proposals: Vec<crate::mls_rules::ProposalInfo<Proposal>>,
     /// Indicator that the commit contains a path update
     pub contains_update_path: bool,
+    /// Hash of the authenticated content of the commit, i.e. of its wire
+    /// format, framed content and signature. Members processing the commit
+    /// find the same value in
+    /// [`CommitMessageDescription::commit_hash`](crate::group::CommitMessageDescription::commit_hash).
+    pub commit_hash: Vec<u8>,
+    /// Local notes attached to by-value proposals with
+    /// [`CommitBuilder::annotate`], keyed by the index of the proposal in the
+    /// builder. Annotations are never sent to other members.
+    pub annotations: Vec<(usize, Vec<u8>)>,
+    /// Fingerprint of the joiner secret used to produce the welcome messages,
+    /// or `None` if the commit does not add members.
+    ///
+    /// The fingerprint is derived from the joiner secret with a one-way
+    /// function, so it can be shared with a relay in order to match welcome
+    /// messages with the commit that produced them without revealing the secret.
+    pub welcome_fingerprint: Option<Vec<u8>>,
 }
 
 #[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen)]
@@ -166,6 +186,19 @@ impl CommitOutput {
     pub fn unused_proposals(&self) -> &[crate::mls_rules::ProposalInfo<Proposal>] {
         &self.unused_proposals
     }
+
+    /// Hash of the authenticated content of the commit.
+    #[cfg(feature = "ffi")]
+    pub fn commit_hash(&self) -> &[u8] {
+        &self.commit_hash
+    }
+
+    /// Fingerprint of the joiner secret used to produce the welcome messages,
+    /// or `None` if the commit does not add members.
+    #[cfg(feature = "ffi")]
+    pub fn welcome_fingerprint(&self) -> Option<&[u8]> {
+        self.welcome_fingerprint.as_deref()
+    }
 }
 
 /// Build a commit with multiple proposals by-value.
@@ -186,6 +219,8 @@ where
     new_signer: Option<SignatureSecretKey>,
     new_signing_identity: Option<SigningIdentity>,
     new_leaf_node_extensions: Option<ExtensionList>,
+    annotations: Vec<(usize, Vec<u8>)>,
+    suppress_welcome: bool,
 }
 
 impl<'a, C> CommitBuilder<'a, C>
@@ -234,6 +269,35 @@ where
         Ok(self)
     }
 
+    /// Replace the list of signing identities allowed to send external
+    /// proposals to the group.
+    ///
+    /// This inserts a
+    /// [`GroupContextExtensions`](crate::group::proposal::Proposal::GroupContextExtensions)
+    /// proposal setting the [`ExternalSendersExt`](crate::extension::ExternalSendersExt)
+    /// extension while keeping all other group context extensions. If a group
+    /// context extensions proposal was already inserted into this commit, it is
+    /// updated instead. Passing an empty list removes the extension.
+    #[cfg(feature = "by_ref_proposal")]
+    pub fn set_external_senders(mut self, senders: Vec<SigningIdentity>) -> Result<Self, MlsError> {
+        let existing = self.proposals.iter_mut().find_map(|p| match p {
+            Proposal::GroupContextExtensions(extensions) => Some(extensions),
+            _ => None,
+        });
+
+        if let Some(extensions) = existing {
+            set_external_senders_ext(extensions, senders)?;
+            return Ok(self);
+        }
+
+        let mut extensions = self.group.context().extensions.clone();
+        set_external_senders_ext(&mut extensions, senders)?;
+
+        let proposal = self.group.group_context_extensions_proposal(extensions);
+        self.proposals.push(proposal);
+        Ok(self)
+    }
+
     /// Insert a
     /// [`PreSharedKeyProposal`](crate::group::proposal::PreSharedKeyProposal) with
     /// an external PSK into the current commit that is being built.
@@ -303,6 +367,26 @@ where
         self
     }
 
+    /// Attach a local note to the by-value proposal at `proposal_index`, i.e.
+    /// the proposal added by the `proposal_index`-th call inserting a proposal
+    /// into this builder, counting from 0 and after the proposals staged with
+    /// [`Group::stage_proposal`]. A proposal has at most one note, annotating
+    /// it again replaces the previous note.
+    ///
+    /// Notes are returned in [`CommitOutput::annotations`] for the committer's
+    /// bookkeeping and are not part of the commit message. Unlike
+    /// [`CommitBuilder::authenticated_data`], they are never sent.
+    pub fn annotate(mut self, proposal_index: usize, note: Vec<u8>) -> Result<Self, MlsError> {
+        if proposal_index >= self.proposals.len() {
+            return Err(MlsError::ProposalIndexOutOfRange(proposal_index));
+        }
+
+        self.annotations.retain(|(i, _)| *i != proposal_index);
+        self.annotations.push((proposal_index, note));
+
+        Ok(self)
+    }
+
     /// Add additional authenticated data to the commit.
     ///
     /// # Warning
@@ -342,6 +426,18 @@ where
         }
     }
 
+    /// Do not output welcome messages for members added by this commit.
+    ///
+    /// The committer's state is unaffected, but the application becomes
+    /// responsible for providing new members with the secrets they need to
+    /// join the group, as [`CommitOutput::welcome_messages`] is empty.
+    pub fn suppress_welcome(self) -> Self {
+        Self {
+            suppress_welcome: true,
+            ..self
+        }
+    }
+
     /// Finalize the commit to send.
     ///
     /// # Errors
@@ -350,9 +446,13 @@ where
     /// are not contextually valid according to the rules defined by the
     /// MLS RFC, or if they do not pass the custom rules defined by the current
     /// [proposal rules](crate::client_builder::ClientBuilder::mls_rules).
+    ///
+    /// The group is only modified once the commit is fully built. If this
+    /// function returns an error or its future is dropped before completing,
+    /// the group is left unchanged.
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn build(self) -> Result<CommitOutput, MlsError> {
-        let (output, pending_commit) = self
+        let (mut output, pending_commit, epoch_secrets) = self
             .group
             .commit_internal(
                 self.proposals,
@@ -367,6 +467,17 @@ where
 
         self.group.pending_commit = pending_commit.try_into()?;
 
+        if let Some(epoch_secrets) = epoch_secrets {
+            self.group.epoch_secrets = epoch_secrets;
+        }
+
+        self.group.staged_proposals.clear();
+        output.annotations = self.annotations;
+
+        if self.suppress_welcome {
+            output.welcome_messages.clear();
+        }
+
         Ok(output)
     }
 
@@ -376,7 +487,7 @@ where
     /// A detached commit can be applied using `Group::apply_detached_commit`.
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn build_detached(self) -> Result<(CommitOutput, CommitSecrets), MlsError> {
-        let (output, pending_commit) = self
+        let (mut output, pending_commit, epoch_secrets) = self
             .group
             .commit_internal(
                 self.proposals,
@@ -389,12 +500,22 @@ where
             )
             .await?;
 
-        Ok((
-            output,
-            CommitSecrets(PendingCommitSnapshot::PendingCommit(
-                pending_commit.mls_encode_to_vec()?,
-            )),
-        ))
+        let commit_secrets = CommitSecrets(PendingCommitSnapshot::PendingCommit(
+            pending_commit.mls_encode_to_vec()?,
+        ));
+
+        if let Some(epoch_secrets) = epoch_secrets {
+            self.group.epoch_secrets = epoch_secrets;
+        }
+
+        self.group.staged_proposals.clear();
+        output.annotations = self.annotations;
+
+        if self.suppress_welcome {
+            output.welcome_messages.clear();
+        }
+
+        Ok((output, commit_secrets))
     }
 }
 
@@ -465,17 +586,73 @@ where
             .await
     }
 
+    /// Stage a proposal to be included by value in the next commit built by
+    /// this member with [`Group::commit`] or [`Group::commit_builder`].
+    ///
+    /// This allows accumulating changes before committing them at once.
+    /// Staged proposals are kept separately from proposals received
+    /// by reference, and are dropped once a commit including them is built
+    /// or when calling [`Group::clear_staged_proposals`]. They are not
+    /// validated until the commit is built and are not persisted by
+    /// [`Group::write_to_storage`].
+    pub fn stage_proposal(&mut self, proposal: Proposal) {
+        self.staged_proposals.push(proposal);
+    }
+
+    /// Proposals staged with [`Group::stage_proposal`] for the next commit.
+    pub fn staged_proposals(&self) -> &[Proposal] {
+        &self.staged_proposals
+    }
+
+    /// Drop all proposals staged with [`Group::stage_proposal`].
+    pub fn clear_staged_proposals(&mut self) {
+        self.staged_proposals.clear();
+    }
+
+    /// Create a commit containing `proposals` by value and immediately apply
+    /// it, equivalent to [`CommitBuilder::build`] followed by
+    /// [`Group::apply_pending_commit`].
+    ///
+    /// This is intended for cases where no other member can commit
+    /// concurrently. The returned [`CommitOutput`] must still be sent to the
+    /// rest of the group. If applying the commit fails, no pending commit
+    /// is left behind.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn commit_and_apply(
+        &mut self,
+        proposals: Vec<Proposal>,
+        authenticated_data: Vec<u8>,
+    ) -> Result<CommitOutput, MlsError> {
+        let output = self
+            .commit_builder()
+            .raw_proposals(proposals)
+            .authenticated_data(authenticated_data)
+            .build()
+            .await?;
+
+        // `apply_pending_commit` takes the pending commit out of the group
+        // before applying it, so it is cleared even on failure.
+        self.apply_pending_commit().await?;
+
+        Ok(output)
+    }
+
     /// Create a new commit builder that can include proposals
     /// by-value.
+    ///
+    /// The builder starts with the proposals staged with
+    /// [`Group::stage_proposal`], in the order they were staged.
     pub fn commit_builder(&mut self) -> CommitBuilder<C> {
         CommitBuilder {
+            proposals: self.staged_proposals.clone(),
             group: self,
-            proposals: Default::default(),
             authenticated_data: Default::default(),
             group_info_extensions: Default::default(),
             new_signer: Default::default(),
             new_signing_identity: Default::default(),
             new_leaf_node_extensions: Default::default(),
+            annotations: Default::default(),
+            suppress_welcome: false,
         }
     }
 
@@ -483,6 +660,18 @@ where
     /// for newly added members.
     #[allow(clippy::too_many_arguments)]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "commit",
+            level = "debug",
+            skip_all,
+            fields(
+                group_id = ?mls_rs_core::debug::pretty_group_id(self.group_id()),
+                epoch = self.current_epoch(),
+            )
+        )
+    )]
     pub(super) async fn commit_internal(
         &mut self,
         proposals: Vec<Proposal>,
@@ -492,7 +681,7 @@ where
         new_signer: Option<SignatureSecretKey>,
         new_signing_identity: Option<SigningIdentity>,
         new_leaf_node_extensions: Option<ExtensionList>,
-    ) -> Result<(CommitOutput, PendingCommit), MlsError> {
+    ) -> Result<(CommitOutput, PendingCommit, Option<EpochSecrets>), MlsError> {
         if !self.pending_commit.is_none() {
             return Err(MlsError::ExistingPendingCommit);
         }
@@ -517,11 +706,7 @@ where
         let new_signer = new_signer.unwrap_or_else(|| self.signer.clone());
         let old_signer = &self.signer;
 
-        #[cfg(feature = "std")]
-        let time = Some(crate::time::MlsTime::now());
-
-        #[cfg(not(feature = "std"))]
-        let time = None;
+        let time = self.config.current_time();
 
         #[cfg(feature = "by_ref_proposal")]
         let proposals = self.state.proposals.prepare_commit(sender, proposals);
@@ -539,8 +724,13 @@ where
                 &self.cipher_suite_provider,
                 &self.config.secret_store(),
                 &mls_rules,
+                &self.config.disallowed_proposal_types(),
+                #[cfg(feature = "psk")]
+                self.config.max_psks_per_commit(),
+                &self.config.leaf_node_extension_validators(),
                 time,
                 CommitDirection::Send,
+                false,
             )
             .await?;
 
@@ -551,8 +741,6 @@ where
             provisional_private_tree.self_index = provisional_state
                 .external_init_index
                 .ok_or(MlsError::ExternalCommitMissingExternalInit)?;
-
-            self.private_tree.self_index = provisional_private_tree.self_index;
         }
 
         // Decide whether to populate the path field: If the path field is required based on the
@@ -697,6 +885,14 @@ where
 
         auth_content.auth.confirmation_tag = Some(confirmation_tag.clone());
 
+        #[cfg(test)]
+        {
+            auth_content.auth.confirmation_tag =
+                Some((self.commit_modifiers.modify_confirmation_tag)(
+                    confirmation_tag.clone(),
+                ));
+        }
+
         let ratchet_tree_ext = commit_options
             .ratchet_tree_extension
             .then(|| RatchetTreeExt {
@@ -724,6 +920,7 @@ where
                         &provisional_state.group_context,
                         extensions,
                         &confirmation_tag,
+                        provisional_private_tree.self_index,
                         &new_signer,
                     )
                     .await?;
@@ -747,6 +944,7 @@ where
                 &provisional_state.group_context,
                 welcome_group_info_extensions,
                 &confirmation_tag,
+                provisional_private_tree.self_index,
                 &new_signer,
             )
             .await?;
@@ -774,6 +972,7 @@ where
             .map(|(key_package, leaf_index)| {
                 self.encrypt_group_secrets(
                     &key_package,
+                    provisional_private_tree.self_index,
                     *leaf_index,
                     &key_schedule_result.joiner_secret,
                     path_secrets,
@@ -795,6 +994,7 @@ where
                 secrets.push(
                     self.encrypt_group_secrets(
                         &key_package,
+                        provisional_private_tree.self_index,
                         *leaf_index,
                         &key_schedule_result.joiner_secret,
                         path_secrets,
@@ -819,7 +1019,44 @@ where
                     .collect()
             };
 
-        let commit_message = self.format_for_wire(auth_content.clone()).await?;
+        #[cfg(feature = "tracing")]
+        if !welcome_messages.is_empty() {
+            tracing::debug!(
+                message_type = "welcome",
+                count = welcome_messages.len(),
+                "generated welcome messages"
+            );
+        }
+
+        let welcome_fingerprint = if welcome_messages.is_empty() {
+            None
+        } else {
+            Some(
+                key_schedule_result
+                    .joiner_secret
+                    .fingerprint(&self.cipher_suite_provider)
+                    .await?,
+            )
+        };
+
+        let commit_hash = auth_content.hash(&self.cipher_suite_provider).await?;
+
+        let self_index = provisional_private_tree.self_index;
+
+        let (path_updated_nodes, path_updated_leaves) = match perform_path_update {
+            true => (
+                provisional_state
+                    .public_tree
+                    .path_updated_nodes(self_index)?,
+                provisional_state
+                    .public_tree
+                    .path_updated_leaves(self_index)?,
+            ),
+            false => Default::default(),
+        };
+
+        let (commit_message, epoch_secrets) =
+            self.format_for_wire_detached(auth_content.clone()).await?;
 
         // TODO is it necessary to clone the tree here? or can we just output serialized bytes?
         let ratchet_tree = (!commit_options.ratchet_tree_extension)
@@ -833,6 +1070,9 @@ where
         let pending_commit = PendingCommit {
             output: CommitMessageDescription {
                 is_external: matches!(auth_content.content.sender, Sender::NewMemberCommit),
+                commit_hash: commit_hash.clone(),
+                path_updated_nodes,
+                path_updated_leaves: path_updated_leaves.into_iter().map(|i| *i).collect(),
                 authenticated_data: auth_content.content.authenticated_data,
                 committer: *provisional_private_tree.self_index,
                 effect: match pending_reinit {
@@ -873,9 +1113,19 @@ where
             contains_update_path: perform_path_update,
             #[cfg(feature = "by_ref_proposal")]
             unused_proposals: provisional_state.unused_proposals,
+            commit_hash,
+            annotations: Vec::new(),
+            welcome_fingerprint,
         };
 
-        Ok((output, pending_commit))
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            message_type = "commit",
+            new_epoch = pending_commit.state.context.epoch,
+            "created commit"
+        );
+
+        Ok((output, pending_commit, epoch_secrets))
     }
 
     // Construct a GroupInfo reflecting the new state
@@ -886,13 +1136,14 @@ where
         group_context: &GroupContext,
         extensions: ExtensionList,
         confirmation_tag: &ConfirmationTag,
+        signer_index: LeafIndex,
         signer: &SignatureSecretKey,
     ) -> Result<GroupInfo, MlsError> {
         let mut group_info = GroupInfo {
             group_context: group_context.clone(),
             extensions,
             confirmation_tag: confirmation_tag.clone(), // The confirmation_tag from the MlsPlaintext object
-            signer: LeafIndex(self.current_member_index()),
+            signer: signer_index,
             signature: vec![],
         };
 
@@ -922,12 +1173,27 @@ where
     }
 }
 
+#[cfg(feature = "by_ref_proposal")]
+fn set_external_senders_ext(
+    extensions: &mut ExtensionList,
+    senders: Vec<SigningIdentity>,
+) -> Result<(), MlsError> {
+    if senders.is_empty() {
+        extensions.remove(ExtensionType::EXTERNAL_SENDERS);
+    } else {
+        extensions.set_from(ExternalSendersExt::new(senders))?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 pub(crate) mod test_utils {
     use alloc::vec::Vec;
 
     use crate::{
         crypto::SignatureSecretKey,
+        group::ConfirmationTag,
         tree_kem::{leaf_node::LeafNode, TreeKemPublic, UpdatePathNode},
     };
 
@@ -936,6 +1202,7 @@ pub(crate) mod test_utils {
         pub modify_leaf: fn(&mut LeafNode, &SignatureSecretKey) -> Option<SignatureSecretKey>,
         pub modify_tree: fn(&mut TreeKemPublic),
         pub modify_path: fn(Vec<UpdatePathNode>) -> Vec<UpdatePathNode>,
+        pub modify_confirmation_tag: fn(ConfirmationTag) -> ConfirmationTag,
     }
 
     impl Default for CommitModifiers {
@@ -944,6 +1211,7 @@ pub(crate) mod test_utils {
                 modify_leaf: |_, _| None,
                 modify_tree: |_| (),
                 modify_path: |a| a,
+                modify_confirmation_tag: |t| t,
             }
         }
     }
@@ -953,21 +1221,26 @@ pub(crate) mod test_utils {
 mod tests {
     use mls_rs_core::{
         error::IntoAnyError,
-        extension::ExtensionType,
+        extension::{Extension, ExtensionType},
         identity::{CredentialType, IdentityProvider, MemberValidationContext},
         time::MlsTime,
     };
 
     use crate::extension::RequiredCapabilitiesExt;
     use crate::{
-        client::test_utils::{test_client_with_key_pkg, TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        client::test_utils::{
+            test_client_with_key_pkg, test_client_with_key_pkg_custom, TestClientBuilder,
+            TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION,
+        },
         client_builder::{
             test_utils::TestClientConfig, BaseConfig, ClientBuilder, WithCryptoProvider,
             WithIdentityProvider,
         },
         client_config::ClientConfig,
         crypto::test_utils::TestCryptoProvider,
-        extension::test_utils::{TestExtension, TEST_EXTENSION_TYPE},
+        extension::test_utils::{
+            TestExtension, TestLeafExtension, TEST_EXTENSION_TYPE, TEST_LEAF_EXTENSION_TYPE,
+        },
         group::test_utils::{test_group, test_group_custom},
         group::{
             proposal::ProposalType,
@@ -980,6 +1253,20 @@ mod tests {
         Client,
     };
 
+    use crate::group::{proposal::RemoveProposal, ReceivedMessage};
+
+    use assert_matches::assert_matches;
+
+    #[cfg(feature = "private_message")]
+    use crate::{
+        group::{
+            mls_rules::{CommitDirection, CommitSource},
+            proposal_filter::ProposalBundle,
+            Roster,
+        },
+        MlsRules,
+    };
+
     #[cfg(feature = "by_ref_proposal")]
     use crate::crypto::test_utils::test_cipher_suite_provider;
     #[cfg(feature = "by_ref_proposal")]
@@ -1112,6 +1399,473 @@ mod tests {
         );
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn unknown_group_info_extension_is_rejected_when_strict() {
+        let mut group = test_commit_builder_group().await;
+        let unknown_type = ExtensionType::from(0xff01);
+
+        for strict in [false, true] {
+            let (client, key_package) = test_client_with_key_pkg_custom(
+                TEST_PROTOCOL_VERSION,
+                TEST_CIPHER_SUITE,
+                "bob",
+                Default::default(),
+                Default::default(),
+                |c| c.0.settings.reject_unknown_group_info_extensions = strict,
+            )
+            .await;
+
+            let extensions = ExtensionList::from(vec![Extension::new(unknown_type, vec![1])]);
+
+            let welcome_message = group
+                .commit_builder()
+                .add_member(key_package)
+                .unwrap()
+                .set_group_info_ext(extensions)
+                .build()
+                .await
+                .unwrap()
+                .welcome_messages
+                .remove(0);
+
+            group.clear_pending_commit();
+
+            let res = client.join_group(None, &welcome_message).await.map(|_| ());
+
+            if strict {
+                assert_matches!(
+                    res,
+                    Err(MlsError::UnknownGroupInfoExtension(t)) if t == unknown_type
+                );
+            } else {
+                assert_matches!(res, Ok(()));
+            }
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn joiner_can_read_typed_group_info_extension() {
+        let mut group = test_commit_builder_group().await;
+
+        let (bob_client, bob_key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let ext = TestExtension { foo: 42 };
+        let mut extension_list = ExtensionList::default();
+        extension_list.set_from(ext.clone()).unwrap();
+
+        let welcome_message = group
+            .commit_builder()
+            .add_member(bob_key_package)
+            .unwrap()
+            .set_group_info_ext(extension_list)
+            .build()
+            .await
+            .unwrap()
+            .welcome_messages
+            .remove(0);
+
+        let (_, new_member_info) = bob_client.join_group(None, &welcome_message).await.unwrap();
+
+        assert_eq!(
+            new_member_info
+                .group_info_extension::<TestExtension>()
+                .unwrap(),
+            Some(ext)
+        );
+
+        assert_eq!(
+            new_member_info
+                .group_info_extension::<RequiredCapabilitiesExt>()
+                .unwrap(),
+            None
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn welcome_fingerprint_identifies_commit() {
+        let mut group = test_commit_builder_group().await;
+
+        let empty_commit = group.commit_builder().build().await.unwrap();
+        assert_eq!(empty_commit.welcome_fingerprint, None);
+        group.clear_pending_commit();
+
+        let mut fingerprints = Vec::new();
+
+        for name in ["bob", "carol"] {
+            let key_package =
+                test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, name).await;
+
+            let output = group
+                .commit_builder()
+                .add_member(key_package)
+                .unwrap()
+                .build()
+                .await
+                .unwrap();
+
+            let fingerprint = output.welcome_fingerprint.unwrap();
+
+            group.clear_pending_commit();
+            fingerprints.push(fingerprint);
+        }
+
+        assert_ne!(fingerprints[0], fingerprints[1]);
+    }
+
+    #[cfg(feature = "private_message")]
+    #[derive(Debug, Clone, Default)]
+    struct InterruptibleMlsRules {
+        interrupt: alloc::sync::Arc<core::sync::atomic::AtomicBool>,
+    }
+
+    #[cfg(feature = "private_message")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl MlsRules for InterruptibleMlsRules {
+        type Error = MlsError;
+
+        async fn filter_proposals(
+            &self,
+            _: CommitDirection,
+            _: CommitSource,
+            _: &Roster,
+            _: &GroupContext,
+            proposals: ProposalBundle,
+        ) -> Result<ProposalBundle, MlsError> {
+            Ok(proposals)
+        }
+
+        fn commit_options(
+            &self,
+            _: &Roster,
+            _: &GroupContext,
+            _: &ProposalBundle,
+        ) -> Result<CommitOptions, MlsError> {
+            Ok(Default::default())
+        }
+
+        // Called after the update path is generated, in the middle of building a commit
+        fn encryption_options(
+            &self,
+            _: &Roster,
+            _: &GroupContext,
+        ) -> Result<crate::mls_rules::EncryptionOptions, MlsError> {
+            if self.interrupt.load(core::sync::atomic::Ordering::SeqCst) {
+                Err(MlsError::InvalidSender)
+            } else {
+                Ok(Default::default())
+            }
+        }
+    }
+
+    #[cfg(feature = "private_message")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn interrupted_commit_leaves_group_unchanged() {
+        let mls_rules = InterruptibleMlsRules::default();
+
+        let mut group = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .mls_rules(mls_rules.clone())
+            .build()
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let state = group.state.clone();
+        let private_tree = group.private_tree.clone();
+
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        mls_rules
+            .interrupt
+            .store(true, core::sync::atomic::Ordering::SeqCst);
+
+        let res = group
+            .commit_builder()
+            .add_member(key_package.clone())
+            .unwrap()
+            .build()
+            .await
+            .map(|_| ());
+
+        assert!(res.is_err());
+        assert!(!group.has_pending_commit());
+        assert_eq!(group.state, state);
+        assert_eq!(group.private_tree, private_tree);
+
+        mls_rules
+            .interrupt
+            .store(false, core::sync::atomic::Ordering::SeqCst);
+
+        group
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        group.apply_pending_commit().await.unwrap();
+        assert_eq!(group.roster().members_iter().count(), 2);
+    }
+
+    #[cfg(all(feature = "private_message", mls_build_async))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn dropped_commit_future_leaves_group_unchanged() {
+        use crate::crypto::test_utils::ModifiedCryptoProvider;
+        use alloc::sync::Arc;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        use futures::FutureExt;
+
+        let yield_after_hashes = Arc::new(AtomicUsize::new(usize::MAX));
+
+        let crypto_provider = ModifiedCryptoProvider {
+            yield_after_hashes: Some(yield_after_hashes.clone()),
+            ..Default::default()
+        };
+
+        let (signing_identity, secret_key) =
+            crate::identity::test_utils::get_test_signing_identity(TEST_CIPHER_SUITE, b"alice")
+                .await;
+
+        let mut group = TestClientBuilder::new_for_test()
+            .crypto_provider(crypto_provider)
+            .signing_identity(signing_identity, secret_key, TEST_CIPHER_SUITE)
+            .mls_rules(
+                crate::mls_rules::DefaultMlsRules::new().with_encryption_options(
+                    crate::mls_rules::EncryptionOptions::new(
+                        true,
+                        crate::group::padding::PaddingMode::None,
+                    ),
+                ),
+            )
+            .build()
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let state = group.state.clone();
+        let epoch_secrets = group.epoch_secrets.clone();
+        let private_tree = group.private_tree.clone();
+
+        // Drop the commit future at every hash computation, including those
+        // after the commit message is encrypted, until it completes.
+        for hashes in 0.. {
+            yield_after_hashes.store(hashes, Ordering::SeqCst);
+
+            let output = group.commit_builder().build().now_or_never();
+
+            if output.is_some() {
+                break;
+            }
+
+            assert!(!group.has_pending_commit());
+            assert_eq!(group.state, state);
+            assert_eq!(group.private_tree, private_tree);
+            assert!(group.epoch_secrets == epoch_secrets);
+        }
+
+        assert!(group.has_pending_commit());
+        assert!(group.epoch_secrets != epoch_secrets);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_hash_matches_for_committer_and_receiver() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let output = alice.commit(b"aad".to_vec()).await.unwrap();
+        assert!(!output.commit_hash.is_empty());
+
+        let committed = alice.apply_pending_commit().await.unwrap();
+        assert_eq!(committed.commit_hash, output.commit_hash);
+
+        let received = bob
+            .process_incoming_message(output.commit_message)
+            .await
+            .unwrap();
+
+        let ReceivedMessage::Commit(received) = received else {
+            panic!("expected commit");
+        };
+
+        assert_eq!(received.commit_hash, output.commit_hash);
+
+        let next = alice.commit(vec![]).await.unwrap();
+        assert_ne!(next.commit_hash, output.commit_hash);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_builder_annotations_are_returned_in_output() {
+        let mut group = test_commit_builder_group().await;
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let res = group
+            .commit_builder()
+            .annotate(0, b"nothing to annotate".to_vec())
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::ProposalIndexOutOfRange(0)));
+
+        let output = group
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .annotate(0, b"first try".to_vec())
+            .unwrap()
+            .annotate(0, b"invite from contact list".to_vec())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output.annotations,
+            vec![(0, b"invite from contact list".to_vec())]
+        );
+
+        let commit = output.commit_message.into_plaintext().unwrap();
+        assert!(commit.content.authenticated_data.is_empty());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn staged_proposals_are_included_in_next_commit() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
+
+        let add_carol = alice.add_proposal(key_package).unwrap();
+        let remove_bob = alice.remove_proposal(1).unwrap();
+
+        alice.stage_proposal(remove_bob.clone());
+        alice.clear_staged_proposals();
+        assert!(alice.staged_proposals().is_empty());
+
+        alice.stage_proposal(add_carol.clone());
+        alice.stage_proposal(remove_bob.clone());
+        assert_eq!(alice.staged_proposals(), [add_carol, remove_bob]);
+
+        let output = alice.commit(vec![]).await.unwrap();
+        assert!(alice.staged_proposals().is_empty());
+
+        let description = alice.apply_pending_commit().await.unwrap();
+
+        let CommitEffect::NewEpoch(new_epoch) = description.effect else {
+            panic!("expected new epoch");
+        };
+
+        assert_eq!(new_epoch.applied_proposals.len(), 2);
+
+        let members = alice
+            .roster()
+            .members_iter()
+            .map(|m| {
+                m.signing_identity
+                    .credential
+                    .as_basic()
+                    .unwrap()
+                    .identifier
+                    .clone()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(members.len(), 2);
+        assert!(members.contains(&b"carol".to_vec()));
+        assert!(!members.contains(&b"bob".to_vec()));
+
+        let received = bob.process_message(output.commit_message).await.unwrap();
+
+        assert_matches!(
+            received,
+            ReceivedMessage::Commit(CommitMessageDescription {
+                effect: CommitEffect::Removed { .. },
+                ..
+            })
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_and_apply_advances_one_epoch() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+        let epoch = alice.current_epoch();
+
+        let remove_bob = Proposal::Remove(RemoveProposal {
+            to_remove: LeafIndex(1),
+        });
+
+        let output = alice
+            .commit_and_apply(vec![remove_bob], b"aad".to_vec())
+            .await
+            .unwrap();
+
+        assert_eq!(alice.current_epoch(), epoch + 1);
+        assert!(!alice.has_pending_commit());
+        assert_eq!(alice.roster().members_iter().count(), 1);
+
+        let received = bob
+            .process_incoming_message(output.commit_message)
+            .await
+            .unwrap();
+
+        assert_matches!(received, ReceivedMessage::Commit(_));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn path_updated_nodes_cover_committer_direct_path() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 4).await;
+
+        // Leaf 1 is node 2, its direct path is nodes 1 and 3 (the root).
+        let output = groups[1]
+            .commit_builder()
+            .remove_member(2)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        assert!(output.contains_update_path);
+
+        let committed = groups[1].apply_pending_commit().await.unwrap();
+        assert_eq!(committed.path_updated_nodes, vec![2, 1, 3]);
+        assert_eq!(committed.path_updated_leaves, vec![0, 1, 3]);
+
+        let received = groups[0]
+            .process_incoming_message(output.commit_message)
+            .await
+            .unwrap();
+
+        let ReceivedMessage::Commit(received) = received else {
+            panic!("expected commit");
+        };
+
+        assert_eq!(received.path_updated_nodes, vec![2, 1, 3]);
+        assert_eq!(received.path_updated_leaves, vec![0, 1, 3]);
+
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "dave").await;
+
+        let output = groups[0]
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        assert!(!output.contains_update_path);
+
+        let committed = groups[0].apply_pending_commit().await.unwrap();
+        assert!(committed.path_updated_nodes.is_empty());
+        assert!(committed.path_updated_leaves.is_empty());
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_commit_builder_remove() {
         let mut group = test_commit_builder_group().await;
@@ -1166,6 +1920,52 @@ mod tests {
         assert_commit_builder_output(group, commit_output, vec![expected_psk], 0)
     }
 
+    #[cfg(feature = "psk")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_with_too_many_psks_is_rejected_before_resolution() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let (mut bob, _) = alice
+            .join_with_custom_config("bob", false, |c| c.0.settings.max_psks_per_commit = Some(1))
+            .await
+            .unwrap();
+
+        let psk_ids = [ExternalPskId::new(vec![1]), ExternalPskId::new(vec![2])];
+
+        // Neither PSK is known to bob, so resolution would fail with a different error
+        let res = bob
+            .commit_builder()
+            .add_external_psk(psk_ids[0].clone())
+            .unwrap()
+            .add_external_psk(psk_ids[1].clone())
+            .unwrap()
+            .build()
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::TooManyPsks(2)));
+
+        for id in &psk_ids {
+            alice
+                .config
+                .secret_store()
+                .insert(id.clone(), PreSharedKey::from(vec![1]));
+        }
+
+        let commit = alice
+            .commit_builder()
+            .add_external_psk(psk_ids[0].clone())
+            .unwrap()
+            .add_external_psk(psk_ids[1].clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let res = bob.process_message(commit.commit_message).await.map(|_| ());
+        assert_matches!(res, Err(MlsError::TooManyPsks(2)));
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_commit_builder_group_context_ext() {
         let mut group = test_commit_builder_group().await;
@@ -1297,6 +2097,38 @@ mod tests {
         );
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn suppressed_welcome_is_not_output() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = group.join("bob").await;
+        let epoch = group.current_epoch();
+
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
+
+        let output = group
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .suppress_welcome()
+            .build()
+            .await
+            .unwrap();
+
+        assert!(output.welcome_messages.is_empty());
+
+        group.apply_pending_commit().await.unwrap();
+        bob.process_message(output.commit_message).await.unwrap();
+
+        assert_eq!(group.current_epoch(), epoch + 1);
+        assert_eq!(group.roster().members().len(), 3);
+
+        assert_eq!(
+            group.epoch_authenticator().unwrap(),
+            bob.epoch_authenticator().unwrap()
+        );
+    }
+
     #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_commit_builder_multiple_welcome_messages() {
@@ -1335,6 +2167,80 @@ mod tests {
         }
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn confirmation_tag_for_matches_commit() {
+        let mut group = test_group_custom(
+            TEST_PROTOCOL_VERSION,
+            TEST_CIPHER_SUITE,
+            Default::default(),
+            None,
+            Some(CommitOptions::new().with_path_required(false)),
+        )
+        .await;
+
+        let prior_key_schedule = group.key_schedule.clone();
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        group
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        group.apply_pending_commit().await.unwrap();
+
+        let cs = group.cipher_suite_provider.clone();
+        let context = group.context().clone();
+
+        let key_schedule_result = KeySchedule::from_key_schedule(
+            &prior_key_schedule,
+            &PathSecret::empty(&cs),
+            &context,
+            #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+            group.state.public_tree.total_leaf_count(),
+            &crate::psk::secret::PskSecret::new(&cs),
+            &cs,
+        )
+        .await
+        .unwrap();
+
+        let tag = crate::group::confirmation_tag_for(
+            &key_schedule_result.confirmation_key,
+            &context.confirmed_transcript_hash,
+            &cs,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(tag, group.state.confirmation_tag);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn current_signing_identity_follows_credential_change() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let old_identity = group.current_member_signing_identity().unwrap().clone();
+        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"member").await;
+
+        group
+            .commit_builder()
+            .set_new_signing_identity(secret_key, identity.clone())
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            group.current_member_signing_identity().unwrap(),
+            &old_identity
+        );
+
+        group.apply_pending_commit().await.unwrap();
+
+        assert_eq!(group.current_member_signing_identity().unwrap(), &identity);
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn commit_can_change_credential() {
         let cs = TEST_CIPHER_SUITE;
@@ -1700,4 +2606,117 @@ mod tests {
         group.apply_detached_commit(secrets).await.unwrap();
         assert_eq!(group.context().epoch, 1);
     }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn group_with_leaf_extension(declare_dependency: bool) -> Group<impl ClientConfig> {
+        let mut group_extensions = ExtensionList::new();
+        group_extensions.set_from(TestExtension { foo: 1 }).unwrap();
+
+        let mut leaf_extensions = ExtensionList::new();
+        leaf_extensions
+            .set_from(TestLeafExtension { bar: 1 })
+            .unwrap();
+
+        let mut builder = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .extension_types([TEST_EXTENSION_TYPE.into(), TEST_LEAF_EXTENSION_TYPE.into()]);
+
+        if declare_dependency {
+            builder = builder.leaf_node_extension_dependency::<TestLeafExtension, TestExtension>();
+        }
+
+        builder
+            .build()
+            .create_group(group_extensions, leaf_extensions)
+            .await
+            .unwrap()
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn removing_group_extension_used_by_member_fails() {
+        let mut group = group_with_leaf_extension(true).await;
+
+        let res = group
+            .commit_builder()
+            .set_group_context_ext(Default::default())
+            .unwrap()
+            .build()
+            .await
+            .map(|_| ());
+
+        assert_matches!(
+            res,
+            Err(MlsError::RemovedGroupExtensionInUse {
+                extension_type,
+                leaf_index: 0
+            }) if extension_type == TEST_EXTENSION_TYPE.into()
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn receiving_removal_of_group_extension_used_by_member_succeeds() {
+        let mut alice = group_with_leaf_extension(true).await;
+
+        let (bob, bob_key_package) = test_client_with_key_pkg_custom(
+            TEST_PROTOCOL_VERSION,
+            TEST_CIPHER_SUITE,
+            "bob",
+            Default::default(),
+            Default::default(),
+            |c| {
+                c.0.settings
+                    .extension_types
+                    .push(TEST_EXTENSION_TYPE.into())
+            },
+        )
+        .await;
+
+        let commit = alice
+            .commit_builder()
+            .add_member(bob_key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let (mut bob, _) = bob
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap();
+
+        let commit = bob
+            .commit_builder()
+            .set_group_context_ext(Default::default())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice
+            .process_incoming_message(commit.commit_message)
+            .await
+            .unwrap();
+
+        assert!(alice.context().extensions.is_empty());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn removing_group_extension_without_declared_dependency_succeeds() {
+        let mut group = group_with_leaf_extension(false).await;
+
+        group
+            .commit_builder()
+            .set_group_context_ext(Default::default())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        group.apply_pending_commit().await.unwrap();
+
+        assert!(group.context().extensions.is_empty());
+    }
 }
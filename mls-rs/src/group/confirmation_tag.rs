@@ -68,6 +68,28 @@ impl ConfirmationTag {
     }
 }
 
+/// Compute the confirmation tag of a commit from the `confirmation_key` of the
+/// epoch it creates and the `confirmed_transcript_hash` of the new group context.
+///
+/// This allows a party that does not hold the group state, such as a relay, to
+/// check the confirmation tag of a commit. The confirmation key is a secret of
+/// the new epoch, so whoever provides it to the relay is trusted to provide the
+/// key of the correct epoch. A matching tag only shows that the commit was
+/// produced by a holder of that key.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+pub async fn confirmation_tag_for<P: CipherSuiteProvider>(
+    confirmation_key: &[u8],
+    confirmed_transcript_hash: &ConfirmedTranscriptHash,
+    cipher_suite_provider: &P,
+) -> Result<ConfirmationTag, MlsError> {
+    ConfirmationTag::create(
+        confirmation_key,
+        confirmed_transcript_hash,
+        cipher_suite_provider,
+    )
+    .await
+}
+
 #[cfg(test)]
 impl ConfirmationTag {
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
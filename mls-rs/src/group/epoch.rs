@@ -23,13 +23,19 @@ use super::ciphertext_processor::GroupStateProvider;
 use crate::group::secret_tree::SecretTree;
 
 #[cfg(feature = "prior_epoch")]
-#[derive(Debug, Clone, MlsEncode, MlsDecode, MlsSize, PartialEq)]
+#[derive(Debug, Clone, MlsEncode, MlsSize, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct PriorEpoch {
     pub(crate) context: GroupContext,
     pub(crate) self_index: LeafIndex,
     pub(crate) secrets: EpochSecrets,
     pub(crate) signature_public_keys: Vec<Option<SignaturePublicKey>>,
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "mls_rs_core::zeroizing_serde")
+    )]
+    pub(crate) authenticator: Zeroizing<Vec<u8>>,
 }
 
 #[cfg(feature = "prior_epoch")]
@@ -45,6 +51,32 @@ impl PriorEpoch {
     }
 }
 
+#[cfg(feature = "prior_epoch")]
+impl MlsDecode for PriorEpoch {
+    fn mls_decode(reader: &mut &[u8]) -> Result<Self, mls_rs_codec::Error> {
+        let context = GroupContext::mls_decode(reader)?;
+        let self_index = LeafIndex::mls_decode(reader)?;
+        let secrets = EpochSecrets::mls_decode(reader)?;
+        let signature_public_keys = Vec::mls_decode(reader)?;
+
+        // Prior epochs stored before the epoch authenticator was recorded
+        // end here.
+        let authenticator = if reader.is_empty() {
+            Default::default()
+        } else {
+            Zeroizing::new(mls_rs_codec::byte_vec::mls_decode(reader)?)
+        };
+
+        Ok(Self {
+            context,
+            self_index,
+            secrets,
+            signature_public_keys,
+            authenticator,
+        })
+    }
+}
+
 #[cfg(all(feature = "private_message", feature = "prior_epoch"))]
 impl GroupStateProvider for PriorEpoch {
     fn group_context(&self) -> &GroupContext {
@@ -76,6 +108,23 @@ pub(crate) struct EpochSecrets {
     pub(crate) secret_tree: SecretTree<NodeIndex>,
 }
 
+impl EpochSecrets {
+    /// Replace all secrets with empty values, zeroizing the previous ones.
+    pub(crate) fn clear(&mut self) {
+        #[cfg(feature = "psk")]
+        {
+            self.resumption_secret = PreSharedKey::new(Vec::new());
+        }
+
+        self.sender_data_secret = Vec::new().into();
+
+        #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+        {
+            self.secret_tree = SecretTree::empty();
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, MlsEncode, MlsDecode, MlsSize)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct SenderDataSecret(
@@ -160,6 +209,7 @@ pub(crate) mod test_utils {
             self_index: LeafIndex(0),
             secrets: get_test_epoch_secrets(cipher_suite),
             signature_public_keys: Default::default(),
+            authenticator: Zeroizing::new(id.to_be_bytes().to_vec()),
         }
     }
 }
@@ -179,6 +179,7 @@ impl<C: ClientConfig> ExternalCommitBuilder<C> {
             protocol_version,
             &group_info,
             self.tree_data,
+            self.config.max_ratchet_tree_size(),
             &self.config.identity_provider(),
             &cipher_suite,
         )
@@ -258,7 +259,8 @@ impl<C: ClientConfig> ExternalCommitBuilder<C> {
             }));
         }
 
-        let (commit_output, pending_commit) = group
+        // External commits are never encrypted, so no epoch secrets are returned
+        let (commit_output, pending_commit, _) = group
             .commit_internal(
                 proposals,
                 Some(&leaf_node),
@@ -6,13 +6,19 @@ use core::ops::Deref;
 
 use crate::{client::MlsError, tree_kem::node::LeafIndex, KeyPackage, KeyPackageRef};
 
+use super::proposal::ProposalOrRefView;
 use super::{Commit, FramedContentAuthData, GroupInfo, MembershipTag, Welcome};
 
 #[cfg(feature = "by_ref_proposal")]
-use crate::{group::Proposal, mls_rules::ProposalRef};
+use crate::{
+    group::{proposal_ref::PROPOSAL_REF_LABEL, Proposal},
+    mls_rules::ProposalRef,
+};
 
 use alloc::vec::Vec;
 use core::fmt::{self, Debug};
+#[cfg(feature = "std")]
+use mls_rs_codec::VarInt;
 use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 use mls_rs_core::{
     crypto::{CipherSuite, CipherSuiteProvider},
@@ -20,6 +26,11 @@ use mls_rs_core::{
 };
 use zeroize::ZeroizeOnDrop;
 
+#[cfg(feature = "std")]
+use mls_rs_core::error::IntoAnyError;
+#[cfg(feature = "std")]
+use std::io::Read;
+
 #[cfg(feature = "private_message")]
 use alloc::boxed::Box;
 
@@ -175,11 +186,17 @@ impl MlsEncode for PublicMessage {
 
 impl MlsDecode for PublicMessage {
     fn mls_decode(reader: &mut &[u8]) -> Result<Self, mls_rs_codec::Error> {
-        let content = FramedContent::mls_decode(reader)?;
-        let auth = FramedContentAuthData::mls_decode(reader, content.content_type())?;
+        let content = FramedContent::mls_decode(reader)
+            .map_err(|e| e.with_context("PublicMessage.content"))?;
+
+        let auth = FramedContentAuthData::mls_decode(reader, content.content_type())
+            .map_err(|e| e.with_context("PublicMessage.auth"))?;
 
         let membership_tag = match content.sender {
-            Sender::Member(_) => Some(MembershipTag::mls_decode(reader)?),
+            Sender::Member(_) => Some(
+                MembershipTag::mls_decode(reader)
+                    .map_err(|e| e.with_context("PublicMessage.membership_tag"))?,
+            ),
             _ => None,
         };
 
@@ -290,6 +307,7 @@ impl Debug for PrivateContentAAD {
 
 #[cfg(feature = "private_message")]
 #[derive(Clone, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+#[mls_codec(error_context)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PrivateMessage {
     #[mls_codec(with = "mls_rs_codec::byte_vec")]
@@ -343,6 +361,7 @@ impl From<&PrivateMessage> for PrivateContentAAD {
 }
 
 #[derive(Clone, Debug, PartialEq, MlsSize, MlsEncode, MlsDecode)]
+#[mls_codec(error_context)]
 #[cfg_attr(
     all(feature = "ffi", not(test)),
     ::safer_ffi_gen::ffi_type(clone, opaque)
@@ -470,6 +489,65 @@ impl MlsMessage {
         Self::mls_decode(&mut &*bytes).map_err(Into::into)
     }
 
+    /// Deserialize a message from a reader, reading at most `max_size` bytes.
+    ///
+    /// Welcome messages are decoded incrementally: every length prefixed
+    /// field is read and decoded on its own, and its declared length is
+    /// checked against the bytes left within `max_size` before anything is
+    /// read or allocated for it. Other messages are read to the end, stopping
+    /// as soon as the reader yields more than `max_size` bytes, and then
+    /// decoded. In both cases [`MlsError::MessageTooLarge`] is returned if the
+    /// message does not fit in `max_size` bytes.
+    #[cfg(feature = "std")]
+    #[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn from_reader<R: std::io::Read>(reader: R, max_size: usize) -> Result<Self, MlsError> {
+        let mut reader = BoundedReader {
+            reader,
+            remaining: max_size,
+            max_size,
+        };
+
+        // Protocol version, wire format and cipher suite are all encoded as u16
+        let u16_len = core::mem::size_of::<u16>();
+
+        let version = reader.decode(u16_len)?;
+        let wire_format: WireFormat = reader.decode(u16_len)?;
+
+        let payload = if wire_format == WireFormat::Welcome {
+            MlsMessagePayload::Welcome(Welcome {
+                cipher_suite: reader.decode(u16_len)?,
+                secrets: reader.decode_prefixed()?,
+                encrypted_group_info: reader.decode_prefixed_bytes()?,
+            })
+        } else {
+            let mut bytes = wire_format.mls_encode_to_vec()?;
+            reader.read_remaining(&mut bytes)?;
+            MlsMessagePayload::mls_decode(&mut &*bytes)?
+        };
+
+        Ok(Self::new(version, payload))
+    }
+
+    /// Deserialize a message from untrusted input, for use as a fuzzing
+    /// target.
+    ///
+    /// This behaves like [`MlsMessage::from_bytes`] but additionally rejects
+    /// input with bytes left after the end of the message. It returns an error
+    /// and never panics on malformed input, so any panic observed while
+    /// fuzzing it is a bug.
+    #[cfg(any(test, feature = "arbitrary"))]
+    #[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn fuzz_parse(data: &[u8]) -> Result<Self, MlsError> {
+        let reader = &mut &*data;
+        let message = Self::mls_decode(reader)?;
+
+        if !reader.is_empty() {
+            return Err(MlsError::UnexpectedTrailingBytes(reader.len()));
+        }
+
+        Ok(message)
+    }
+
     /// Serialize a message for transport.
     pub fn to_bytes(&self) -> Result<Vec<u8>, MlsError> {
         self.mls_encode_to_vec().map_err(Into::into)
@@ -488,6 +566,24 @@ impl MlsMessage {
         }
     }
 
+    /// If this is a plaintext commit message, return the list of proposals it
+    /// commits, either by value or by reference.
+    ///
+    /// Returns `None` if this is not a commit or if the commit is encrypted.
+    #[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn commit_proposals(&self) -> Option<Vec<ProposalOrRefView<'_>>> {
+        match &self.payload {
+            MlsMessagePayload::Plain(plaintext) => match &plaintext.content.content {
+                Content::Commit(commit) => {
+                    Some(commit.proposals.iter().map(|p| p.as_view()).collect())
+                }
+                #[cfg(any(feature = "private_message", feature = "by_ref_proposal"))]
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     /// If this is a welcome message, return key package references of all members who can
     /// join using this message.
     pub fn welcome_key_package_references(&self) -> Vec<&KeyPackageRef> {
@@ -498,6 +594,16 @@ impl MlsMessage {
         welcome.secrets.iter().map(|s| &s.new_member).collect()
     }
 
+    /// If this is a welcome message, return the number of encrypted group
+    /// secrets it contains, i.e. the number of members who can join using
+    /// this message.
+    pub fn welcome_secret_count(&self) -> Option<usize> {
+        match &self.payload {
+            MlsMessagePayload::Welcome(welcome) => Some(welcome.secrets.len()),
+            _ => None,
+        }
+    }
+
     /// If this is a key package, return its key package reference.
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn key_package_reference<C: CipherSuiteProvider>(
@@ -513,17 +619,34 @@ impl MlsMessage {
 
     /// If this is a plaintext proposal, return the proposal reference that can be matched e.g. with
     /// [`NewEpoch::unused_proposals`](super::NewEpoch::unused_proposals).
+    ///
+    /// The reference is computed with the RFC 9420 label. Clients configured with a custom
+    /// [`proposal_ref_label`](crate::client_builder::ClientBuilder::proposal_ref_label) should use
+    /// [`MlsMessage::into_proposal_reference_with_label`] instead.
     #[cfg(feature = "by_ref_proposal")]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn into_proposal_reference<C: CipherSuiteProvider>(
         self,
         cipher_suite: &C,
+    ) -> Result<Option<Vec<u8>>, MlsError> {
+        self.into_proposal_reference_with_label(cipher_suite, PROPOSAL_REF_LABEL)
+            .await
+    }
+
+    /// Same as [`MlsMessage::into_proposal_reference`] but computes the reference with a
+    /// custom label.
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn into_proposal_reference_with_label<C: CipherSuiteProvider>(
+        self,
+        cipher_suite: &C,
+        label: &[u8],
     ) -> Result<Option<Vec<u8>>, MlsError> {
         let MlsMessagePayload::Plain(public_message) = self.payload else {
             return Ok(None);
         };
 
-        ProposalRef::from_content(cipher_suite, &public_message.into())
+        ProposalRef::from_content_with_label(cipher_suite, &public_message.into(), label)
             .await
             .map(|r| Some(r.to_vec()))
     }
@@ -546,6 +669,97 @@ impl MlsMessage {
     }
 }
 
+/// Reader used by [`MlsMessage::from_reader`] that never reads more than
+/// `max_size` bytes in total.
+#[cfg(feature = "std")]
+struct BoundedReader<R> {
+    reader: R,
+    remaining: usize,
+    max_size: usize,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> BoundedReader<R> {
+    fn read_exact(&mut self, len: usize, bytes: &mut Vec<u8>) -> Result<(), MlsError> {
+        if len > self.remaining {
+            return Err(MlsError::MessageTooLarge(self.max_size));
+        }
+
+        // Reading through `take` grows the buffer with the bytes actually
+        // received rather than allocating `len` bytes upfront.
+        let read = (&mut self.reader)
+            .take(len as u64)
+            .read_to_end(bytes)
+            .map_err(|e| MlsError::IoError(e.into_any_error()))?;
+
+        self.remaining -= read;
+
+        if read < len {
+            return Err(mls_rs_codec::Error::UnexpectedEOF.into());
+        }
+
+        Ok(())
+    }
+
+    fn read_remaining(&mut self, bytes: &mut Vec<u8>) -> Result<(), MlsError> {
+        let read = (&mut self.reader)
+            .take(self.remaining as u64 + 1)
+            .read_to_end(bytes)
+            .map_err(|e| MlsError::IoError(e.into_any_error()))?;
+
+        if read > self.remaining {
+            return Err(MlsError::MessageTooLarge(self.max_size));
+        }
+
+        self.remaining -= read;
+
+        Ok(())
+    }
+
+    fn decode<T: MlsDecode>(&mut self, len: usize) -> Result<T, MlsError> {
+        let mut bytes = Vec::new();
+        self.read_exact(len, &mut bytes)?;
+
+        T::mls_decode(&mut &*bytes).map_err(Into::into)
+    }
+
+    /// Read a variable length integer prefix followed by the number of bytes
+    /// it declares, returning the prefix along with the data.
+    fn read_prefixed(&mut self) -> Result<Vec<u8>, MlsError> {
+        let mut bytes = Vec::new();
+        self.read_exact(1, &mut bytes)?;
+
+        // The first two bits give the length of the prefix, 0b11 is invalid
+        // and rejected when decoding it
+        let prefix = bytes[0] >> 6;
+
+        if prefix < 3 {
+            self.read_exact((1 << prefix) - 1, &mut bytes)?;
+        }
+
+        let len = VarInt::mls_decode(&mut &*bytes)?;
+        self.read_exact(u32::from(len) as usize, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    fn decode_prefixed<T: MlsDecode>(&mut self) -> Result<T, MlsError> {
+        let bytes = self.read_prefixed()?;
+
+        T::mls_decode(&mut &*bytes).map_err(Into::into)
+    }
+
+    fn decode_prefixed_bytes(&mut self) -> Result<Vec<u8>, MlsError> {
+        let mut bytes = self.read_prefixed()?;
+        let prefix_len = 1 << (bytes[0] >> 6);
+
+        // Drop the prefix without allocating a second buffer for the data
+        bytes.drain(..prefix_len);
+
+        Ok(bytes)
+    }
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, PartialEq, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -582,6 +796,7 @@ pub enum WireFormat {
 }
 
 #[derive(Clone, PartialEq, MlsSize, MlsEncode, MlsDecode)]
+#[mls_codec(error_context)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct FramedContent {
@@ -682,10 +897,15 @@ mod tests {
         crypto::test_utils::test_cipher_suite_provider,
         group::{
             framing::test_utils::get_test_ciphertext_content,
-            proposal_ref::test_utils::auth_content_from_proposal, RemoveProposal,
+            proposal_ref::test_utils::auth_content_from_proposal, test_utils::test_group,
+            AddProposal, RemoveProposal,
         },
+        key_package::test_utils::test_key_package_message,
     };
 
+    #[cfg(feature = "std")]
+    use crate::client::test_utils::test_client_with_key_pkg;
+
     use super::*;
 
     #[test]
@@ -745,4 +965,175 @@ mod tests {
 
         assert_eq!(computed_ref, expected_ref.to_vec());
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_proposals_lists_committed_proposals() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        assert_eq!(key_package.commit_proposals(), None);
+
+        #[cfg(feature = "by_ref_proposal")]
+        let proposal = group
+            .propose_group_context_extensions(Default::default(), vec![])
+            .await
+            .unwrap();
+
+        let commit = group
+            .commit_builder()
+            .add_member(key_package.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+            .commit_message;
+
+        let proposals = commit.commit_proposals().unwrap();
+
+        let expected_add = Proposal::Add(alloc::boxed::Box::new(AddProposal {
+            key_package: key_package.into_key_package().unwrap(),
+        }));
+
+        assert!(proposals.contains(&ProposalOrRefView::Proposal(&expected_add)));
+
+        #[cfg(feature = "by_ref_proposal")]
+        {
+            let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+            let proposal_ref = proposal
+                .into_proposal_reference(&cs)
+                .await
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(proposals.len(), 2);
+
+            assert!(proposals.iter().any(
+                |p| matches!(p, ProposalOrRefView::Reference(r) if r.to_vec() == proposal_ref)
+            ));
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn welcome_secret_count_matches_added_members() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let mut commit = group.commit_builder();
+
+        for name in ["bob", "carol", "dave"] {
+            let key_package =
+                test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, name).await;
+
+            commit = commit.add_member(key_package).unwrap();
+        }
+
+        let output = commit.build().await.unwrap();
+        let welcome = &output.welcome_messages[0];
+
+        assert_eq!(welcome.welcome_secret_count(), Some(3));
+        assert_eq!(welcome.welcome_key_package_references().len(), 3);
+        assert_eq!(output.commit_message.welcome_secret_count(), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn large_welcome_can_be_read_from_reader() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let mut commit = group.commit_builder();
+
+        for i in 0..20 {
+            let (_, key_package) =
+                test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, &format!("{i}"))
+                    .await;
+
+            commit = commit.add_member(key_package).unwrap();
+        }
+
+        let welcome = commit.build().await.unwrap().welcome_messages.remove(0);
+        let bytes = welcome.to_bytes().unwrap();
+
+        let decoded = MlsMessage::from_reader(bytes.as_slice(), bytes.len()).unwrap();
+        assert_eq!(decoded, welcome);
+
+        let res = MlsMessage::from_reader(bytes.as_slice(), bytes.len() - 1);
+        assert_matches!(res, Err(MlsError::MessageTooLarge(_)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn oversized_declared_length_is_rejected() {
+        let mut bytes = TEST_PROTOCOL_VERSION.mls_encode_to_vec().unwrap();
+        bytes.extend(WireFormat::Welcome.mls_encode_to_vec().unwrap());
+        bytes.extend(TEST_CIPHER_SUITE.mls_encode_to_vec().unwrap());
+
+        // Varint length prefix declaring a list of secrets close to 1GiB
+        bytes.extend([0xbf, 0xff, 0xff, 0xff]);
+
+        // The declared length is rejected before reading any of the data
+        let res = MlsMessage::from_reader(bytes.as_slice().chain(UnreadableReader), 1024);
+
+        assert_matches!(res, Err(MlsError::MessageTooLarge(1024)));
+    }
+
+    #[cfg(feature = "std")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_package_can_be_read_from_reader() {
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+
+        let bytes = key_package.to_bytes().unwrap();
+
+        let decoded = MlsMessage::from_reader(bytes.as_slice(), bytes.len()).unwrap();
+        assert_eq!(decoded, key_package);
+
+        let res = MlsMessage::from_reader(bytes.as_slice(), bytes.len() - 1);
+        assert_matches!(res, Err(MlsError::MessageTooLarge(_)));
+    }
+
+    #[cfg(feature = "std")]
+    struct UnreadableReader;
+
+    #[cfg(feature = "std")]
+    impl std::io::Read for UnreadableReader {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            panic!("read past the rejected length prefix")
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn fuzz_parse_rejects_malformed_input() {
+        let valid = test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice")
+            .await
+            .mls_encode_to_vec()
+            .unwrap();
+
+        assert_eq!(
+            MlsMessage::fuzz_parse(&valid)
+                .unwrap()
+                .mls_encode_to_vec()
+                .unwrap(),
+            valid
+        );
+
+        let mut trailing = valid.clone();
+        trailing.push(0);
+
+        assert_matches!(
+            MlsMessage::fuzz_parse(&trailing),
+            Err(MlsError::UnexpectedTrailingBytes(1))
+        );
+
+        let mut bad_wire_format = valid.clone();
+        bad_wire_format[2..4].copy_from_slice(&[0xff, 0xff]);
+
+        let mut corpus = vec![vec![], vec![0], vec![0xff; 64], bad_wire_format];
+
+        corpus.extend((0..valid.len()).map(|len| valid[..len].to_vec()));
+        corpus.extend((0..64u8).map(|len| (0..len).map(|i| i.wrapping_mul(37)).collect()));
+
+        for input in corpus {
+            assert!(MlsMessage::fuzz_parse(&input).is_err());
+        }
+    }
 }
@@ -21,7 +21,10 @@ use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 use mls_rs_core::error::IntoAnyError;
 use zeroize::Zeroizing;
 
-use crate::crypto::{HpkeContextR, HpkeContextS, HpkePublicKey, HpkeSecretKey};
+use crate::crypto::{
+    aead_key_size, aead_nonce_size, aead_open, aead_seal, AeadContext, HpkeContextR, HpkeContextS,
+    HpkePublicKey, HpkeSecretKey,
+};
 
 use super::epoch::{EpochSecrets, SenderDataSecret};
 use super::message_signature::AuthenticatedContent;
@@ -192,6 +195,35 @@ impl KeySchedule {
         .await
     }
 
+    /// Derive the first epoch of a new group from a random init secret, injecting
+    /// `psk_secret` into the key schedule.
+    #[cfg(feature = "psk")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn from_random_init_secret<P: CipherSuiteProvider>(
+        cipher_suite_provider: &P,
+        context: &GroupContext,
+        #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+        secret_tree_size: u32,
+        psk_secret: &PskSecret,
+    ) -> Result<KeyScheduleDerivationResult, MlsError> {
+        let init_secret = cipher_suite_provider
+            .random_bytes_vec(cipher_suite_provider.kdf_extract_size())
+            .map(Zeroizing::new)
+            .map(InitSecret)
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
+
+        Self::from_key_schedule(
+            &KeySchedule::new(init_secret),
+            &PathSecret::empty(cipher_suite_provider),
+            context,
+            #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+            secret_tree_size,
+            psk_secret,
+            cipher_suite_provider,
+        )
+        .await
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn from_epoch_secret<P: CipherSuiteProvider>(
         cipher_suite_provider: &P,
@@ -353,6 +385,26 @@ impl From<Zeroizing<Vec<u8>>> for JoinerSecret {
     }
 }
 
+impl JoinerSecret {
+    /// Public value derived from the joiner secret that can be used to
+    /// identify it without revealing it.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn fingerprint<P: CipherSuiteProvider>(
+        &self,
+        cipher_suite_provider: &P,
+    ) -> Result<Vec<u8>, MlsError> {
+        kdf_expand_with_label(
+            cipher_suite_provider,
+            &self.0,
+            b"welcome fingerprint",
+            &[],
+            None,
+        )
+        .await
+        .map(|fingerprint| fingerprint.to_vec())
+    }
+}
+
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 pub(crate) async fn get_pre_epoch_secret<P: CipherSuiteProvider>(
     cipher_suite_provider: &P,
@@ -461,11 +513,11 @@ impl<'a, P: CipherSuiteProvider> WelcomeSecret<'a, P> {
     ) -> Result<WelcomeSecret<'a, P>, MlsError> {
         let welcome_secret = get_welcome_secret(cipher_suite, joiner_secret, psk_secret).await?;
 
-        let key_len = cipher_suite.aead_key_size();
+        let key_len = aead_key_size(cipher_suite, AeadContext::Hpke);
         let key = kdf_expand_with_label(cipher_suite, &welcome_secret, b"key", &[], Some(key_len))
             .await?;
 
-        let nonce_len = cipher_suite.aead_nonce_size();
+        let nonce_len = aead_nonce_size(cipher_suite, AeadContext::Hpke);
 
         let nonce = kdf_expand_with_label(
             cipher_suite,
@@ -485,18 +537,30 @@ impl<'a, P: CipherSuiteProvider> WelcomeSecret<'a, P> {
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub(crate) async fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, MlsError> {
-        self.cipher_suite
-            .aead_seal(&self.key, plaintext, None, &self.nonce)
-            .await
-            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
+        aead_seal(
+            self.cipher_suite,
+            AeadContext::Hpke,
+            &self.key,
+            plaintext,
+            None,
+            &self.nonce,
+        )
+        .await
+        .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
     }
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub(crate) async fn decrypt(&self, ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>, MlsError> {
-        self.cipher_suite
-            .aead_open(&self.key, ciphertext, None, &self.nonce)
-            .await
-            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
+        aead_open(
+            self.cipher_suite,
+            AeadContext::Hpke,
+            &self.key,
+            ciphertext,
+            None,
+            &self.nonce,
+        )
+        .await
+        .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
     }
 }
 
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 use super::{
+    commit::Commit,
     commit_sender,
     confirmation_tag::ConfirmationTag,
     framing::{
@@ -11,17 +12,18 @@ use super::{
     message_signature::AuthenticatedContent,
     mls_rules::{CommitDirection, MlsRules},
     proposal_filter::ProposalBundle,
+    roster::member_from_leaf_node,
     state::GroupState,
     transcript_hash::InterimTranscriptHash,
-    transcript_hashes, validate_group_info_member, GroupContext, GroupInfo, ReInitProposal,
-    RemoveProposal, Welcome,
+    transcript_hashes, validate_group_info_member, GroupContext, GroupInfo, Member, ProposalType,
+    ReInitProposal, RemoveProposal, Welcome,
 };
 use crate::{
     client::MlsError,
     key_package::validate_key_package_properties,
     time::MlsTime,
     tree_kem::{
-        leaf_node_validator::{LeafNodeValidator, ValidationContext},
+        leaf_node_validator::{LeafNodeExtensionValidators, LeafNodeValidator, ValidationContext},
         node::LeafIndex,
         path_secret::PathSecret,
         validate_update_path, TreeKemPrivate, TreeKemPublic, ValidatedUpdatePath,
@@ -35,13 +37,15 @@ use alloc::boxed::Box;
 use alloc::vec::Vec;
 use core::fmt::{self, Debug};
 use mls_rs_core::{
+    error::IntoAnyError,
     identity::{IdentityProvider, MemberValidationContext},
     protocol_version::ProtocolVersion,
     psk::PreSharedKeyStorage,
+    secret::Secret,
 };
 
 #[cfg(feature = "by_ref_proposal")]
-use super::proposal_ref::ProposalRef;
+use super::{proposal_filter::ProposalSource, proposal_ref::ProposalRef};
 
 #[cfg(not(feature = "by_ref_proposal"))]
 use crate::group::proposal_cache::resolve_for_commit;
@@ -60,6 +64,7 @@ pub(crate) struct ProvisionalState {
     pub(crate) external_init_index: Option<LeafIndex>,
     pub(crate) indexes_of_added_kpkgs: Vec<LeafIndex>,
     pub(crate) unused_proposals: Vec<ProposalInfo<Proposal>>,
+    pub(crate) rejected_proposals: Vec<ProposalInfo<Proposal>>,
 }
 
 //By default, the path field of a Commit MUST be populated. The path field MAY be omitted if
@@ -94,21 +99,91 @@ pub struct NewEpoch {
     pub prior_state: GroupState,
     pub applied_proposals: Vec<ProposalInfo<Proposal>>,
     pub unused_proposals: Vec<ProposalInfo<Proposal>>,
+    /// Committed proposals that the local
+    /// [`MlsRules::filter_proposals`](crate::MlsRules::filter_proposals) would
+    /// have filtered out, see
+    /// [`ClientBuilder::warn_on_foreign_rejected_proposals`](crate::client_builder::ClientBuilder::warn_on_foreign_rejected_proposals).
+    pub rejected_proposals: Vec<ProposalInfo<Proposal>>,
+    /// Authenticated data of the proposal messages that were committed by
+    /// reference, along with the reference of each proposal.
+    #[cfg(feature = "by_ref_proposal")]
+    pub proposal_authenticated_data: Vec<(ProposalRef, Vec<u8>)>,
 }
 
 impl NewEpoch {
     pub(crate) fn new(prior_state: GroupState, provisional_state: &ProvisionalState) -> NewEpoch {
+        let applied_proposals = provisional_state
+            .applied_proposals
+            .clone()
+            .into_proposals()
+            .collect_vec();
+
+        #[cfg(feature = "by_ref_proposal")]
+        let proposal_authenticated_data = applied_proposals
+            .iter()
+            .filter_map(|p| match &p.source {
+                ProposalSource::ByReference(r) => prior_state
+                    .proposals
+                    .get(r)
+                    .map(|cached| (r.clone(), cached.authenticated_data.clone())),
+                _ => None,
+            })
+            .collect();
+
         NewEpoch {
             epoch: provisional_state.group_context.epoch,
             prior_state,
             unused_proposals: provisional_state.unused_proposals.clone(),
-            applied_proposals: provisional_state
-                .applied_proposals
-                .clone()
-                .into_proposals()
-                .collect_vec(),
+            rejected_proposals: provisional_state.rejected_proposals.clone(),
+            applied_proposals,
+            #[cfg(feature = "by_ref_proposal")]
+            proposal_authenticated_data,
         }
     }
+
+    /// Authenticated data of the proposal message referenced by
+    /// `proposal_ref`, if it was committed by reference in this epoch.
+    #[cfg(feature = "by_ref_proposal")]
+    pub fn proposal_authenticated_data(&self, proposal_ref: &ProposalRef) -> Option<&[u8]> {
+        self.proposal_authenticated_data
+            .iter()
+            .find_map(|(r, data)| (r == proposal_ref).then_some(data.as_slice()))
+    }
+
+    /// Members removed by this commit, as they were in the prior epoch.
+    pub fn removed_members(&self) -> Vec<Member> {
+        self.applied_proposals
+            .iter()
+            .filter_map(|p| match &p.proposal {
+                Proposal::Remove(remove) => self.prior_state.member_at_index(remove.to_remove()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Identities of the [removed members](NewEpoch::removed_members) as
+    /// reported by `identity_provider` in the context of the prior epoch.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn removed_identities<I: IdentityProvider>(
+        &self,
+        identity_provider: &I,
+    ) -> Result<Vec<Vec<u8>>, MlsError> {
+        let mut identities = Vec::new();
+
+        for member in self.removed_members() {
+            let identity = identity_provider
+                .identity(
+                    &member.signing_identity,
+                    &self.prior_state.context.extensions,
+                )
+                .await
+                .map_err(|e| MlsError::IdentityProviderError(e.into_any_error()))?;
+
+            identities.push(identity);
+        }
+
+        Ok(identities)
+    }
 }
 
 #[cfg(all(feature = "ffi", not(test)))]
@@ -129,6 +204,10 @@ impl NewEpoch {
     pub fn unused_proposals(&self) -> &[ProposalInfo<Proposal>] {
         &self.unused_proposals
     }
+
+    pub fn rejected_proposals(&self) -> &[ProposalInfo<Proposal>] {
+        &self.rejected_proposals
+    }
 }
 
 #[cfg_attr(
@@ -297,6 +376,11 @@ impl ApplicationMessageDescription {
 #[derive(Clone, PartialEq, MlsSize, MlsEncode, MlsDecode)]
 #[non_exhaustive]
 /// Description of a processed MLS commit message.
+///
+/// The MLS encoding of this description is not stable across versions of
+/// this crate. Fields such as `commit_hash` were added to it, changing its
+/// encoding, so an encoded description must be decoded by the same version
+/// of the crate that encoded it.
 pub struct CommitMessageDescription {
     /// True if this is the result of an external commit.
     pub is_external: bool,
@@ -307,6 +391,23 @@ pub struct CommitMessageDescription {
     /// Plaintext authenticated data in the received MLS packet.
     #[mls_codec(with = "mls_rs_codec::byte_vec")]
     pub authenticated_data: Vec<u8>,
+    /// Hash of the authenticated content of the commit, i.e. of its wire
+    /// format, framed content and signature. This value is equal to
+    /// [`CommitOutput::commit_hash`](crate::group::CommitOutput::commit_hash)
+    /// for the committer.
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    pub commit_hash: Vec<u8>,
+    /// Tree node indexes that received new HPKE keys from the update path of
+    /// this commit, i.e. the committer's leaf followed by the nodes of its
+    /// filtered direct path. Empty if the commit has no update path.
+    ///
+    /// Node indexes use the array representation of the tree from RFC 9420
+    /// Appendix C: leaf `i` is node `2 * i` and parent nodes have odd indexes.
+    pub path_updated_nodes: Vec<u32>,
+    /// Indexes of the members whose leaf or direct path received new HPKE keys
+    /// from the update path of this commit, in increasing order. This includes
+    /// the committer. Empty if the commit has no update path.
+    pub path_updated_leaves: Vec<u32>,
 }
 
 impl Debug for CommitMessageDescription {
@@ -319,10 +420,48 @@ impl Debug for CommitMessageDescription {
                 "authenticated_data",
                 &mls_rs_core::debug::pretty_bytes(&self.authenticated_data),
             )
+            .field(
+                "commit_hash",
+                &mls_rs_core::debug::pretty_bytes(&self.commit_hash),
+            )
+            .field("path_updated_nodes", &self.path_updated_nodes)
+            .field("path_updated_leaves", &self.path_updated_leaves)
             .finish()
     }
 }
 
+impl CommitMessageDescription {
+    /// Leaf index of the member that joined the group with this commit, if it
+    /// is an external commit, i.e. a commit containing an `ExternalInit`
+    /// proposal.
+    pub fn external_init_sender(&self) -> Option<u32> {
+        self.is_external.then_some(self.committer)
+    }
+
+    /// True if this commit initiated a reinitialization of the group.
+    pub fn is_reinit(&self) -> bool {
+        matches!(self.effect, CommitEffect::ReInit(_))
+    }
+}
+
+#[cfg_attr(
+    all(feature = "ffi", not(test)),
+    safer_ffi_gen::ffi_type(clone, opaque)
+)]
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+/// A commit that was applied to the group, bundled with the state a caller
+/// typically needs to persist or report after the epoch change.
+pub struct AppliedCommit {
+    /// Description of the applied commit.
+    pub description: CommitMessageDescription,
+    /// Epoch authenticator of the new epoch, or `None` if the commit removed
+    /// the current member from the group.
+    pub epoch_authenticator: Option<Secret>,
+    /// Leaf indexes of the members removed by the commit.
+    pub removed_members: Vec<u32>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, MlsEncode, MlsDecode, MlsSize)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
@@ -388,18 +527,34 @@ impl Debug for ProposalMessageDescription {
 }
 
 #[cfg(feature = "by_ref_proposal")]
-#[derive(MlsSize, MlsEncode, MlsDecode)]
+#[derive(MlsSize, MlsEncode)]
 pub struct CachedProposal {
     pub(crate) proposal: Proposal,
     pub(crate) proposal_ref: ProposalRef,
     pub(crate) sender: Sender,
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    pub(crate) authenticated_data: Vec<u8>,
 }
 
 #[cfg(feature = "by_ref_proposal")]
 impl CachedProposal {
     /// Deserialize the proposal
+    ///
+    /// Proposals serialized before their authenticated data was kept are
+    /// accepted, with empty authenticated data.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, MlsError> {
-        Ok(Self::mls_decode(&mut &*bytes)?)
+        let reader = &mut &*bytes;
+
+        Ok(Self {
+            proposal: Proposal::mls_decode(reader)?,
+            proposal_ref: ProposalRef::mls_decode(reader)?,
+            sender: Sender::mls_decode(reader)?,
+            authenticated_data: if reader.is_empty() {
+                Vec::new()
+            } else {
+                mls_rs_codec::byte_vec::mls_decode(reader)?
+            },
+        })
     }
 
     /// Serialize the proposal
@@ -421,6 +576,7 @@ impl ProposalMessageDescription {
             proposal: self.proposal,
             proposal_ref: self.proposal_ref,
             sender,
+            authenticated_data: self.authenticated_data,
         }
     }
 
@@ -433,12 +589,14 @@ impl ProposalMessageDescription {
         cs: &C,
         content: &AuthenticatedContent,
         proposal: Proposal,
+        proposal_ref_label: &[u8],
     ) -> Result<Self, MlsError> {
         Ok(ProposalMessageDescription {
             authenticated_data: content.content.authenticated_data.clone(),
             proposal,
             sender: content.content.sender.try_into()?,
-            proposal_ref: ProposalRef::from_content(cs, content).await?,
+            proposal_ref: ProposalRef::from_content_with_label(cs, content, proposal_ref_label)
+                .await?,
         })
     }
 }
@@ -631,6 +789,7 @@ pub(crate) trait MessageProcessor: Send + Sync {
             self.cipher_suite_provider(),
             auth_content,
             proposal.clone(),
+            &self.proposal_ref_label(),
         )
         .await?;
 
@@ -641,6 +800,7 @@ pub(crate) trait MessageProcessor: Send + Sync {
                 proposal.proposal_ref.clone(),
                 proposal.proposal.clone(),
                 auth_content.content.sender,
+                proposal.authenticated_data.clone(),
             );
         }
 
@@ -664,6 +824,8 @@ pub(crate) trait MessageProcessor: Send + Sync {
         )
         .await?;
 
+        let commit_hash = auth_content.hash(self.cipher_suite_provider()).await?;
+
         #[cfg(any(feature = "private_message", feature = "by_ref_proposal"))]
         let commit = match auth_content.content.content {
             Content::Commit(commit) => Ok(commit),
@@ -673,56 +835,19 @@ pub(crate) trait MessageProcessor: Send + Sync {
         #[cfg(not(any(feature = "private_message", feature = "by_ref_proposal")))]
         let Content::Commit(commit) = auth_content.content.content;
 
-        let group_state = self.group_state();
-        let id_provider = self.identity_provider();
-
-        #[cfg(feature = "by_ref_proposal")]
-        let proposals = group_state
-            .proposals
-            .resolve_for_commit(auth_content.content.sender, commit.proposals)?;
-
-        #[cfg(not(feature = "by_ref_proposal"))]
-        let proposals = resolve_for_commit(auth_content.content.sender, commit.proposals)?;
-
-        let mut provisional_state = group_state
-            .apply_resolved(
-                auth_content.content.sender,
-                proposals,
-                commit.path.as_ref().map(|path| &path.leaf_node),
-                &id_provider,
-                self.cipher_suite_provider(),
-                &self.psk_storage(),
-                &self.mls_rules(),
-                time_sent,
-                CommitDirection::Receive,
-            )
+        let (mut provisional_state, sender, update_path) = self
+            .validate_commit(auth_content.content.sender, *commit, time_sent)
             .await?;
 
-        let sender = commit_sender(&auth_content.content.sender, &provisional_state)?;
-
-        //Verify that the path value is populated if the proposals vector contains any Update
-        // or Remove proposals, or if it's empty. Otherwise, the path value MAY be omitted.
-        if path_update_required(&provisional_state.applied_proposals) && commit.path.is_none() {
-            return Err(MlsError::CommitMissingPath);
-        }
-
         let self_removed = self.removal_proposal(&provisional_state);
         let is_self_removed = self_removed.is_some();
 
-        let update_path = match commit.path {
-            Some(update_path) => Some(
-                validate_update_path(
-                    &self.identity_provider(),
-                    self.cipher_suite_provider(),
-                    update_path,
-                    &provisional_state,
-                    sender,
-                    time_sent,
-                    &group_state.context,
-                )
-                .await?,
+        let (path_updated_nodes, path_updated_leaves) = match update_path {
+            Some(_) => (
+                provisional_state.public_tree.path_updated_nodes(sender)?,
+                provisional_state.public_tree.path_updated_leaves(sender)?,
             ),
-            None => None,
+            None => Default::default(),
         };
 
         let commit_effect =
@@ -777,8 +902,20 @@ pub(crate) trait MessageProcessor: Send + Sync {
                 .await?;
             }
 
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                group_id = ?mls_rs_core::debug::pretty_group_id(&self.group_state().context.group_id),
+                message_type = "commit",
+                epoch = self.group_state().context.epoch,
+                removed = is_self_removed,
+                "processed commit"
+            );
+
             Ok(CommitMessageDescription {
                 is_external: matches!(auth_content.content.sender, Sender::NewMemberCommit),
+                commit_hash,
+                path_updated_nodes,
+                path_updated_leaves: path_updated_leaves.into_iter().map(|i| *i).collect(),
                 authenticated_data: auth_content.content.authenticated_data,
                 committer: *sender,
                 effect: commit_effect,
@@ -788,6 +925,82 @@ pub(crate) trait MessageProcessor: Send + Sync {
         }
     }
 
+    /// Resolve the proposals of `commit` and validate its update path
+    /// against the current group state, without modifying it.
+    async fn validate_commit(
+        &self,
+        content_sender: Sender,
+        commit: Commit,
+        time_sent: Option<MlsTime>,
+    ) -> Result<(ProvisionalState, LeafIndex, Option<ValidatedUpdatePath>), MlsError> {
+        let group_state = self.group_state();
+        let id_provider = self.identity_provider();
+
+        #[cfg(feature = "by_ref_proposal")]
+        let proposals = group_state
+            .proposals
+            .resolve_for_commit(content_sender, commit.proposals)?;
+
+        #[cfg(not(feature = "by_ref_proposal"))]
+        let proposals = resolve_for_commit(content_sender, commit.proposals)?;
+
+        let provisional_state = group_state
+            .apply_resolved(
+                content_sender,
+                proposals,
+                commit.path.as_ref().map(|path| &path.leaf_node),
+                &id_provider,
+                self.cipher_suite_provider(),
+                &self.psk_storage(),
+                &self.mls_rules(),
+                &self.disallowed_proposal_types(),
+                #[cfg(feature = "psk")]
+                self.max_psks_per_commit(),
+                &self.leaf_node_extension_validators(),
+                time_sent,
+                CommitDirection::Receive,
+                self.warn_on_foreign_rejected_proposals(),
+            )
+            .await?;
+
+        let sender = commit_sender(&content_sender, &provisional_state)?;
+
+        //Verify that the path value is populated if the proposals vector contains any Update
+        // or Remove proposals, or if it's empty. Otherwise, the path value MAY be omitted.
+        if path_update_required(&provisional_state.applied_proposals) && commit.path.is_none() {
+            return Err(MlsError::CommitMissingPath);
+        }
+
+        let update_path = match commit.path {
+            Some(update_path) => Some(
+                validate_update_path(
+                    &id_provider,
+                    self.cipher_suite_provider(),
+                    update_path,
+                    &provisional_state,
+                    sender,
+                    time_sent,
+                    &group_state.context,
+                )
+                .await?,
+            ),
+            None => None,
+        };
+
+        if let (Sender::Member(_), Some(update_path)) = (content_sender, &update_path) {
+            let current = group_state.public_tree.get_leaf_node(sender)?;
+
+            self.mls_rules()
+                .validate_update_path_leaf(
+                    &member_from_leaf_node(current, sender),
+                    &member_from_leaf_node(&update_path.leaf_node, sender),
+                )
+                .map_err(|e| MlsError::MlsRulesError(e.into_any_error()))?;
+        }
+
+        Ok((provisional_state, sender, update_path))
+    }
+
     fn group_state(&self) -> &GroupState;
     fn group_state_mut(&mut self) -> &mut GroupState;
     fn mls_rules(&self) -> Self::MlsRules;
@@ -795,6 +1008,28 @@ pub(crate) trait MessageProcessor: Send + Sync {
     fn cipher_suite_provider(&self) -> &Self::CipherSuiteProvider;
     fn psk_storage(&self) -> Self::PreSharedKeyStorage;
 
+    #[cfg(feature = "by_ref_proposal")]
+    fn proposal_ref_label(&self) -> Vec<u8>;
+
+    fn disallowed_proposal_types(&self) -> Vec<ProposalType> {
+        Vec::new()
+    }
+
+    #[cfg(feature = "psk")]
+    fn max_psks_per_commit(&self) -> Option<usize> {
+        None
+    }
+
+    fn warn_on_foreign_rejected_proposals(&self) -> bool {
+        false
+    }
+
+    fn leaf_node_extension_validators(&self) -> LeafNodeExtensionValidators {
+        Default::default()
+    }
+
+    fn current_time(&self) -> Option<MlsTime>;
+
     fn removal_proposal(
         &self,
         provisional_state: &ProvisionalState,
@@ -828,10 +1063,32 @@ pub(crate) trait MessageProcessor: Send + Sync {
                 return Err(MlsError::GroupIdMismatch);
             }
 
+            // Proposal and commit messages must be sent in the current epoch
+            let check_epoch = content_type == ContentType::Commit;
+
+            #[cfg(feature = "by_ref_proposal")]
+            let check_epoch = check_epoch || content_type == ContentType::Proposal;
+
+            // A proposal or commit from a member removed since it was sent would otherwise
+            // surface as an epoch mismatch.
+            if let MlsMessagePayload::Plain(plaintext) = &message.payload {
+                if let Sender::Member(index) = plaintext.content.sender {
+                    if check_epoch {
+                        self.group_state()
+                            .public_tree
+                            .get_leaf_node(LeafIndex(index))
+                            .map_err(|_| MlsError::SenderNotInGroup(index))?;
+                    }
+                }
+            }
+
             match content_type {
                 ContentType::Commit => {
                     if context.epoch != epoch {
-                        Err(MlsError::InvalidEpoch)
+                        Err(MlsError::CommitEpochMismatch {
+                            current: context.epoch,
+                            commit: epoch,
+                        })
                     } else {
                         Ok(())
                     }
@@ -858,12 +1115,6 @@ pub(crate) trait MessageProcessor: Send + Sync {
                 }
             }?;
 
-            // Proposal and commit messages must be sent in the current epoch
-            let check_epoch = content_type == ContentType::Commit;
-
-            #[cfg(feature = "by_ref_proposal")]
-            let check_epoch = check_epoch || content_type == ContentType::Proposal;
-
             if check_epoch && epoch != context.epoch {
                 return Err(MlsError::InvalidEpoch);
             }
@@ -901,7 +1152,15 @@ pub(crate) trait MessageProcessor: Send + Sync {
         let cs = self.cipher_suite_provider();
         let id = self.identity_provider();
 
-        validate_key_package(key_package, version, cs, &id).await
+        validate_key_package(
+            key_package,
+            version,
+            cs,
+            &id,
+            &self.leaf_node_extension_validators(),
+            self.current_time(),
+        )
+        .await
     }
 
     #[cfg(feature = "private_message")]
@@ -949,16 +1208,44 @@ pub(crate) async fn validate_key_package<C: CipherSuiteProvider, I: IdentityProv
     version: ProtocolVersion,
     cs: &C,
     id: &I,
+    extension_validators: &LeafNodeExtensionValidators,
+    time: Option<MlsTime>,
 ) -> Result<(), MlsError> {
-    let validator = LeafNodeValidator::new(cs, id, MemberValidationContext::None);
+    let validator = LeafNodeValidator::new(cs, id, MemberValidationContext::None)
+        .with_extension_validators(extension_validators);
+
+    validate_key_package_with(key_package, version, cs, validator, time).await
+}
 
-    #[cfg(feature = "std")]
-    let context = Some(MlsTime::now());
+/// Same as [`validate_key_package`] without calling the identity provider to
+/// validate the credential.
+#[cfg(feature = "external_client")]
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+pub(crate) async fn validate_key_package_crypto_only<
+    C: CipherSuiteProvider,
+    I: IdentityProvider,
+>(
+    key_package: &KeyPackage,
+    version: ProtocolVersion,
+    cs: &C,
+    id: &I,
+    time: Option<MlsTime>,
+) -> Result<(), MlsError> {
+    let validator =
+        LeafNodeValidator::new(cs, id, MemberValidationContext::None).without_identity_validation();
 
-    #[cfg(not(feature = "std"))]
-    let context = None;
+    validate_key_package_with(key_package, version, cs, validator, time).await
+}
 
-    let context = ValidationContext::Add(context);
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+async fn validate_key_package_with<C: CipherSuiteProvider, I: IdentityProvider>(
+    key_package: &KeyPackage,
+    version: ProtocolVersion,
+    cs: &C,
+    validator: LeafNodeValidator<'_, I, C>,
+    time: Option<MlsTime>,
+) -> Result<(), MlsError> {
+    let context = ValidationContext::Add(time);
 
     validator
         .check_if_valid(&key_package.leaf_node, context)
@@ -996,6 +1283,9 @@ mod tests {
             },
             applied_proposals: vec![],
             unused_proposals: vec![],
+            rejected_proposals: vec![],
+            #[cfg(feature = "by_ref_proposal")]
+            proposal_authenticated_data: vec![],
         };
 
         let effects = vec![
@@ -5,6 +5,7 @@
 use super::framing::Content;
 use crate::client::MlsError;
 use crate::crypto::SignatureSecretKey;
+use crate::error::IntoAnyError;
 use crate::group::framing::{ContentType, FramedContent, PublicMessage, Sender, WireFormat};
 use crate::group::{ConfirmationTag, GroupContext};
 use crate::signer::Signable;
@@ -135,6 +136,15 @@ impl AuthenticatedContent {
 
         Ok(plaintext)
     }
+
+    /// Hash of the serialized authenticated content, including the wire
+    /// format, used to identify a commit.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn hash<P: CipherSuiteProvider>(&self, cs: &P) -> Result<Vec<u8>, MlsError> {
+        cs.hash(&self.mls_encode_to_vec()?)
+            .await
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
+    }
 }
 
 impl MlsDecode for AuthenticatedContent {
@@ -2,6 +2,7 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
+use crate::client::MlsError;
 use crate::group::{proposal_filter::ProposalBundle, Roster};
 
 #[cfg(feature = "private_message")]
@@ -12,10 +13,17 @@ use crate::{
 
 use alloc::boxed::Box;
 use core::convert::Infallible;
-use mls_rs_core::{error::IntoAnyError, group::Member, identity::SigningIdentity};
+use mls_rs_core::{
+    error::IntoAnyError,
+    group::{Capabilities, Member},
+    identity::SigningIdentity,
+};
 
 use super::GroupContext;
 
+#[cfg(feature = "by_ref_proposal")]
+use crate::group::proposal::UpdateProposal;
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum CommitDirection {
     Send,
@@ -170,6 +178,20 @@ pub trait MlsRules: Send + Sync {
         current_roster: &Roster,
         current_context: &GroupContext,
     ) -> Result<EncryptionOptions, Self::Error>;
+
+    /// This is called when receiving a commit from a member with an update path, after the
+    /// new leaf node of the committer is validated by the standard MLS rules.
+    ///
+    /// `current` describes the committer before the commit and `updated` describes it with
+    /// the leaf node of the update path. By default, the leaf node is accepted.
+    fn validate_update_path_leaf(
+        &self,
+        current: &Member,
+        updated: &Member,
+    ) -> Result<(), Self::Error> {
+        let _ = (current, updated);
+        Ok(())
+    }
 }
 
 macro_rules! delegate_mls_rules {
@@ -209,6 +231,14 @@ macro_rules! delegate_mls_rules {
             ) -> Result<EncryptionOptions, Self::Error> {
                 (**self).encryption_options(roster, context)
             }
+
+            fn validate_update_path_leaf(
+                &self,
+                current: &Member,
+                updated: &Member,
+            ) -> Result<(), Self::Error> {
+                (**self).validate_update_path_leaf(current, updated)
+            }
         }
     };
 }
@@ -281,3 +311,117 @@ impl MlsRules for DefaultMlsRules {
         Ok(self.encryption_options)
     }
 }
+
+/// MLS rules that reject updates removing proposal, extension or credential
+/// types that the updated member currently advertises in its capabilities, on
+/// top of the `inner` rules.
+///
+/// This prevents a member from downgrading its capabilities, for example to
+/// stop supporting a proposal type the group relies on. Received commits with
+/// such an update, either as an update proposal or as the leaf node of the
+/// committer's update path, are rejected with [`MlsError::CapabilityDowngrade`].
+/// When preparing a commit, such updates received by reference are filtered out.
+#[derive(Clone, Debug, Default)]
+pub struct CapabilityMonotonicityRules<R = DefaultMlsRules> {
+    inner: R,
+}
+
+impl<R> CapabilityMonotonicityRules<R> {
+    /// Enforce monotonic capabilities on top of `inner`.
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl<R: MlsRules> MlsRules for CapabilityMonotonicityRules<R> {
+    type Error = MlsError;
+
+    async fn filter_proposals(
+        &self,
+        direction: CommitDirection,
+        source: CommitSource,
+        current_roster: &Roster,
+        current_context: &GroupContext,
+        proposals: ProposalBundle,
+    ) -> Result<ProposalBundle, Self::Error> {
+        #[cfg_attr(not(feature = "by_ref_proposal"), allow(unused_mut))]
+        let mut proposals = self
+            .inner
+            .filter_proposals(
+                direction,
+                source,
+                current_roster,
+                current_context,
+                proposals,
+            )
+            .await
+            .map_err(|e| MlsError::MlsRulesError(e.into_any_error()))?;
+
+        #[cfg(feature = "by_ref_proposal")]
+        proposals.retain_by_type::<UpdateProposal, _, _>(|p| {
+            let super::Sender::Member(index) = p.sender else {
+                return Ok(true);
+            };
+
+            let current = current_roster.member_with_index(index)?.capabilities;
+            let downgrade = is_capability_downgrade(&current, &p.proposal.capabilities());
+
+            match direction {
+                _ if !downgrade => Ok(true),
+                CommitDirection::Send if p.is_by_reference() => Ok(false),
+                _ => Err(MlsError::CapabilityDowngrade(index)),
+            }
+        })?;
+
+        Ok(proposals)
+    }
+
+    fn commit_options(
+        &self,
+        new_roster: &Roster,
+        new_context: &GroupContext,
+        proposals: &ProposalBundle,
+    ) -> Result<CommitOptions, Self::Error> {
+        self.inner
+            .commit_options(new_roster, new_context, proposals)
+            .map_err(|e| MlsError::MlsRulesError(e.into_any_error()))
+    }
+
+    fn encryption_options(
+        &self,
+        current_roster: &Roster,
+        current_context: &GroupContext,
+    ) -> Result<EncryptionOptions, Self::Error> {
+        self.inner
+            .encryption_options(current_roster, current_context)
+            .map_err(|e| MlsError::MlsRulesError(e.into_any_error()))
+    }
+
+    fn validate_update_path_leaf(
+        &self,
+        current: &Member,
+        updated: &Member,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .validate_update_path_leaf(current, updated)
+            .map_err(|e| MlsError::MlsRulesError(e.into_any_error()))?;
+
+        if is_capability_downgrade(&current.capabilities, &updated.capabilities) {
+            return Err(MlsError::CapabilityDowngrade(updated.index));
+        }
+
+        Ok(())
+    }
+}
+
+fn is_capability_downgrade(current: &Capabilities, proposed: &Capabilities) -> bool {
+    fn removes_any<T: PartialEq>(current: &[T], proposed: &[T]) -> bool {
+        current.iter().any(|t| !proposed.contains(t))
+    }
+
+    removes_any(&current.proposals, &proposed.proposals)
+        || removes_any(&current.extensions, &proposed.extensions)
+        || removes_any(&current.credentials, &proposed.credentials)
+}
@@ -7,9 +7,8 @@ use alloc::vec::Vec;
 use core::fmt::{self, Debug};
 use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 use mls_rs_core::error::IntoAnyError;
-#[cfg(feature = "last_resort_key_package_ext")]
 use mls_rs_core::extension::MlsExtension;
-use mls_rs_core::identity::MemberValidationContext;
+use mls_rs_core::identity::{IdentityProvider, MemberValidationContext};
 use mls_rs_core::secret::Secret;
 use mls_rs_core::time::MlsTime;
 use snapshot::PendingCommitSnapshot;
@@ -30,23 +29,31 @@ use crate::signer::Signable;
 use crate::tree_kem::hpke_encryption::HpkeEncryptable;
 use crate::tree_kem::kem::TreeKem;
 use crate::tree_kem::leaf_node::LeafNode;
-use crate::tree_kem::leaf_node_validator::{LeafNodeValidator, ValidationContext};
+pub use crate::tree_kem::leaf_node_validator::LeafNodeValidationError;
+use crate::tree_kem::leaf_node_validator::{
+    LeafNodeExtensionValidators, LeafNodeValidator, ValidationContext,
+};
 use crate::tree_kem::node::LeafIndex;
 use crate::tree_kem::path_secret::PathSecret;
 pub use crate::tree_kem::Capabilities;
 use crate::tree_kem::{math as tree_math, ValidatedUpdatePath};
 use crate::tree_kem::{TreeKemPrivate, TreeKemPublic};
 use crate::{CipherSuiteProvider, CryptoProvider};
+
 pub use state::GroupState;
 
 #[cfg(feature = "by_ref_proposal")]
 use crate::crypto::{HpkePublicKey, HpkeSecretKey};
+#[cfg(feature = "by_ref_proposal")]
+use crate::extension::ExternalSendersExt;
 
 use crate::extension::ExternalPubExt;
 
 use self::message_hash::MessageHash;
 #[cfg(feature = "private_message")]
 use self::mls_rules::{EncryptionOptions, MlsRules};
+#[cfg(feature = "private_message")]
+use self::padding::PaddingMode;
 
 #[cfg(feature = "psk")]
 pub use self::resumption::ReinitClient;
@@ -60,7 +67,6 @@ use crate::psk::{
 #[cfg(feature = "private_message")]
 use ciphertext_processor::*;
 
-use confirmation_tag::*;
 use framing::*;
 use key_schedule::*;
 use membership_tag::*;
@@ -87,7 +93,7 @@ use self::epoch::PriorEpoch;
 
 use self::epoch::EpochSecrets;
 pub use self::message_processor::{
-    ApplicationMessageDescription, CommitEffect, CommitMessageDescription, NewEpoch,
+    ApplicationMessageDescription, AppliedCommit, CommitEffect, CommitMessageDescription, NewEpoch,
     ProposalMessageDescription, ProposalSender, ReceivedMessage,
 };
 use self::message_processor::{EventOrContent, MessageProcessor, ProvisionalState};
@@ -110,6 +116,10 @@ pub use self::message_processor::CachedProposal;
 #[cfg(feature = "private_message")]
 mod ciphertext_processor;
 
+#[cfg(all(feature = "private_message", any(test, feature = "test_util")))]
+#[doc(hidden)]
+pub use ciphertext_processor::EncryptionModifiers;
+
 mod commit;
 pub(crate) mod confirmation_tag;
 pub(crate) mod epoch;
@@ -162,6 +172,30 @@ mod exported_tree;
 
 pub use exported_tree::ExportedTree;
 
+#[cfg(feature = "private_message")]
+mod sealed_epoch_secrets;
+
+#[cfg(feature = "private_message")]
+pub use sealed_epoch_secrets::SealedEpochSecrets;
+
+pub use confirmation_tag::{confirmation_tag_for, ConfirmationTag};
+
+pub use transcript_hash::recompute_transcript_hashes;
+
+pub use snapshot::SnapshotFormat;
+
+/// Label defined by RFC 9420 for the HPKE encryption of the group secrets in
+/// welcome messages.
+pub(crate) const WELCOME_HPKE_LABEL: &[u8] = b"MLS 1.0 Welcome";
+
+/// Exporter label used by [`Group::derive_epoch_binding`].
+const EPOCH_BINDING_LABEL: &[u8] = b"mls-rs epoch binding";
+
+/// Upper bound on the bytes a private application message adds to its data, other than
+/// padding: the encoded data length, the signature and the AEAD tag.
+#[cfg(feature = "private_message")]
+const MAX_APPLICATION_CIPHERTEXT_OVERHEAD: usize = 8192;
+
 #[derive(Clone, Debug, PartialEq, MlsSize, MlsEncode, MlsDecode)]
 struct GroupSecrets {
     joiner_secret: JoinerSecret,
@@ -240,6 +274,16 @@ impl NewMemberInfo {
         new_member_info
     }
 
+    /// Get a group info extension found within the Welcome message, decoded
+    /// as `T`.
+    ///
+    /// Returns `None` if the inviter did not include an extension of type
+    /// `T`.
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn group_info_extension<T: MlsExtension>(&self) -> Result<Option<T>, MlsError> {
+        self.group_info_extensions.get_as().map_err(Into::into)
+    }
+
     /// Group info extensions found within the Welcome message used to join
     /// the group.
     #[cfg(feature = "ffi")]
@@ -248,6 +292,22 @@ impl NewMemberInfo {
     }
 }
 
+/// Result of comparing epoch authenticators with another member using
+/// [`Group::fork_check`].
+#[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::ffi_type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ForkStatus {
+    /// Both members are in the same epoch of the same group state.
+    Same,
+    /// The authenticator does not match the current or any retained prior
+    /// epoch, indicating that the members have diverging views of the group.
+    Diverged,
+    /// The authenticator matches a retained prior epoch. The other member
+    /// has not yet processed the latest commits.
+    DifferentEpoch,
+}
+
 /// An MLS end-to-end encrypted group.
 ///
 /// # Group Evolution
@@ -279,7 +339,14 @@ where
     previous_psk: Option<PskSecretInput>,
     #[cfg(test)]
     pub(crate) commit_modifiers: CommitModifiers,
+    #[cfg(all(feature = "private_message", any(test, feature = "test_util")))]
+    pub(crate) encryption_modifiers: EncryptionModifiers,
     pub(crate) signer: SignatureSecretKey,
+    // Hash of the context of the first epoch known to this member, empty if
+    // the group was loaded from a snapshot that predates it.
+    origin_hash: Vec<u8>,
+    pub(crate) staged_proposals: Vec<Proposal>,
+    last_removed: Vec<Member>,
 }
 
 #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen)]
@@ -298,6 +365,7 @@ where
         group_context_extensions: ExtensionList,
         leaf_node_extensions: ExtensionList,
         signer: SignatureSecretKey,
+        #[cfg(feature = "psk")] psks: Vec<ExternalPskId>,
     ) -> Result<Self, MlsError> {
         let cipher_suite_provider = cipher_suite_provider(config.crypto_provider(), cipher_suite)?;
 
@@ -359,8 +427,46 @@ where
             config.group_state_storage(),
             config.key_package_repo(),
             None,
+            #[cfg(feature = "prior_epoch")]
+            config.max_retained_epochs(),
         )?;
 
+        #[cfg(feature = "psk")]
+        let key_schedule_result = if psks.is_empty() {
+            KeySchedule::from_random_epoch_secret(
+                &cipher_suite_provider,
+                #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+                public_tree.total_leaf_count(),
+            )
+            .await?
+        } else {
+            let psk_ids = psks
+                .into_iter()
+                .map(|id| {
+                    PreSharedKeyID::new(JustPreSharedKeyID::External(id), &cipher_suite_provider)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let psk_secret = PskResolver {
+                group_context: None,
+                current_epoch: None,
+                prior_epochs: Some(&state_repo),
+                psk_store: &config.secret_store(),
+            }
+            .resolve_to_secret(&psk_ids, &cipher_suite_provider)
+            .await?;
+
+            KeySchedule::from_random_init_secret(
+                &cipher_suite_provider,
+                &context,
+                #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+                public_tree.total_leaf_count(),
+                &psk_secret,
+            )
+            .await?
+        };
+
+        #[cfg(not(feature = "psk"))]
         let key_schedule_result = KeySchedule::from_random_epoch_secret(
             &cipher_suite_provider,
             #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
@@ -382,6 +488,8 @@ where
         )
         .await?;
 
+        let origin_hash = Self::origin_hash(&cipher_suite_provider, &context).await?;
+
         Ok(Self {
             config,
             state: GroupState::new(context, public_tree, interim_hash, confirmation_tag),
@@ -392,12 +500,17 @@ where
             pending_commit: Default::default(),
             #[cfg(test)]
             commit_modifiers: Default::default(),
+            #[cfg(all(feature = "private_message", any(test, feature = "test_util")))]
+            encryption_modifiers: Default::default(),
             epoch_secrets: key_schedule_result.epoch_secrets,
             state_repo,
             cipher_suite_provider,
             #[cfg(feature = "psk")]
             previous_psk: None,
             signer,
+            origin_hash,
+            staged_proposals: Vec::new(),
+            last_removed: Vec::new(),
         })
     }
 
@@ -447,11 +560,42 @@ where
             welcome.version,
             &group_info,
             tree_data,
+            config.max_ratchet_tree_size(),
             &id_provider,
             &cipher_suite_provider,
         )
         .await?;
 
+        if config.reject_unknown_group_info_extensions() {
+            let supported = config.supported_extensions();
+
+            if let Some(unknown) = group_info
+                .extensions
+                .iter()
+                .map(|ext| ext.extension_type)
+                .find(|ext_type| !ext_type.is_default() && !supported.contains(ext_type))
+            {
+                return Err(MlsError::UnknownGroupInfoExtension(unknown));
+            }
+        }
+
+        // The group info signature was verified against the inviter's leaf above.
+        if let Some(allowed_inviters) = config.allowed_inviters() {
+            let inviter = public_tree.get_leaf_node(group_info.signer)?;
+
+            let inviter_identity = id_provider
+                .identity(
+                    &inviter.signing_identity,
+                    &group_info.group_context.extensions,
+                )
+                .await
+                .map_err(|e| MlsError::IdentityProviderError(e.into_any_error()))?;
+
+            if !allowed_inviters.contains(&inviter_identity) {
+                return Err(MlsError::UnauthorizedInviter);
+            }
+        }
+
         let key_package = key_package_generation.key_package;
 
         // Identify a leaf in the tree array (any even-numbered node) whose leaf_node is identical
@@ -527,6 +671,18 @@ where
 
     #[allow(clippy::too_many_arguments)]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "join",
+            level = "debug",
+            skip_all,
+            fields(
+                group_id = ?mls_rs_core::debug::pretty_group_id(&group_info.group_context.group_id),
+                epoch = group_info.group_context.epoch,
+            )
+        )
+    )]
     async fn join_with(
         config: C,
         group_info: GroupInfo,
@@ -559,8 +715,12 @@ where
             config.group_state_storage(),
             config.key_package_repo(),
             used_key_package_ref,
+            #[cfg(feature = "prior_epoch")]
+            config.max_retained_epochs(),
         )?;
 
+        let origin_hash = Self::origin_hash(&cs, &group_info.group_context).await?;
+
         let group = Group {
             config,
             state: GroupState::new(
@@ -576,14 +736,22 @@ where
             pending_commit: Default::default(),
             #[cfg(test)]
             commit_modifiers: Default::default(),
+            #[cfg(all(feature = "private_message", any(test, feature = "test_util")))]
+            encryption_modifiers: Default::default(),
             epoch_secrets,
             state_repo,
             cipher_suite_provider: cs,
             #[cfg(feature = "psk")]
             previous_psk: None,
             signer,
+            origin_hash,
+            staged_proposals: Vec::new(),
+            last_removed: Vec::new(),
         };
 
+        #[cfg(feature = "tracing")]
+        tracing::debug!(leaf_index = *group.private_tree.self_index, "joined group");
+
         Ok((
             group,
             NewMemberInfo::new(group_info.extensions, *group_info.signer),
@@ -597,6 +765,8 @@ where
 
     /// The current epoch of the group. This value is incremented each
     /// time a [`Group::commit`] message is processed.
+    ///
+    /// This is the same value as the `epoch` of [`Group::context`].
     #[inline(always)]
     pub fn current_epoch(&self) -> u64 {
         self.context().epoch
@@ -617,6 +787,11 @@ where
     }
 
     /// Signing identity currently in use by the local group instance.
+    ///
+    /// This is the identity in the local member's leaf for the current epoch.
+    /// A new identity set with
+    /// [`CommitBuilder::set_new_signing_identity`](crate::group::CommitBuilder::set_new_signing_identity)
+    /// is returned only once the corresponding commit is applied.
     #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
     pub fn current_member_signing_identity(&self) -> Result<&SigningIdentity, MlsError> {
         self.current_user_leaf_node().map(|ln| &ln.signing_identity)
@@ -655,9 +830,13 @@ where
 
         let sender = auth_content.content.sender;
 
-        let proposal_desc =
-            ProposalMessageDescription::new(&self.cipher_suite_provider, &auth_content, proposal)
-                .await?;
+        let proposal_desc = ProposalMessageDescription::new(
+            &self.cipher_suite_provider,
+            &auth_content,
+            proposal,
+            &self.config.proposal_ref_label(),
+        )
+        .await?;
 
         let message = self.format_for_wire(auth_content).await?;
 
@@ -674,6 +853,35 @@ where
         &self.context().group_id
     }
 
+    /// Returns true if `other` is a handle to the same group as `self`, possibly
+    /// at a different epoch.
+    ///
+    /// Unlike comparing [`Group::group_id`], this tells apart a group and a
+    /// group that reuses its id, such as the successor created by a
+    /// [`ReInitProposal`](crate::group::proposal::ReInitProposal). Groups are
+    /// identified by their id and the context of the first epoch known to this
+    /// member, i.e. the epoch in which the group was created or joined, so
+    /// handles held by different members may not compare as the same group.
+    /// Handles loaded from state written by a version of this library that
+    /// did not record the first epoch are compared by group id only.
+    pub fn same_group_as(&self, other: &Group<C>) -> bool {
+        self.group_id() == other.group_id()
+            && (self.origin_hash.is_empty()
+                || other.origin_hash.is_empty()
+                || self.origin_hash == other.origin_hash)
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn origin_hash<P: CipherSuiteProvider>(
+        cipher_suite_provider: &P,
+        context: &GroupContext,
+    ) -> Result<Vec<u8>, MlsError> {
+        cipher_suite_provider
+            .hash(&context.mls_encode_to_vec()?)
+            .await
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
+    }
+
     fn provisional_private_tree(
         &self,
         provisional_state: &ProvisionalState,
@@ -731,10 +939,12 @@ where
         Ok((provisional_private_tree, new_signer))
     }
 
+    #[allow(clippy::too_many_arguments)]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn encrypt_group_secrets(
         &self,
         key_package: &KeyPackage,
+        self_index: LeafIndex,
         leaf_index: LeafIndex,
         joiner_secret: &JoinerSecret,
         path_secrets: Option<&Vec<Option<PathSecret>>>,
@@ -744,11 +954,7 @@ where
         let path_secret = path_secrets
             .map(|secrets| {
                 secrets
-                    .get(
-                        tree_math::leaf_lca_level(*self.private_tree.self_index, *leaf_index)
-                            as usize
-                            - 1,
-                    )
+                    .get(tree_math::leaf_lca_level(*self_index, *leaf_index) as usize - 1)
                     .cloned()
                     .flatten()
                     .ok_or(MlsError::InvalidTreeKemPrivateKey)
@@ -765,9 +971,10 @@ where
         };
 
         let encrypted_group_secrets = group_secrets
-            .encrypt(
+            .encrypt_with_label(
                 &self.cipher_suite_provider,
                 &key_package.hpke_init_key,
+                &self.config.welcome_hpke_label(),
                 encrypted_group_info,
             )
             .await?;
@@ -796,10 +1003,19 @@ where
     }
 
     fn add_proposal(&self, key_package: MlsMessage) -> Result<Proposal, MlsError> {
+        let key_package = key_package
+            .into_key_package()
+            .ok_or(MlsError::UnexpectedMessageType)?;
+
+        if key_package.cipher_suite != self.cipher_suite() {
+            return Err(MlsError::KeyPackageCipherSuiteMismatch {
+                expected: self.cipher_suite(),
+                found: key_package.cipher_suite,
+            });
+        }
+
         Ok(Proposal::Add(alloc::boxed::Box::new(AddProposal {
-            key_package: key_package
-                .into_key_package()
-                .ok_or(MlsError::UnexpectedMessageType)?,
+            key_package,
         })))
     }
 
@@ -1065,6 +1281,51 @@ where
         self.state.proposals.clear()
     }
 
+    /// Delete the signing keys of this member's own update proposals that are
+    /// no longer cached in the current epoch, for example after calling
+    /// [`Group::clear_proposal_cache`].
+    ///
+    /// Only signers kept for pending update proposals are affected. These are
+    /// dropped anyway when the group moves to a new epoch and prior epochs do
+    /// not retain any signing key. The signing key used by the current leaf is
+    /// never removed.
+    #[cfg(feature = "by_ref_proposal")]
+    pub fn prune_uncached_update_signers(&mut self) {
+        let self_sender = Sender::Member(*self.private_tree.self_index);
+
+        #[cfg(feature = "std")]
+        let cached = self.state.proposals.proposals.values();
+
+        #[cfg(not(feature = "std"))]
+        let cached = self.state.proposals.proposals.iter().map(|(_, p)| p);
+
+        let referenced = cached
+            .filter(|p| p.sender == self_sender)
+            .filter_map(|p| match &p.proposal {
+                Proposal::Update(update) => Some(&update.leaf_node.public_key),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let is_used = |public_key: &HpkePublicKey, signer: &Option<SignatureSecretKey>| {
+            signer.is_none() || referenced.contains(&public_key)
+        };
+
+        #[cfg(feature = "std")]
+        self.pending_updates
+            .retain(|public_key, (_, signer)| is_used(public_key, signer));
+
+        #[cfg(not(feature = "std"))]
+        self.pending_updates
+            .retain(|(public_key, (_, signer))| is_used(public_key, signer));
+    }
+
+    #[cfg(any(
+        feature = "private_message",
+        feature = "by_ref_proposal",
+        test,
+        feature = "test_util"
+    ))]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub(crate) async fn format_for_wire(
         &mut self,
@@ -1082,6 +1343,43 @@ where
         Ok(MlsMessage::new(self.protocol_version(), payload))
     }
 
+    /// Same as [`Group::format_for_wire`] except that the group is left
+    /// unchanged. If `content` is encrypted, the epoch secrets with the
+    /// advanced sending ratchet are returned and must replace the epoch
+    /// secrets of the group before the message is sent.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn format_for_wire_detached(
+        &self,
+        content: AuthenticatedContent,
+    ) -> Result<(MlsMessage, Option<EpochSecrets>), MlsError> {
+        #[cfg(feature = "private_message")]
+        if content.wire_format == WireFormat::PrivateMessage {
+            let padding_mode = self.encryption_options()?.padding_mode;
+
+            let mut state = DetachedGroupState {
+                context: self.context(),
+                self_index: self.private_tree.self_index,
+                secrets: self.epoch_secrets.clone(),
+            };
+
+            let ciphertext =
+                CiphertextProcessor::new(&mut state, self.cipher_suite_provider.clone())
+                    .seal(content, padding_mode)
+                    .await?;
+
+            let payload = MlsMessagePayload::Cipher(ciphertext);
+
+            return Ok((
+                MlsMessage::new(self.protocol_version(), payload),
+                Some(state.secrets),
+            ));
+        }
+
+        let payload = MlsMessagePayload::Plain(self.create_plaintext(content).await?);
+
+        Ok((MlsMessage::new(self.protocol_version(), payload), None))
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn create_plaintext(
         &self,
@@ -1136,6 +1434,8 @@ where
             return Err(MlsError::CommitRequired);
         }
 
+        self.check_application_message_len(message.len())?;
+
         let auth_content = AuthenticatedContent::new_signed(
             &self.cipher_suite_provider,
             self.context(),
@@ -1150,12 +1450,114 @@ where
         self.format_for_wire(auth_content).await
     }
 
+    /// Set hooks used to tamper with messages encrypted by this group.
+    ///
+    /// This is only meant to test that receivers reject malformed messages.
+    #[cfg(all(feature = "private_message", feature = "test_util"))]
+    #[doc(hidden)]
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn set_encryption_modifiers(&mut self, encryption_modifiers: EncryptionModifiers) {
+        self.encryption_modifiers = encryption_modifiers;
+    }
+
+    /// Advance the application sender ratchet of this member by `count`
+    /// generations without sending any messages.
+    ///
+    /// The keys for the skipped generations are derived and immediately
+    /// discarded, so an attacker that learned the current state of the ratchet
+    /// cannot use it to decrypt the next messages sent by this member. Receivers
+    /// derive keys on demand and will accept the next message as long as its
+    /// generation does not exceed the maximum forward jump they allow from the
+    /// start of the epoch. This is enforced here, taking into account messages
+    /// sent and generations skipped earlier in the epoch.
+    #[cfg(feature = "private_message")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn skip_application_generations(&mut self, count: u32) -> Result<(), MlsError> {
+        let self_index = crate::tree_kem::node::NodeIndex::from(self.private_tree.self_index);
+
+        self.epoch_secrets
+            .secret_tree
+            .skip_generations(
+                &self.cipher_suite_provider,
+                self_index,
+                KeyType::Application,
+                count,
+            )
+            .await
+    }
+
+    /// Decrypt an application message sent in the current epoch without
+    /// modifying the group state.
+    ///
+    /// The message keys of the sender are derived from a copy of the current
+    /// epoch secrets, so the message can still be processed with
+    /// [`Group::process_incoming_message`] afterwards. Since the secret ratchet
+    /// only moves forward, a message can only be peeked at as long as the key
+    /// for its generation has not been consumed by processing it or a later
+    /// message.
+    #[cfg(feature = "private_message")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn peek_application_message(
+        &self,
+        message: &MlsMessage,
+    ) -> Result<Vec<u8>, MlsError> {
+        let MlsMessagePayload::Cipher(ciphertext) = &message.payload else {
+            return Err(MlsError::UnexpectedMessageType);
+        };
+
+        if ciphertext.group_id != self.context().group_id {
+            return Err(MlsError::GroupIdMismatch);
+        }
+
+        if ciphertext.epoch != self.context().epoch {
+            return Err(MlsError::InvalidEpoch);
+        }
+
+        if ciphertext.content_type != ContentType::Application {
+            return Err(MlsError::UnexpectedMessageType);
+        }
+
+        self.check_application_ciphertext_len(ciphertext)?;
+
+        let mut state = DetachedGroupState {
+            context: self.context(),
+            self_index: self.private_tree.self_index,
+            secrets: self.epoch_secrets.clone(),
+        };
+
+        let content = CiphertextProcessor::new(&mut state, self.cipher_suite_provider.clone())
+            .open(ciphertext)
+            .await?;
+
+        verify_auth_content_signature(
+            &self.cipher_suite_provider,
+            SignaturePublicKeysContainer::RatchetTree(&self.state.public_tree),
+            self.context(),
+            &content,
+            #[cfg(feature = "by_ref_proposal")]
+            &[],
+        )
+        .await?;
+
+        match content.content.content {
+            Content::Application(data) => {
+                self.check_application_message_len(data.as_bytes().len())?;
+                Ok(data.as_bytes().to_vec())
+            }
+            _ => Err(MlsError::UnexpectedMessageType),
+        }
+    }
+
     #[cfg(feature = "private_message")]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn decrypt_incoming_ciphertext(
         &mut self,
         message: &PrivateMessage,
     ) -> Result<AuthenticatedContent, MlsError> {
+        if message.content_type == ContentType::Application {
+            self.check_application_ciphertext_len(message)?;
+        }
+
         let epoch_id = message.epoch;
 
         let auth_content = if epoch_id == self.context().epoch {
@@ -1204,9 +1606,40 @@ where
             Err(MlsError::EpochNotFound)
         }?;
 
+        if let Content::Application(data) = &auth_content.content.content {
+            self.check_application_message_len(data.as_bytes().len())?;
+        }
+
         Ok(auth_content)
     }
 
+    #[cfg(feature = "private_message")]
+    fn check_application_message_len(&self, len: usize) -> Result<(), MlsError> {
+        match self.config.max_application_message_len() {
+            Some(max_len) if len > max_len => Err(MlsError::ApplicationMessageTooLarge(len)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reject an application message before decrypting it if its ciphertext is larger than
+    /// any message within the maximum length, padded with [`PaddingMode::StepFunction`].
+    #[cfg(feature = "private_message")]
+    fn check_application_ciphertext_len(&self, message: &PrivateMessage) -> Result<(), MlsError> {
+        let max_ciphertext_len = self
+            .config
+            .max_application_message_len()
+            .and_then(|max_len| max_len.checked_add(MAX_APPLICATION_CIPHERTEXT_OVERHEAD))
+            .filter(|max_content_len| *max_content_len <= u32::MAX as usize)
+            .map(|max_content_len| PaddingMode::StepFunction.padded_size(max_content_len));
+
+        match max_ciphertext_len {
+            Some(max_len) if message.ciphertext.len() > max_len => Err(
+                MlsError::ApplicationMessageTooLarge(message.ciphertext.len()),
+            ),
+            _ => Ok(()),
+        }
+    }
+
     /// Apply a pending commit that was created by [`Group::commit`] or
     /// [`CommitBuilder::build`].
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -1215,6 +1648,53 @@ where
         self.apply_detached_commit(CommitSecrets(pending)).await
     }
 
+    /// Apply a pending commit like [`Group::apply_pending_commit`] and bundle
+    /// the result with the new epoch's details, see [`Group::commit_details`].
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn apply_pending_commit_with_details(&mut self) -> Result<AppliedCommit, MlsError> {
+        let description = self.apply_pending_commit().await?;
+        self.commit_details(description)
+    }
+
+    /// Bundle the description of the most recently applied commit, either
+    /// returned by [`Group::apply_pending_commit`] or received as
+    /// [`ReceivedMessage::Commit`], with the epoch authenticator of the
+    /// resulting epoch and the indexes of removed members.
+    ///
+    /// Returns [`MlsError::InvalidEpoch`] if `description` does not describe
+    /// the commit that created the current epoch.
+    pub fn commit_details(
+        &self,
+        description: CommitMessageDescription,
+    ) -> Result<AppliedCommit, MlsError> {
+        let (new_epoch, removed) = match &description.effect {
+            CommitEffect::NewEpoch(new_epoch) => (Some(new_epoch), false),
+            CommitEffect::Removed { new_epoch, .. } => (Some(new_epoch), true),
+            CommitEffect::ReInit(_) => (None, false),
+        };
+
+        if matches!(new_epoch, Some(e) if !removed && e.epoch != self.current_epoch()) {
+            return Err(MlsError::InvalidEpoch);
+        }
+
+        let removed_members = new_epoch
+            .iter()
+            .flat_map(|e| e.applied_proposals.iter())
+            .filter_map(|p| match &p.proposal {
+                Proposal::Remove(remove) => Some(remove.to_remove()),
+                _ => None,
+            })
+            .collect();
+
+        let epoch_authenticator = (!removed).then(|| self.epoch_authenticator()).transpose()?;
+
+        Ok(AppliedCommit {
+            description,
+            epoch_authenticator,
+            removed_members,
+        })
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn apply_pending_commit_backwards_compatible(
         &mut self,
@@ -1244,6 +1724,11 @@ where
         self.key_schedule = pending.key_schedule;
         self.signer = pending.signer;
 
+        self.last_removed = match &pending.output.effect {
+            CommitEffect::NewEpoch(new_epoch) => new_epoch.removed_members(),
+            _ => Vec::new(),
+        };
+
         Ok(pending.output)
     }
 
@@ -1287,47 +1772,236 @@ where
         !self.state.proposals.is_empty()
     }
 
-    /// Process an inbound message for this group.
+    /// Proposals cached for commit along with the member that proposed them,
+    /// resolved in the current epoch.
     ///
-    /// # Warning
+    /// The proposer is `None` for proposals sent by external senders or new
+    /// members. The order of the returned proposals is unspecified.
+    #[cfg(feature = "by_ref_proposal")]
+    pub fn pending_proposals_with_proposer(&self) -> Vec<(ProposalRef, Proposal, Option<Member>)> {
+        self.state
+            .proposals
+            .proposals
+            .iter()
+            .map(|(proposal_ref, cached)| {
+                let proposer = match cached.sender {
+                    Sender::Member(index) => self.member_at_index(index),
+                    _ => None,
+                };
+
+                (proposal_ref.clone(), cached.proposal.clone(), proposer)
+            })
+            .collect()
+    }
+
+    /// References of the cached proposals that the next commit created by
+    /// this member must include.
     ///
-    /// Changes to the group's state as a result of processing `message` will
-    /// not be persisted by the
-    /// [`GroupStateStorage`](crate::GroupStateStorage)
-    /// in use by this group until [`Group::write_to_storage`] is called.
+    /// RFC 9420 requires a commit to include all valid proposals received in
+    /// the current epoch. Cached proposals that are invalid for this member
+    /// to commit, e.g. an update superseded by a remove of the same member or
+    /// this member's own update, as well as those filtered out by the
+    /// [`MlsRules`] in use by this group, are not required.
+    #[cfg(feature = "by_ref_proposal")]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    #[inline(never)]
-    pub async fn process_incoming_message(
-        &mut self,
-        message: MlsMessage,
-    ) -> Result<ReceivedMessage, MlsError> {
-        if let Some(pending) = self.pending_commit.commit_hash()? {
-            let message_hash = MessageHash::compute(&self.cipher_suite_provider, &message).await?;
+    pub async fn required_proposals(&self) -> Result<Vec<ProposalRef>, MlsError> {
+        let sender = Sender::Member(*self.private_tree.self_index);
+        let proposals = self.state.proposals.prepare_commit(sender, Vec::new());
 
-            if message_hash == pending {
-                let message_description = self.apply_pending_commit().await?;
+        let provisional_state = self
+            .state
+            .apply_resolved(
+                sender,
+                proposals,
+                None,
+                &self.identity_provider(),
+                &self.cipher_suite_provider,
+                &self.psk_storage(),
+                &self.mls_rules(),
+                &self.disallowed_proposal_types(),
+                #[cfg(feature = "psk")]
+                self.max_psks_per_commit(),
+                &self.leaf_node_extension_validators(),
+                self.config.current_time(),
+                mls_rules::CommitDirection::Send,
+                false,
+            )
+            .await?;
 
-                return Ok(ReceivedMessage::Commit(message_description));
-            }
-        }
+        Ok(provisional_state
+            .applied_proposals
+            .into_proposals()
+            .filter_map(|p| match p.source {
+                proposal_filter::ProposalSource::ByReference(r) => Some(r),
+                _ => None,
+            })
+            .collect())
+    }
 
-        #[cfg(feature = "by_ref_proposal")]
-        if message.wire_format() == WireFormat::PrivateMessage {
-            let cached_own_proposal = self
-                .state
-                .proposals
-                .get_own(&self.cipher_suite_provider, &message)
-                .await?;
+    /// Members removed by the commit that created the current epoch, as they
+    /// were before being removed.
+    ///
+    /// This is not persisted and is empty for a group loaded from storage.
+    pub fn last_removed(&self) -> &[Member] {
+        &self.last_removed
+    }
 
-            if let Some(cached) = cached_own_proposal {
-                return Ok(ReceivedMessage::Proposal(cached));
-            }
-        }
+    /// Validate a received proposal message without caching it or otherwise
+    /// modifying the group state.
+    ///
+    /// The message signature and membership tag are verified, and the
+    /// proposal is checked against the same rules that apply when it is
+    /// committed by reference, including the sender's authorization to send
+    /// it and the [`MlsRules`] in use by this group. Only proposals sent as
+    /// [`WireFormat::PublicMessage`] can be validated, since decrypting a
+    /// private message consumes its key.
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn validate_proposal_message(&self, message: &MlsMessage) -> Result<(), MlsError> {
+        self.check_metadata(message)?;
 
-        MessageProcessor::process_incoming_message(
-            self,
-            message,
-            #[cfg(feature = "by_ref_proposal")]
+        let MlsMessagePayload::Plain(plaintext) = &message.payload else {
+            return Err(MlsError::UnexpectedMessageType);
+        };
+
+        let EventOrContent::Content(auth_content) = self
+            .verify_plaintext_authentication(plaintext.clone())
+            .await?
+        else {
+            return Err(MlsError::UnexpectedMessageType);
+        };
+
+        let Content::Proposal(proposal) = &auth_content.content.content else {
+            return Err(MlsError::UnexpectedMessageType);
+        };
+
+        let description = ProposalMessageDescription::new(
+            &self.cipher_suite_provider,
+            &auth_content,
+            (**proposal).clone(),
+            &self.proposal_ref_label(),
+        )
+        .await?;
+
+        // The committer can neither be removed by nor be the sender of an
+        // update proposal it commits, so pick a member for which it is valid.
+        let excluded = match (&description.proposal, auth_content.content.sender) {
+            (Proposal::Remove(remove), _) => Some(remove.to_remove),
+            (Proposal::Update(_), Sender::Member(index)) => Some(LeafIndex(index)),
+            _ => None,
+        };
+
+        let committer = self
+            .state
+            .public_tree
+            .non_empty_leaves()
+            .map(|(index, _)| index)
+            .find(|index| Some(*index) != excluded)
+            .unwrap_or(self.private_tree.self_index);
+
+        let mut proposals = proposal_filter::ProposalBundle::default();
+
+        proposals.add(
+            description.proposal,
+            auth_content.content.sender,
+            proposal_filter::ProposalSource::ByReference(description.proposal_ref),
+        );
+
+        self.state
+            .apply_resolved(
+                Sender::Member(*committer),
+                proposals,
+                None,
+                &self.identity_provider(),
+                &self.cipher_suite_provider,
+                &self.psk_storage(),
+                &self.mls_rules(),
+                &self.disallowed_proposal_types(),
+                #[cfg(feature = "psk")]
+                self.max_psks_per_commit(),
+                &self.leaf_node_extension_validators(),
+                None,
+                mls_rules::CommitDirection::Receive,
+                false,
+            )
+            .await
+            .map(|_| ())
+    }
+
+    /// Validate a received commit message without modifying the group
+    /// state, returning a description of what processing it would do.
+    ///
+    /// All checks performed by [`Group::process_incoming_message`] are run,
+    /// including decryption, signature and confirmation tag verification and
+    /// the [`MlsRules`] in use by this group, on a copy of the group. This is
+    /// therefore as expensive as processing the commit.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn validate_commit(
+        &self,
+        message: &MlsMessage,
+    ) -> Result<CommitMessageDescription, MlsError> {
+        let mut group = self.clone();
+
+        match group.process_incoming_message(message.clone()).await? {
+            ReceivedMessage::Commit(description) => Ok(description),
+            _ => Err(MlsError::UnexpectedMessageType),
+        }
+    }
+
+    /// Process an inbound message for this group.
+    ///
+    /// # Warning
+    ///
+    /// Changes to the group's state as a result of processing `message` will
+    /// not be persisted by the
+    /// [`GroupStateStorage`](crate::GroupStateStorage)
+    /// in use by this group until [`Group::write_to_storage`] is called.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "process_incoming_message",
+            level = "debug",
+            skip_all,
+            fields(
+                group_id = ?mls_rs_core::debug::pretty_group_id(self.group_id()),
+                epoch = self.current_epoch(),
+                message_type = ?message.wire_format(),
+            )
+        )
+    )]
+    #[inline(never)]
+    pub async fn process_incoming_message(
+        &mut self,
+        message: MlsMessage,
+    ) -> Result<ReceivedMessage, MlsError> {
+        if let Some(pending) = self.pending_commit.commit_hash()? {
+            let message_hash = MessageHash::compute(&self.cipher_suite_provider, &message).await?;
+
+            if message_hash == pending {
+                let message_description = self.apply_pending_commit().await?;
+
+                return Ok(ReceivedMessage::Commit(message_description));
+            }
+        }
+
+        #[cfg(feature = "by_ref_proposal")]
+        if message.wire_format() == WireFormat::PrivateMessage {
+            let cached_own_proposal = self
+                .state
+                .proposals
+                .get_own(&self.cipher_suite_provider, &message)
+                .await?;
+
+            if let Some(cached) = cached_own_proposal {
+                return Ok(ReceivedMessage::Proposal(cached));
+            }
+        }
+
+        MessageProcessor::process_incoming_message(
+            self,
+            message,
+            #[cfg(feature = "by_ref_proposal")]
             true,
         )
         .await
@@ -1350,6 +2024,19 @@ where
     /// [`GroupStateStorage`](crate::GroupStateStorage)
     /// in use by this group until [`Group::write_to_storage`] is called.
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "process_incoming_message",
+            level = "debug",
+            skip_all,
+            fields(
+                group_id = ?mls_rs_core::debug::pretty_group_id(self.group_id()),
+                epoch = self.current_epoch(),
+                message_type = ?message.wire_format(),
+            )
+        )
+    )]
     pub async fn process_incoming_message_with_time(
         &mut self,
         message: MlsMessage,
@@ -1371,6 +2058,12 @@ where
     /// This function determines identity by calling the
     /// [`IdentityProvider`](crate::IdentityProvider)
     /// currently in use by the group.
+    ///
+    /// Identities are unique among the members of a group: adding a leaf whose
+    /// identity is already in use fails with [`MlsError::DuplicateLeafData`]. An
+    /// application that maps several devices to one user account should therefore
+    /// return a per-device identity from its `IdentityProvider`, and group members
+    /// by account by inspecting their credentials, e.g. in [`Group::roster`].
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn member_with_identity(&self, identity: &[u8]) -> Result<Member, MlsError> {
         let tree = &self.state.public_tree;
@@ -1418,6 +2111,11 @@ where
     }
 
     /// Create a group info message that can be used for external proposals.
+    ///
+    /// The returned `GroupInfo` does not contain the
+    /// [`ExternalPubExt`](crate::extension::ExternalPubExt) extension, so it can not
+    /// be used for external commits. Use [`Group::group_info_message_allowing_ext_commit`]
+    /// instead to allow new members to join via an external commit.
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn group_info_message(
         &self,
@@ -1459,11 +2157,29 @@ where
     }
 
     /// Get the current group context summarizing various information about the group.
+    ///
+    /// The returned context is the one of the current epoch. A pending commit created
+    /// by this member is not reflected until it is applied with
+    /// [`Group::apply_pending_commit`].
     #[inline(always)]
     pub fn context(&self) -> &GroupContext {
         &self.group_state().context
     }
 
+    /// Get the signing identities that are currently allowed to send external
+    /// proposals to this group, as listed in the
+    /// [`ExternalSendersExt`](crate::extension::ExternalSendersExt) group context
+    /// extension. Returns an empty list if the extension is not set.
+    #[cfg(feature = "by_ref_proposal")]
+    pub fn external_senders(&self) -> Result<Vec<SigningIdentity>, MlsError> {
+        Ok(self
+            .context()
+            .extensions
+            .get_as::<ExternalSendersExt>()?
+            .map(|ext| ext.allowed_senders)
+            .unwrap_or_default())
+    }
+
     /// Get the
     /// [epoch_authenticator](https://messaginglayersecurity.rocks/mls-protocol/draft-ietf-mls-protocol.html#name-key-schedule)
     /// of the current epoch.
@@ -1471,6 +2187,54 @@ where
         Ok(self.key_schedule.authentication_secret.clone().into())
     }
 
+    /// [Epoch authenticators](Group::epoch_authenticator) of the prior epochs
+    /// retained by this group followed by the one of the current epoch, along
+    /// with their epoch numbers, in ascending epoch order.
+    ///
+    /// Prior epochs are read from the group state storage, up to the number
+    /// of epochs retained by the group.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn epoch_authenticator_history(&self) -> Result<Vec<(u64, Vec<u8>)>, MlsError> {
+        #[cfg(feature = "prior_epoch")]
+        let prior = self.state_repo.prior_epoch_authenticators().await?;
+
+        #[cfg(not(feature = "prior_epoch"))]
+        let prior = Vec::new();
+
+        let current = (
+            self.current_epoch(),
+            self.key_schedule.authentication_secret.to_vec(),
+        );
+
+        Ok(prior.into_iter().chain(core::iter::once(current)).collect())
+    }
+
+    /// Compare the [epoch authenticator](Group::epoch_authenticator) of the
+    /// current epoch with `other_authenticator`, obtained from another member
+    /// out-of-band, to detect whether the group has forked.
+    ///
+    /// [`ForkStatus::DifferentEpoch`] is returned if `other_authenticator`
+    /// matches one of the prior epochs retained by the group state storage.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn fork_check(&self, other_authenticator: &[u8]) -> Result<ForkStatus, MlsError> {
+        if self.key_schedule.authentication_secret.as_slice() == other_authenticator {
+            return Ok(ForkStatus::Same);
+        }
+
+        #[cfg(feature = "prior_epoch")]
+        if self
+            .state_repo
+            .prior_epoch_authenticators()
+            .await?
+            .iter()
+            .any(|(_, authenticator)| authenticator == other_authenticator)
+        {
+            return Ok(ForkStatus::DifferentEpoch);
+        }
+
+        Ok(ForkStatus::Diverged)
+    }
+
     /// Export a secret for use outside of MLS. Each epoch, label, context
     /// combination has a unique and independent secret. Secrets for all
     /// epochs, labels and contexts can be derived until either the epoch
@@ -1489,6 +2253,27 @@ where
             .map(Into::into)
     }
 
+    /// Derive a value bound to the current epoch for use in key derivations of
+    /// the application, labeled with `app_label`.
+    ///
+    /// The value is a one-way function of the epoch secret, so it can be mixed
+    /// into application keys without giving access to any MLS secret. All
+    /// members of the group derive the same value for the same label in the
+    /// same epoch, and different labels yield independent values. Its length
+    /// is the output length of the KDF of the cipher suite.
+    ///
+    /// This is derived from the exporter secret and fails after
+    /// [Group::delete_exporter] is called.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn derive_epoch_binding(&self, app_label: &[u8]) -> Result<Secret, MlsError> {
+        self.export_secret(
+            EPOCH_BINDING_LABEL,
+            app_label,
+            self.cipher_suite_provider.kdf_extract_size(),
+        )
+        .await
+    }
+
     /// Delete the exporter secret. Afterwards the state contains no information
     /// about any secrets outputted by [Group::export_secret] (for the current or
     /// past epochs). This means that after calling this function, [Group::export_secret]
@@ -1506,6 +2291,43 @@ where
         ExportedTree::new_borrowed(&self.current_epoch_tree().nodes)
     }
 
+    /// Length in bytes of the current epoch's ratchet tree in the serialized
+    /// format returned by [`Group::export_tree`], computed without serializing it.
+    pub fn ratchet_tree_size(&self) -> usize {
+        self.export_tree().byte_size()
+    }
+
+    /// Hash of the public state of the current epoch, computed over the
+    /// serialized group context, followed by the serialized interim transcript
+    /// hash and the ratchet tree as exported by [`Group::export_tree`].
+    ///
+    /// All members of the group in the same epoch compute the same hash, so
+    /// comparing it is a cheap way to detect diverging group states.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn public_state_hash(&self) -> Result<Vec<u8>, MlsError> {
+        let mut state = self.context().mls_encode_to_vec()?;
+        state.extend(self.state.interim_transcript_hash.mls_encode_to_vec()?);
+        state.extend(self.export_tree().to_bytes()?);
+
+        self.cipher_suite_provider
+            .hash(&state)
+            .await
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
+    }
+
+    /// Export the leaf node of the member at `index` in serialized format.
+    ///
+    /// Observers can use
+    /// [`ExternalClient::import_member_leaves`](crate::external_client::ExternalClient::import_member_leaves)
+    /// to synchronize individual leaves of their tree instead of importing
+    /// the whole tree again.
+    pub fn export_member_leaf(&self, index: u32) -> Result<Vec<u8>, MlsError> {
+        Ok(self
+            .current_epoch_tree()
+            .get_leaf_node(LeafIndex(index))?
+            .mls_encode_to_vec()?)
+    }
+
     /// Current version of the MLS protocol in use by this group.
     pub fn protocol_version(&self) -> ProtocolVersion {
         self.context().protocol_version
@@ -1516,11 +2338,78 @@ where
         self.context().cipher_suite
     }
 
+    /// Protocol version and cipher suite in use by this group, as returned by
+    /// [`Group::protocol_version`] and [`Group::cipher_suite`].
+    pub fn negotiated_parameters(&self) -> (ProtocolVersion, CipherSuite) {
+        (self.protocol_version(), self.cipher_suite())
+    }
+
     /// Current roster
+    ///
+    /// Members are listed in order of their leaf index. Since the leaf of a
+    /// removed member is reused by the next member added to the group, this
+    /// order is not stable over time. See [`Group::roster_sorted_by_identity`]
+    /// for an order suitable for display.
     pub fn roster(&self) -> Roster<'_> {
         self.group_state().public_tree.roster()
     }
 
+    /// Current members sorted by their [identity](crate::IdentityProvider::identity).
+    ///
+    /// Unlike the order of [`Group::roster`], the relative order of two members
+    /// does not change when members are added or removed. Identities are determined
+    /// by the [`IdentityProvider`](crate::IdentityProvider) currently in use by the group.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn roster_sorted_by_identity(&self) -> Result<Vec<Member>, MlsError> {
+        let identity_provider = self.identity_provider();
+        let mut members = Vec::new();
+
+        for member in self.roster().members_iter() {
+            let identity = identity_provider
+                .identity(&member.signing_identity, &self.context().extensions)
+                .await
+                .map_err(|e| MlsError::IdentityProviderError(e.into_any_error()))?;
+
+            members.push((identity, member));
+        }
+
+        members.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(members.into_iter().map(|(_, member)| member).collect())
+    }
+
+    /// Iterator over the current group members that lazily copies data out
+    /// of the internal group state.
+    ///
+    /// This avoids building the full list of members when only scanning
+    /// for a specific one. See [`Roster::members_iter`].
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn members(&self) -> impl Iterator<Item = Member> + '_ {
+        self.roster().members_iter()
+    }
+
+    /// Number of members currently in the group.
+    pub fn member_count(&self) -> u32 {
+        self.group_state().public_tree.occupied_leaf_count()
+    }
+
+    /// Number of blank leaves left in the ratchet tree by removed members.
+    ///
+    /// Blank leaves at the right edge of the tree are truncated and not
+    /// counted. Blank leaves are filled again when new members are added.
+    pub fn blank_leaf_count(&self) -> u32 {
+        self.group_state().public_tree.blank_leaf_count()
+    }
+
+    /// Ratio of occupied leaves to the number of leaves in the ratchet tree,
+    /// as counted by [`Group::blank_leaf_count`]. A low utilization indicates
+    /// a fragmented tree that may be compacted with a
+    /// [reinit](proposal::ReInitProposal).
+    pub fn tree_utilization(&self) -> f32 {
+        let occupied = self.member_count();
+        occupied as f32 / (occupied + self.blank_leaf_count()) as f32
+    }
+
     /// Determines equality of two different groups internal states.
     /// Useful for testing.
     ///
@@ -1700,10 +2589,11 @@ impl<C: ClientConfig> Group<C> {
         // cipher suite and the HPKE private key corresponding to the GroupSecrets. If a
         // PreSharedKeyID is part of the GroupSecrets and the client is not in possession of
         // the corresponding PSK, return an error
-        let group_secrets = GroupSecrets::decrypt(
+        let group_secrets = GroupSecrets::decrypt_with_label(
             &cipher_suite_provider,
             &key_package_generation.init_secret_key,
             &key_package_generation.key_package.hpke_init_key,
+            &config.welcome_hpke_label(),
             &welcome.encrypted_group_info,
             &encrypted_group_secrets.encrypted_group_secrets,
         )
@@ -1758,6 +2648,7 @@ impl<C: ClientConfig> Group<C> {
             self_index: self.private_tree.self_index,
             secrets: self.epoch_secrets.clone(),
             signature_public_keys,
+            authenticator: self.key_schedule.authentication_secret.clone(),
         };
 
         self.state_repo.insert(past_epoch).await?;
@@ -1792,6 +2683,11 @@ where
     fn epoch_secrets(&self) -> &EpochSecrets {
         &self.epoch_secrets
     }
+
+    #[cfg(any(test, feature = "test_util"))]
+    fn encryption_modifiers(&self) -> EncryptionModifiers {
+        self.encryption_modifiers
+    }
 }
 
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -1949,25 +2845,34 @@ where
         // Use the confirmation_key for the new epoch to compute the confirmation tag for
         // this message, as described below, and verify that it is the same as the
         // confirmation_tag field in the MlsPlaintext object.
-        let new_confirmation_tag = ConfirmationTag::create(
-            &key_schedule_result.confirmation_key,
-            &provisional_state.group_context.confirmed_transcript_hash,
-            &self.cipher_suite_provider,
-        )
-        .await?;
+        if self.config.verify_confirmation_tag() {
+            let new_confirmation_tag = ConfirmationTag::create(
+                &key_schedule_result.confirmation_key,
+                &provisional_state.group_context.confirmed_transcript_hash,
+                &self.cipher_suite_provider,
+            )
+            .await?;
 
-        if &new_confirmation_tag != confirmation_tag {
-            return Err(MlsError::InvalidConfirmationTag);
+            if &new_confirmation_tag != confirmation_tag {
+                return Err(MlsError::InvalidConfirmationTag);
+            }
         }
 
         self.insert_past_epoch().await?;
 
+        self.last_removed = provisional_state
+            .applied_proposals
+            .removals
+            .iter()
+            .filter_map(|p| self.state.member_at_index(p.proposal.to_remove()))
+            .collect();
+
         self.epoch_secrets = key_schedule_result.epoch_secrets;
         self.state.context = provisional_state.group_context;
         self.state.interim_transcript_hash = interim_transcript_hash;
         self.key_schedule = key_schedule_result.key_schedule;
         self.state.public_tree = provisional_state.public_tree;
-        self.state.confirmation_tag = new_confirmation_tag;
+        self.state.confirmation_tag = confirmation_tag.clone();
 
         // Clear the proposals list
         #[cfg(feature = "by_ref_proposal")]
@@ -1996,6 +2901,32 @@ where
         self.config.secret_store()
     }
 
+    #[cfg(feature = "by_ref_proposal")]
+    fn proposal_ref_label(&self) -> Vec<u8> {
+        self.config.proposal_ref_label()
+    }
+
+    fn disallowed_proposal_types(&self) -> Vec<ProposalType> {
+        self.config.disallowed_proposal_types()
+    }
+
+    #[cfg(feature = "psk")]
+    fn max_psks_per_commit(&self) -> Option<usize> {
+        self.config.max_psks_per_commit()
+    }
+
+    fn warn_on_foreign_rejected_proposals(&self) -> bool {
+        self.config.warn_on_foreign_rejected_proposals()
+    }
+
+    fn leaf_node_extension_validators(&self) -> LeafNodeExtensionValidators {
+        self.config.leaf_node_extension_validators()
+    }
+
+    fn current_time(&self) -> Option<MlsTime> {
+        self.config.current_time()
+    }
+
     fn group_state(&self) -> &GroupState {
         &self.state
     }
@@ -2016,7 +2947,13 @@ where
             .cloned()
     }
 
-    #[cfg(feature = "private_message")]
+    #[cfg(all(feature = "private_message", feature = "prior_epoch"))]
+    fn min_epoch_available(&self) -> Option<u64> {
+        self.state_repo
+            .min_epoch_id(self.current_epoch().checked_sub(1)?)
+    }
+
+    #[cfg(all(feature = "private_message", not(feature = "prior_epoch")))]
     fn min_epoch_available(&self) -> Option<u64> {
         None
     }
@@ -2067,7 +3004,6 @@ mod tests {
 
     use crate::{extension::RequiredCapabilitiesExt, key_package::test_utils::test_key_package};
 
-    #[cfg(all(feature = "by_ref_proposal", feature = "custom_proposal"))]
     use super::test_utils::test_group_custom_config;
 
     #[cfg(any(feature = "psk", feature = "std"))]
@@ -2093,7 +3029,10 @@ mod tests {
         *,
     };
 
+    use crate::time::Clock;
+    use alloc::sync::Arc;
     use assert_matches::assert_matches;
+    use core::sync::atomic::{AtomicU64, Ordering};
 
     use message_processor::CommitEffect;
     use mls_rs_core::extension::{Extension, ExtensionType};
@@ -2144,6 +3083,68 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn pending_proposals_resolve_their_proposer() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+        let (carol, commit) = alice.join("carol").await;
+        bob.process_message(commit).await.unwrap();
+
+        let remove_carol = bob.remove_proposal(carol.current_member_index()).unwrap();
+        let message = bob
+            .proposal_message(remove_carol.clone(), vec![])
+            .await
+            .unwrap();
+        alice.process_message(message).await.unwrap();
+
+        let pending = alice.pending_proposals_with_proposer();
+        assert_eq!(pending.len(), 1);
+
+        let (_, proposal, proposer) = &pending[0];
+        assert_eq!(proposal, &remove_carol);
+        assert_eq!(proposer.as_ref(), alice.member_at_index(1).as_ref());
+
+        let proposer = proposer.as_ref().unwrap();
+        assert_eq!(
+            proposer.signing_identity,
+            bob.current_member_signing_identity().unwrap().clone()
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn conflicting_remove_is_reported_as_required() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+        let (mut carol, commit) = alice.join("carol").await;
+        bob.process_message(commit).await.unwrap();
+
+        let update = bob.propose_update(vec![]).await.unwrap();
+        let update = alice.process_message(update).await.unwrap();
+        let ReceivedMessage::Proposal(update) = update else {
+            panic!("expected proposal");
+        };
+
+        let remove = carol
+            .propose_remove(bob.current_member_index(), vec![])
+            .await
+            .unwrap();
+        let remove = alice.process_message(remove).await.unwrap();
+        let ReceivedMessage::Proposal(remove) = remove else {
+            panic!("expected proposal");
+        };
+
+        let required = alice.required_proposals().await.unwrap();
+
+        assert_eq!(required, vec![remove.proposal_ref.clone()]);
+        assert!(!required.contains(&update.proposal_ref));
+
+        // Alice's own update can't be committed by her and is not required.
+        alice.propose_update(vec![]).await.unwrap();
+        let required = alice.required_proposals().await.unwrap();
+        assert_eq!(required, vec![remove.proposal_ref]);
+    }
+
     #[cfg(feature = "private_message")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_pending_proposals_application_data() {
@@ -2281,10 +3282,12 @@ mod tests {
         .unwrap();
 
         // Hack bob's receipt of the proposal
-        bob_group
-            .state
-            .proposals
-            .insert(proposal_ref, proposal, proposal_plaintext.content.sender);
+        bob_group.state.proposals.insert(
+            proposal_ref,
+            proposal,
+            proposal_plaintext.content.sender,
+            Vec::new(),
+        );
 
         let commit_output = bob_group.commit(vec![]).await.unwrap();
 
@@ -2326,6 +3329,35 @@ mod tests {
         (test_group, bob_test_group)
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn joining_with_oversized_embedded_tree_fails() {
+        let mut alice = test_group_custom(
+            TEST_PROTOCOL_VERSION,
+            TEST_CIPHER_SUITE,
+            Default::default(),
+            None,
+            Some(CommitOptions::new().with_ratchet_tree_extension(true)),
+        )
+        .await;
+
+        let res = alice
+            .join_with_custom_config("bob", false, |c| {
+                c.0.settings.max_ratchet_tree_size = Some(64)
+            })
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::RatchetTreeTooLarge(size)) if size > 64);
+
+        let res = alice
+            .join_with_custom_config("carol", false, |c| {
+                c.0.settings.max_ratchet_tree_size = Some(1 << 20)
+            })
+            .await;
+
+        assert!(res.is_ok());
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_welcome_processing_exported_tree() {
         test_two_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, false).await;
@@ -2540,7 +3572,8 @@ mod tests {
 
         assert_matches!(
             commit,
-            Err(MlsError::RequiredExtensionNotFound(a)) if a == 999.into()
+            Err(MlsError::MemberMissingRequiredCapability { leaf_index: 0, missing })
+                if matches!(*missing, MlsError::RequiredExtensionNotFound(a) if a == 999.into())
         );
     }
 
@@ -2696,17 +3729,50 @@ mod tests {
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn external_commit_via_commit_options_round_trip() {
-        let mut group = test_group_custom(
-            TEST_PROTOCOL_VERSION,
-            TEST_CIPHER_SUITE,
-            vec![],
-            None,
-            CommitOptions::default()
-                .with_allow_external_commit(true)
-                .into(),
-        )
-        .await;
+    async fn group_info_allowing_ext_commit_enables_external_commit() {
+        let cipher_suite = TEST_CIPHER_SUITE;
+        let group = test_group(TEST_PROTOCOL_VERSION, cipher_suite).await;
+
+        let info_msg = group
+            .group_info_message_allowing_ext_commit(true)
+            .await
+            .unwrap();
+
+        let info = info_msg.clone().into_group_info().unwrap();
+        let external_pub = info.extensions.get_as::<ExternalPubExt>().unwrap().unwrap();
+
+        let expected_pub = group
+            .key_schedule
+            .get_external_key_pair_ext(&group.cipher_suite_provider)
+            .await
+            .unwrap();
+
+        assert_eq!(external_pub, expected_pub);
+
+        let (bob_identity, bob_secret_key) = get_test_signing_identity(cipher_suite, b"bob").await;
+
+        external_commit::ExternalCommitBuilder::new(
+            bob_secret_key,
+            bob_identity,
+            group.group.config,
+        )
+        .build(info_msg)
+        .await
+        .unwrap();
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_commit_via_commit_options_round_trip() {
+        let mut group = test_group_custom(
+            TEST_PROTOCOL_VERSION,
+            TEST_CIPHER_SUITE,
+            vec![],
+            None,
+            CommitOptions::default()
+                .with_allow_external_commit(true)
+                .into(),
+        )
+        .await;
 
         let commit_output = group.commit(vec![]).await.unwrap();
 
@@ -2855,6 +3921,102 @@ mod tests {
         );
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_init_sender_names_the_joiner() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let (bob_identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let bob = TestClientBuilder::new_for_test()
+            .signing_identity(bob_identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let group_info = alice_group
+            .group_info_message_allowing_ext_commit(true)
+            .await
+            .unwrap();
+
+        let (mut bob_group, commit) = bob
+            .external_commit_builder()
+            .unwrap()
+            .build(group_info)
+            .await
+            .unwrap();
+
+        let event = alice_group.process_message(commit).await.unwrap();
+
+        let ReceivedMessage::Commit(description) = event else {
+            panic!("expected commit");
+        };
+
+        assert_eq!(
+            description.external_init_sender(),
+            Some(bob_group.current_member_index())
+        );
+
+        assert!(!description.is_reinit());
+
+        let commit = bob_group.commit(vec![]).await.unwrap().commit_message;
+        let event = alice_group.process_message(commit).await.unwrap();
+
+        let ReceivedMessage::Commit(description) = event else {
+            panic!("expected commit");
+        };
+
+        assert_eq!(description.external_init_sender(), None);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_details_match_group_accessors() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        let commit = groups[0]
+            .commit_builder()
+            .remove_member(2)
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+            .commit_message;
+
+        let applied = groups[0].apply_pending_commit_with_details().await.unwrap();
+
+        assert_eq!(applied.description.committer, 0);
+        assert_eq!(applied.removed_members, vec![2]);
+
+        assert_eq!(
+            applied.epoch_authenticator,
+            Some(groups[0].epoch_authenticator().unwrap())
+        );
+
+        let ReceivedMessage::Commit(description) =
+            groups[1].process_message(commit.clone()).await.unwrap()
+        else {
+            panic!("expected commit");
+        };
+
+        let received = groups[1].commit_details(description).unwrap();
+
+        assert_eq!(received.removed_members, vec![2]);
+        assert_eq!(received.epoch_authenticator, applied.epoch_authenticator);
+
+        let ReceivedMessage::Commit(description) = groups[2].process_message(commit).await.unwrap()
+        else {
+            panic!("expected commit");
+        };
+
+        let removed = groups[2].commit_details(description).unwrap();
+
+        assert_eq!(removed.removed_members, vec![2]);
+        assert_eq!(removed.epoch_authenticator, None);
+
+        groups[0].commit(vec![]).await.unwrap();
+        groups[0].apply_pending_commit().await.unwrap();
+
+        let res = groups[0].commit_details(applied.description);
+        assert_matches!(res, Err(MlsError::InvalidEpoch));
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn can_join_new_group_externally() {
         use crate::client::test_utils::TestClientBuilder;
@@ -2992,6 +4154,76 @@ mod tests {
         assert!(alice.private_tree.secret_keys[1].is_none());
     }
 
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn member_reports_leaf_node_source() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let bob_member = alice.member_at_index(1).unwrap();
+        assert_eq!(bob_member.source(), LeafNodeSourceType::KeyPackage);
+
+        let update = bob.propose_update(vec![]).await.unwrap();
+        alice.process_message(update).await.unwrap();
+        alice.commit(vec![]).await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
+
+        let bob_member = alice.member_at_index(1).unwrap();
+        assert_eq!(bob_member.source(), LeafNodeSourceType::Update);
+
+        let alice_member = alice.member_at_index(0).unwrap();
+        assert_eq!(alice_member.source(), LeafNodeSourceType::Commit);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn current_epoch_increments_once_per_applied_commit() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+        let start = alice.current_epoch();
+
+        for i in 1..=3 {
+            let commit = alice.commit(vec![]).await.unwrap();
+
+            // A pending commit does not change the epoch
+            assert_eq!(alice.current_epoch(), start + i - 1);
+
+            alice.apply_pending_commit().await.unwrap();
+            bob.process_message(commit.commit_message).await.unwrap();
+
+            assert_eq!(alice.current_epoch(), start + i);
+            assert_eq!(bob.current_epoch(), start + i);
+            assert_eq!(alice.current_epoch(), alice.context().epoch);
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn member_exposes_hpke_public_key_of_key_package() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let (_, key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let expected = key_package
+            .as_key_package()
+            .unwrap()
+            .leaf_node
+            .public_key
+            .clone();
+
+        alice
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let bob_member = alice.member_at_index(1).unwrap();
+        assert_eq!(bob_member.hpke_public_key(), &expected);
+    }
+
     #[cfg(feature = "psk")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn only_selected_members_of_the_original_group_can_join_subgroup() {
@@ -3116,919 +4348,2475 @@ mod tests {
         );
     }
 
-    #[cfg(feature = "private_message")]
+    #[cfg(all(feature = "tracing", feature = "std"))]
+    #[derive(Clone, Default)]
+    struct TracingEventRecorder(
+        std::sync::Arc<std::sync::Mutex<Vec<std::collections::HashMap<String, String>>>>,
+    );
+
+    #[cfg(all(feature = "tracing", feature = "std"))]
+    impl tracing::Subscriber for TracingEventRecorder {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            struct FieldVisitor<'a>(&'a mut std::collections::HashMap<String, String>);
+
+            impl tracing::field::Visit for FieldVisitor<'_> {
+                fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+                    self.0.insert(field.name().into(), value.into());
+                }
+
+                fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn Debug) {
+                    self.0.insert(field.name().into(), format!("{value:?}"));
+                }
+            }
+
+            let mut fields = Default::default();
+            event.record(&mut FieldVisitor(&mut fields));
+            self.0.lock().unwrap().push(fields);
+        }
+
+        fn enter(&self, _: &tracing::span::Id) {}
+
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[cfg(all(feature = "tracing", feature = "std"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn member_cannot_decrypt_same_message_twice() {
+    async fn processing_commit_emits_tracing_event_with_new_epoch() {
         let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
         let (mut bob_group, _) = alice_group.join("bob").await;
 
-        let message = alice_group
-            .encrypt_application_message(b"foobar", Vec::new())
-            .await
-            .unwrap();
+        let commit = alice_group.commit(vec![]).await.unwrap().commit_message;
 
-        let received_message = bob_group
-            .process_incoming_message(message.clone())
-            .await
+        let recorder = TracingEventRecorder::default();
+        let guard = tracing::subscriber::set_default(recorder.clone());
+        bob_group.process_incoming_message(commit).await.unwrap();
+        drop(guard);
+
+        let events = recorder.0.lock().unwrap();
+
+        let processed = events
+            .iter()
+            .find(|fields| fields.get("message").map(String::as_str) == Some("processed commit"))
             .unwrap();
 
-        assert_matches!(
-            received_message,
-            ReceivedMessage::ApplicationMessage(m) if m.data() == b"foobar"
+        assert_eq!(
+            processed.get("epoch"),
+            Some(&bob_group.current_epoch().to_string())
         );
 
-        let res = bob_group.process_incoming_message(message).await;
-
-        assert_matches!(res, Err(MlsError::KeyMissing(0)));
+        assert_eq!(
+            processed.get("message_type").map(String::as_str),
+            Some("commit")
+        );
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn removing_requirements_allows_to_add() {
-        let mut alice_group = test_group_custom(
-            TEST_PROTOCOL_VERSION,
-            TEST_CIPHER_SUITE,
-            vec![17.into()],
-            None,
-            None,
-        )
-        .await;
+    async fn fork_check_compares_epoch_authenticators() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob_group, _) = alice_group.join("bob").await;
 
-        alice_group
-            .commit_builder()
-            .set_group_context_ext(
-                vec![RequiredCapabilitiesExt {
-                    extensions: vec![17.into()],
-                    ..Default::default()
-                }
-                .into_extension()
-                .unwrap()]
-                .try_into()
-                .unwrap(),
-            )
-            .unwrap()
-            .build()
-            .await
-            .unwrap();
+        let bob_authenticator = bob_group.epoch_authenticator().unwrap();
 
-        alice_group.process_pending_commit().await.unwrap();
+        let status = alice_group.fork_check(&bob_authenticator).await.unwrap();
+        assert_eq!(status, ForkStatus::Same);
 
-        let test_key_package =
-            test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+        let status = alice_group.fork_check(&[0u8; 32]).await.unwrap();
+        assert_eq!(status, ForkStatus::Diverged);
 
-        let test_key_package = MlsMessage::new(
-            TEST_PROTOCOL_VERSION,
-            MlsMessagePayload::KeyPackage(test_key_package),
-        );
+        let commit = alice_group.commit(vec![]).await.unwrap().commit_message;
+        alice_group.apply_pending_commit().await.unwrap();
 
-        alice_group
-            .commit_builder()
-            .add_member(test_key_package.clone())
-            .unwrap()
-            .set_group_context_ext(Default::default())
-            .unwrap()
-            .build()
-            .await
-            .unwrap();
+        #[cfg(feature = "prior_epoch")]
+        {
+            let status = alice_group.fork_check(&bob_authenticator).await.unwrap();
+            assert_eq!(status, ForkStatus::DifferentEpoch);
 
-        let CommitEffect::NewEpoch(new_epoch) =
-            alice_group.process_pending_commit().await.unwrap().effect
-        else {
-            panic!("unexpected commit effect")
-        };
+            // Authenticators of prior epochs are read back from storage.
+            alice_group.write_to_storage().await.unwrap();
 
-        assert_eq!(new_epoch.applied_proposals.len(), 2);
+            let restored =
+                Group::from_snapshot(alice_group.config.clone(), alice_group.snapshot().unwrap())
+                    .await
+                    .unwrap();
 
-        assert_matches!(
-            new_epoch.applied_proposals[0],
-            ProposalInfo { proposal: Proposal::Add(ref add), .. }
-                if add.key_package == test_key_package.into_key_package().unwrap()
-        );
+            let status = restored.fork_check(&bob_authenticator).await.unwrap();
+            assert_eq!(status, ForkStatus::DifferentEpoch);
+        }
 
-        assert_eq!(alice_group.roster().members_iter().count(), 2);
+        bob_group.process_incoming_message(commit).await.unwrap();
+        let bob_authenticator = bob_group.epoch_authenticator().unwrap();
+
+        let status = alice_group.fork_check(&bob_authenticator).await.unwrap();
+        assert_eq!(status, ForkStatus::Same);
     }
 
+    #[cfg(feature = "prior_epoch")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_wrong_source() {
-        // RFC, 13.4.2. "The leaf_node_source field MUST be set to commit."
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+    async fn epoch_authenticator_history_covers_retained_epochs() {
+        let max_retained_epochs = 3;
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.leaf_node_source = LeafNodeSource::Update;
-            Some(sk.clone())
-        };
+        let mut group = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.max_retained_epochs(max_retained_epochs)
+        })
+        .await;
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        for _ in 0..max_retained_epochs + 2 {
+            group.commit(vec![]).await.unwrap();
+            group.apply_pending_commit().await.unwrap();
+        }
 
-        let res = groups[2]
-            .process_message(commit_output.commit_message)
-            .await;
+        let history = group.epoch_authenticator_history().await.unwrap();
 
-        assert_matches!(res, Err(MlsError::InvalidLeafNodeSource));
-    }
+        assert_eq!(history.len(), max_retained_epochs + 1);
 
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_same_hpke_key() {
-        // RFC 13.4.2. "Verify that the encryption_key value in the LeafNode is different from the committer's current leaf node"
+        let epochs = history.iter().map(|(epoch, _)| *epoch).collect::<Vec<_>>();
+        assert_eq!(epochs, vec![2, 3, 4, 5]);
 
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+        let (_, current) = history.last().unwrap();
+        assert_eq!(current, &group.epoch_authenticator().unwrap().to_vec());
 
-        // Group 0 starts using fixed key
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.public_key = get_test_25519_key(1u8);
-            Some(sk.clone())
-        };
-
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
-        groups[0].process_pending_commit().await.unwrap();
-        groups[2]
-            .process_message(commit_output.commit_message)
-            .await
-            .unwrap();
-
-        // Group 0 tries to use the fixed key againd
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
-
-        let res = groups[2]
-            .process_message(commit_output.commit_message)
-            .await;
-
-        assert_matches!(res, Err(MlsError::SameHpkeKey(0)));
+        for (i, (_, authenticator)) in history.iter().enumerate() {
+            assert!(history[i + 1..].iter().all(|(_, a)| a != authenticator));
+        }
     }
 
+    #[cfg(feature = "prior_epoch")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_duplicate_hpke_key() {
-        // RFC 8.3 "Verify that the following fields are unique among the members of the group: `encryption_key`"
+    async fn epoch_authenticator_history_is_read_from_storage() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
 
-        if TEST_CIPHER_SUITE != CipherSuite::CURVE25519_AES128
-            && TEST_CIPHER_SUITE != CipherSuite::CURVE25519_CHACHA
-        {
-            return;
+        for _ in 0..3 {
+            group.commit(vec![]).await.unwrap();
+            group.apply_pending_commit().await.unwrap();
         }
 
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
-
-        // Group 1 uses the fixed key
-        groups[1].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.public_key = get_test_25519_key(1u8);
-            Some(sk.clone())
-        };
-
-        let commit_output = groups.get_mut(1).unwrap().commit(vec![]).await.unwrap();
-
-        process_commit(&mut groups, commit_output.commit_message, 1).await;
+        let history = group.epoch_authenticator_history().await.unwrap();
 
-        // Group 0 tries to use the fixed key too
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.public_key = get_test_25519_key(1u8);
-            Some(sk.clone())
-        };
+        group.write_to_storage().await.unwrap();
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let restored = Group::from_snapshot(group.config.clone(), group.snapshot().unwrap())
+            .await
+            .unwrap();
 
-        let res = groups[7]
-            .process_message(commit_output.commit_message)
-            .await;
+        let restored_history = restored.epoch_authenticator_history().await.unwrap();
 
-        assert_matches!(res, Err(MlsError::DuplicateLeafData(_)));
+        assert_eq!(restored_history, history);
+        assert_eq!(history.len(), 4);
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_duplicate_signature_key() {
-        // RFC 8.3 "Verify that the following fields are unique among the members of the group: `signature_key`"
-
-        if TEST_CIPHER_SUITE != CipherSuite::CURVE25519_AES128
-            && TEST_CIPHER_SUITE != CipherSuite::CURVE25519_CHACHA
-        {
-            return;
-        }
+    async fn context_matches_individual_accessors_after_commit() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob_group, _) = alice_group.join("bob").await;
 
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+        let commit = alice_group.commit(vec![]).await.unwrap().commit_message;
 
-        // Group 1 uses the fixed key
-        groups[1].commit_modifiers.modify_leaf = |leaf, _| {
-            let sk = hex!(
-                "3468b4c890255c983e3d5cbf5cb64c1ef7f6433a518f2f3151d6672f839a06ebcad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b"
-            )
-            .into();
+        // The pending commit is not reflected until it is applied.
+        assert_eq!(alice_group.context().epoch, 1);
 
-            leaf.signing_identity.signature_key =
-                hex!("cad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b").into();
+        alice_group.apply_pending_commit().await.unwrap();
+        bob_group.process_incoming_message(commit).await.unwrap();
 
-            Some(sk)
-        };
+        let context = alice_group.context();
 
-        let commit_output = groups.get_mut(1).unwrap().commit(vec![]).await.unwrap();
+        assert_eq!(context.group_id(), alice_group.group_id());
+        assert_eq!(context.epoch(), alice_group.current_epoch());
+        assert_eq!(context.epoch(), 2);
+        assert_eq!(context.cipher_suite(), alice_group.cipher_suite());
+        assert_eq!(context.version(), alice_group.protocol_version());
+        assert_eq!(
+            context.extensions(),
+            &alice_group.group_state().context.extensions
+        );
 
-        process_commit(&mut groups, commit_output.commit_message, 1).await;
+        // Tree hash and confirmed transcript hash agree with the other member.
+        assert_eq!(context, bob_group.context());
+    }
 
-        // Group 0 tries to use the fixed key too
-        groups[0].commit_modifiers.modify_leaf = |leaf, _| {
-            let sk = hex!(
-                "3468b4c890255c983e3d5cbf5cb64c1ef7f6433a518f2f3151d6672f839a06ebcad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b"
-            )
-            .into();
+    #[cfg(feature = "private_message")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn member_cannot_decrypt_same_message_twice() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob_group, _) = alice_group.join("bob").await;
 
-            leaf.signing_identity.signature_key =
-                hex!("cad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b").into();
+        let message = alice_group
+            .encrypt_application_message(b"foobar", Vec::new())
+            .await
+            .unwrap();
 
-            Some(sk)
-        };
+        let received_message = bob_group
+            .process_incoming_message(message.clone())
+            .await
+            .unwrap();
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        assert_matches!(
+            received_message,
+            ReceivedMessage::ApplicationMessage(m) if m.data() == b"foobar"
+        );
 
-        let res = groups[7]
-            .process_message(commit_output.commit_message)
-            .await;
+        let res = bob_group.process_incoming_message(message).await;
 
-        assert_matches!(res, Err(MlsError::DuplicateLeafData(_)));
+        assert_matches!(res, Err(MlsError::KeyMissing(0)));
     }
 
+    #[cfg(feature = "private_message")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_incorrect_signature() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+    async fn message_encrypted_with_wrong_nonce_is_rejected() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob_group, _) = alice_group.join("bob").await;
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, _| {
-            leaf.signature[0] ^= 1;
-            None
-        };
+        alice_group.encryption_modifiers.modify_nonce = |nonce| nonce[0] ^= 1;
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let message = alice_group
+            .encrypt_application_message(b"foobar", Vec::new())
+            .await
+            .unwrap();
 
-        let res = groups[2]
-            .process_message(commit_output.commit_message)
-            .await;
+        let res = bob_group.process_incoming_message(message).await;
 
-        assert_matches!(res, Err(MlsError::InvalidSignature));
+        assert_matches!(res, Err(MlsError::CryptoProviderError(_)));
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(feature = "private_message")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_not_supporting_used_context_extension() {
-        const EXT_TYPE: ExtensionType = ExtensionType::new(999);
+    async fn peeking_application_message_does_not_consume_key() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob_group, _) = alice_group.join("bob").await;
 
-        // The new leaf of the committer doesn't support an extension set in group context
-        let extension = Extension::new(EXT_TYPE, vec![]);
+        let message = alice_group
+            .encrypt_application_message(b"foobar", Vec::new())
+            .await
+            .unwrap();
 
-        let mut groups =
-            get_test_groups_with_features(3, vec![extension].into(), Default::default()).await;
+        for _ in 0..2 {
+            let peeked = bob_group.peek_application_message(&message).await.unwrap();
+            assert_eq!(peeked, b"foobar");
+        }
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.capabilities = get_test_capabilities();
-            Some(sk.clone())
-        };
+        let received_message = bob_group
+            .process_incoming_message(message.clone())
+            .await
+            .unwrap();
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        assert_matches!(
+            received_message,
+            ReceivedMessage::ApplicationMessage(m) if m.data() == b"foobar"
+        );
 
-        let res = groups[1]
-            .process_incoming_message(commit_output.commit_message)
-            .await;
+        let res = bob_group.peek_application_message(&message).await;
 
-        assert_matches!(res, Err(MlsError::UnsupportedGroupExtension(EXT_TYPE)));
+        assert_matches!(res, Err(MlsError::KeyMissing(0)));
     }
 
+    #[cfg(all(feature = "private_message", feature = "prior_epoch"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_not_supporting_required_extension() {
-        // The new leaf of the committer doesn't support an extension required by group context
+    async fn full_state_import_can_decrypt_prior_epoch_message() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
 
-        let extension = RequiredCapabilitiesExt {
-            extensions: vec![999.into()],
-            proposals: vec![],
-            credentials: vec![],
-        };
+        let message = bob
+            .encrypt_application_message(b"foobar", Vec::new())
+            .await
+            .unwrap();
 
-        let extensions = vec![extension.into_extension().unwrap()];
-        let mut groups =
-            get_test_groups_with_features(3, extensions.into(), Default::default()).await;
+        alice.commit(Vec::new()).await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.capabilities = Capabilities::default();
-            Some(sk.clone())
-        };
+        let exported = alice.export_full_state().await.unwrap();
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let mut config = alice.config.clone();
+        config.0.group_state_storage =
+            crate::storage_provider::in_memory::InMemoryGroupStateStorage::new();
 
-        let res = groups[2]
-            .process_incoming_message(commit_output.commit_message)
-            .await;
+        let mut migrated = Client::new(config, None, None, TEST_PROTOCOL_VERSION)
+            .import_full_state(&exported)
+            .await
+            .unwrap();
 
-        assert!(res.is_err());
+        assert_eq!(migrated.group_state(), alice.group_state());
+
+        let received_message = migrated.process_incoming_message(message).await.unwrap();
+
+        assert_matches!(
+            received_message,
+            ReceivedMessage::ApplicationMessage(m) if m.data() == b"foobar"
+        );
     }
 
+    #[cfg(feature = "private_message")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_has_unsupported_credential() {
-        // The new leaf of the committer has a credential unsupported by another leaf
-        let mut groups =
-            get_test_groups_with_features(3, Default::default(), Default::default()).await;
-
-        for group in groups.iter_mut() {
-            group.config.0.identity_provider.allow_any_custom = true;
-        }
+    async fn max_application_message_len_is_enforced_on_send_and_receive() {
+        let max_len = 16;
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.signing_identity.credential = Credential::Custom(CustomCredential::new(
-                CredentialType::new(43),
-                leaf.signing_identity
-                    .credential
-                    .as_basic()
-                    .unwrap()
-                    .identifier
-                    .to_vec(),
-            ));
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.max_application_message_len(max_len)
+        })
+        .await;
 
-            Some(sk.clone())
-        };
+        // Bob is not configured with a maximum length.
+        let (mut bob, _) = alice.join("bob").await;
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let oversized = vec![0u8; max_len + 1];
 
-        let res = groups[2]
-            .process_incoming_message(commit_output.commit_message)
+        let res = alice
+            .encrypt_application_message(&oversized, Vec::new())
             .await;
 
-        assert_matches!(res, Err(MlsError::CredentialTypeOfNewLeafIsUnsupported));
-    }
-
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_not_supporting_credential_used_in_another_leaf() {
-        // The new leaf of the committer doesn't support another leaf's credential
+        assert_matches!(res, Err(MlsError::ApplicationMessageTooLarge(len)) if len == max_len + 1);
 
-        let mut groups =
-            get_test_groups_with_features(3, Default::default(), Default::default()).await;
+        let message = bob
+            .encrypt_application_message(&oversized, Vec::new())
+            .await
+            .unwrap();
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.capabilities.credentials = vec![2.into()];
-            Some(sk.clone())
-        };
+        let res = alice.process_incoming_message(message).await;
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        assert_matches!(res, Err(MlsError::ApplicationMessageTooLarge(len)) if len == max_len + 1);
 
-        let res = groups[2]
-            .process_incoming_message(commit_output.commit_message)
-            .await;
+        let message = bob
+            .encrypt_application_message(&oversized[..max_len], Vec::new())
+            .await
+            .unwrap();
 
-        assert_matches!(res, Err(MlsError::InUseCredentialTypeUnsupportedByNewLeaf));
+        alice.process_incoming_message(message).await.unwrap();
     }
 
+    #[cfg(feature = "private_message")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_not_supporting_required_credential() {
-        // The new leaf of the committer doesn't support a credential required by group context
+    async fn oversized_application_ciphertext_is_rejected_before_decryption() {
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.max_application_message_len(16)
+        })
+        .await;
 
-        let extension = RequiredCapabilitiesExt {
-            extensions: vec![],
-            proposals: vec![],
-            credentials: vec![1.into()],
-        };
+        let (mut bob, _) = alice.join("bob").await;
 
-        let extensions = vec![extension.into_extension().unwrap()];
-        let mut groups =
-            get_test_groups_with_features(3, extensions.into(), Default::default()).await;
+        let mut message = bob
+            .encrypt_application_message(b"hello", Vec::new())
+            .await
+            .unwrap();
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.capabilities.credentials = vec![2.into()];
-            Some(sk.clone())
+        // The extra bytes would fail decryption if the ciphertext was decrypted.
+        let MlsMessagePayload::Cipher(ciphertext) = &mut message.payload else {
+            panic!("expected a private message");
         };
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        ciphertext
+            .ciphertext
+            .resize(2 * MAX_APPLICATION_CIPHERTEXT_OVERHEAD, 0);
+        let len = ciphertext.ciphertext.len();
 
-        let res = groups[2]
-            .process_incoming_message(commit_output.commit_message)
-            .await;
+        let res = alice.peek_application_message(&message).await;
+        assert_matches!(res, Err(MlsError::ApplicationMessageTooLarge(l)) if l == len);
 
-        assert_matches!(res, Err(MlsError::RequiredCredentialNotFound(_)));
+        let res = alice.process_incoming_message(message).await;
+        assert_matches!(res, Err(MlsError::ApplicationMessageTooLarge(l)) if l == len);
     }
 
-    #[cfg(feature = "by_ref_proposal")]
-    #[cfg(not(target_arch = "wasm32"))]
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn make_x509_external_senders_ext() -> ExternalSendersExt {
-        let (_, ext_sender_pk) = test_cipher_suite_provider(TEST_CIPHER_SUITE)
-            .signature_key_generate()
+    #[cfg(all(feature = "private_message", feature = "prior_epoch"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn max_retained_epochs_bounds_prior_epoch_decryption() {
+        let max_retained_epochs = 2;
+
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.max_retained_epochs(max_retained_epochs)
+        })
+        .await;
+
+        let (mut bob, _) = alice.join("bob").await;
+        let mut messages = Vec::new();
+
+        for _ in 0..max_retained_epochs + 2 {
+            let message = bob
+                .encrypt_application_message(b"foobar", Vec::new())
+                .await
+                .unwrap();
+
+            messages.push(message);
+
+            let commit = alice.commit(Vec::new()).await.unwrap().commit_message;
+            alice.apply_pending_commit().await.unwrap();
+            alice.write_to_storage().await.unwrap();
+            bob.process_incoming_message(commit).await.unwrap();
+        }
+
+        assert_eq!(
+            alice.min_epoch_available(),
+            Some(alice.current_epoch() - max_retained_epochs as u64)
+        );
+
+        let recent = messages.split_off(messages.len() - max_retained_epochs);
+
+        for message in messages {
+            let res = alice.process_incoming_message(message).await.map(|_| ());
+            assert_matches!(res, Err(MlsError::InvalidEpoch));
+        }
+
+        for message in recent {
+            let received = alice.process_incoming_message(message).await.unwrap();
+
+            assert_matches!(
+                received,
+                ReceivedMessage::ApplicationMessage(m) if m.data() == b"foobar"
+            );
+        }
+    }
+
+    #[cfg(feature = "prior_epoch")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn full_state_import_rejects_inconsistent_tree() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        alice.join("bob").await;
+
+        let mut snapshot = alice.snapshot().unwrap();
+        snapshot.state.context.tree_hash = vec![0u8; 32];
+
+        let full_state = snapshot::FullGroupState {
+            version: snapshot::FULL_GROUP_STATE_VERSION,
+            snapshot,
+            prior_epochs: Vec::new(),
+        };
+
+        let mut config = alice.config.clone();
+        config.0.group_state_storage =
+            crate::storage_provider::in_memory::InMemoryGroupStateStorage::new();
+
+        let res = Client::new(config, None, None, TEST_PROTOCOL_VERSION)
+            .import_full_state(&full_state.mls_encode_to_vec().unwrap())
             .await
-            .unwrap();
+            .map(|_| ());
 
-        let ext_sender_id = SigningIdentity {
-            signature_key: ext_sender_pk,
-            credential: Credential::X509(CertificateChain::from(vec![random_bytes(32)])),
+        assert_matches!(res, Err(MlsError::TreeHashMismatch));
+    }
+
+    #[cfg(feature = "prior_epoch")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn full_state_import_rejects_unknown_version() {
+        let alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let full_state = snapshot::FullGroupState {
+            version: snapshot::FULL_GROUP_STATE_VERSION + 1,
+            snapshot: alice.snapshot().unwrap(),
+            prior_epochs: Vec::new(),
         };
 
-        ExternalSendersExt::new(vec![ext_sender_id])
+        let mut config = alice.config.clone();
+        config.0.group_state_storage =
+            crate::storage_provider::in_memory::InMemoryGroupStateStorage::new();
+
+        let res = Client::new(config, None, None, TEST_PROTOCOL_VERSION)
+            .import_full_state(&full_state.mls_encode_to_vec().unwrap())
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::UnsupportedFullStateVersion(2)));
     }
 
-    #[cfg(feature = "by_ref_proposal")]
-    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(feature = "private_message")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_not_supporting_external_sender_credential_leads_to_rejected_commit() {
-        let ext_senders = make_x509_external_senders_ext()
+    async fn skipped_application_generations_are_accepted_by_receiver() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob_group, _) = alice_group.join("bob").await;
+
+        alice_group.skip_application_generations(5).await.unwrap();
+
+        let message = alice_group
+            .encrypt_application_message(b"foobar", Vec::new())
             .await
-            .into_extension()
             .unwrap();
 
-        let mut alice = ClientBuilder::new()
-            .crypto_provider(TestCryptoProvider::new())
-            .identity_provider(
-                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
+        let received_message = bob_group.process_incoming_message(message).await.unwrap();
+
+        assert_matches!(
+            received_message,
+            ReceivedMessage::ApplicationMessage(m) if m.data() == b"foobar"
+        );
+
+        let cipher_suite_provider = alice_group.cipher_suite_provider.clone();
+        let self_index =
+            crate::tree_kem::node::NodeIndex::from(alice_group.private_tree.self_index);
+
+        let next_key = alice_group
+            .epoch_secrets
+            .secret_tree
+            .next_message_key(
+                &cipher_suite_provider,
+                self_index,
+                secret_tree::KeyType::Application,
             )
-            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
-            .await
-            .build()
-            .create_group(vec![ext_senders].into(), Default::default())
             .await
             .unwrap();
 
-        let bob = ClientBuilder::new()
-            .crypto_provider(TestCryptoProvider::new())
-            .identity_provider(
-                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
-            )
-            .with_random_signing_identity("bob", TEST_CIPHER_SUITE)
+        assert_eq!(next_key.generation, 6);
+    }
+
+    #[cfg(feature = "private_message")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn skipping_too_many_application_generations_fails() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let res = alice_group
+            .skip_application_generations(secret_tree::MAX_RATCHET_BACK_HISTORY + 1)
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidFutureGeneration(_)));
+    }
+
+    #[cfg(feature = "private_message")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn skipped_application_generations_are_limited_cumulatively() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob_group, _) = alice_group.join("bob").await;
+        let half = secret_tree::MAX_RATCHET_BACK_HISTORY / 2;
+
+        alice_group
+            .skip_application_generations(half)
             .await
-            .build();
+            .unwrap();
+        alice_group
+            .skip_application_generations(half)
+            .await
+            .unwrap();
 
-        let kp = bob
-            .generate_key_package_message(Default::default(), Default::default())
+        let res = alice_group.skip_application_generations(1).await;
+        assert_matches!(res, Err(MlsError::InvalidFutureGeneration(_)));
+
+        let message = alice_group
+            .encrypt_application_message(b"foobar", Vec::new())
             .await
             .unwrap();
 
-        let commit = alice
+        let received_message = bob_group.process_incoming_message(message).await.unwrap();
+
+        assert_matches!(
+            received_message,
+            ReceivedMessage::ApplicationMessage(m) if m.data() == b"foobar"
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn removing_requirements_allows_to_add() {
+        let mut alice_group = test_group_custom(
+            TEST_PROTOCOL_VERSION,
+            TEST_CIPHER_SUITE,
+            vec![17.into()],
+            None,
+            None,
+        )
+        .await;
+
+        alice_group
             .commit_builder()
-            .add_member(kp)
+            .set_group_context_ext(
+                vec![RequiredCapabilitiesExt {
+                    extensions: vec![17.into()],
+                    ..Default::default()
+                }
+                .into_extension()
+                .unwrap()]
+                .try_into()
+                .unwrap(),
+            )
             .unwrap()
             .build()
             .await
             .unwrap();
 
-        let (mut bob, _) = bob
-            .join_group(None, &commit.welcome_messages[0])
+        alice_group.process_pending_commit().await.unwrap();
+
+        let test_key_package =
+            test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let test_key_package = MlsMessage::new(
+            TEST_PROTOCOL_VERSION,
+            MlsMessagePayload::KeyPackage(test_key_package),
+        );
+
+        alice_group
+            .commit_builder()
+            .add_member(test_key_package.clone())
+            .unwrap()
+            .set_group_context_ext(Default::default())
+            .unwrap()
+            .build()
             .await
             .unwrap();
 
-        alice.apply_pending_commit().await.unwrap();
-
-        // New leaf supports only basic credentials (used by the group) but not X509 used by external sender
-        alice.commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.capabilities.credentials = vec![CredentialType::BASIC];
-            Some(sk.clone())
+        let CommitEffect::NewEpoch(new_epoch) =
+            alice_group.process_pending_commit().await.unwrap().effect
+        else {
+            panic!("unexpected commit effect")
         };
 
-        let commit = alice.commit(vec![]).await.unwrap();
-        let res = bob.process_incoming_message(commit.commit_message).await;
+        assert_eq!(new_epoch.applied_proposals.len(), 2);
 
         assert_matches!(
-            res,
-            Err(MlsError::RequiredCredentialNotFound(CredentialType::X509))
+            new_epoch.applied_proposals[0],
+            ProposalInfo { proposal: Proposal::Add(ref add), .. }
+                if add.key_package == test_key_package.into_key_package().unwrap()
         );
+
+        assert_eq!(alice_group.roster().members_iter().count(), 2);
     }
 
-    #[cfg(feature = "by_ref_proposal")]
-    #[cfg(not(target_arch = "wasm32"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn node_not_supporting_external_sender_credential_cannot_join_group() {
-        let ext_senders = make_x509_external_senders_ext()
-            .await
-            .into_extension()
-            .unwrap();
+    async fn commit_leaf_wrong_source() {
+        // RFC, 13.4.2. "The leaf_node_source field MUST be set to commit."
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
 
-        let mut alice = ClientBuilder::new()
-            .crypto_provider(TestCryptoProvider::new())
-            .identity_provider(
-                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
-            )
-            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
-            .await
-            .build()
-            .create_group(core::iter::once(ext_senders).collect(), Default::default())
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.leaf_node_source = LeafNodeSource::Update;
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidLeafNodeSource));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_same_hpke_key() {
+        // RFC 13.4.2. "Verify that the encryption_key value in the LeafNode is different from the committer's current leaf node"
+
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        // Group 0 starts using fixed key
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.public_key = get_test_25519_key(1u8);
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        groups[0].process_pending_commit().await.unwrap();
+        groups[2]
+            .process_message(commit_output.commit_message)
             .await
             .unwrap();
 
-        let (_, bob_key_pkg) =
-            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+        // Group 0 tries to use the fixed key againd
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::SameHpkeKey(0)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_duplicate_hpke_key() {
+        // RFC 8.3 "Verify that the following fields are unique among the members of the group: `encryption_key`"
+
+        if TEST_CIPHER_SUITE != CipherSuite::CURVE25519_AES128
+            && TEST_CIPHER_SUITE != CipherSuite::CURVE25519_CHACHA
+        {
+            return;
+        }
+
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+
+        // Group 1 uses the fixed key
+        groups[1].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.public_key = get_test_25519_key(1u8);
+            Some(sk.clone())
+        };
+
+        let commit_output = groups.get_mut(1).unwrap().commit(vec![]).await.unwrap();
+
+        process_commit(&mut groups, commit_output.commit_message, 1).await;
+
+        // Group 0 tries to use the fixed key too
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.public_key = get_test_25519_key(1u8);
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[7]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::DuplicateLeafData(_)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn roster_sorted_by_identity_is_stable_when_leaf_is_reused() {
+        fn names(members: &[Member]) -> Vec<&[u8]> {
+            members
+                .iter()
+                .map(|m| {
+                    m.signing_identity
+                        .credential
+                        .as_basic()
+                        .unwrap()
+                        .identifier
+                        .as_slice()
+                })
+                .collect()
+        }
+
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        for name in ["dave", "bob"] {
+            let (_, key_package) =
+                test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, name).await;
+
+            alice
+                .commit_builder()
+                .add_member(key_package)
+                .unwrap()
+                .build()
+                .await
+                .unwrap();
+
+            alice.apply_pending_commit().await.unwrap();
+        }
+
+        let sorted = alice.roster_sorted_by_identity().await.unwrap();
+        assert_eq!(names(&sorted), [&b"bob"[..], b"dave", b"member"]);
+
+        let (_, carol) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
+
+        // Carol takes the leaf of dave.
+        alice
+            .commit_builder()
+            .remove_member(1)
+            .unwrap()
+            .add_member(carol)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let by_index = alice.roster().members();
+        assert_eq!(names(&by_index), [&b"member"[..], b"carol", b"bob"]);
+
+        let sorted = alice.roster_sorted_by_identity().await.unwrap();
+        assert_eq!(names(&sorted), [&b"bob"[..], b"carol", b"member"]);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn member_identity_is_unique_per_leaf() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        // Two devices with the same basic credential, and therefore the same identity.
+        let (_, first_device) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let (_, second_device) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        alice
+            .commit_builder()
+            .add_member(first_device)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let bob = alice.member_with_identity(b"bob").await.unwrap();
+        assert_eq!(bob.index, 1);
+
+        let res = alice
+            .commit_builder()
+            .add_member(second_device)
+            .unwrap()
+            .build()
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::DuplicateLeafData(1)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_duplicate_signature_key() {
+        // RFC 8.3 "Verify that the following fields are unique among the members of the group: `signature_key`"
+
+        if TEST_CIPHER_SUITE != CipherSuite::CURVE25519_AES128
+            && TEST_CIPHER_SUITE != CipherSuite::CURVE25519_CHACHA
+        {
+            return;
+        }
+
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+
+        // Group 1 uses the fixed key
+        groups[1].commit_modifiers.modify_leaf = |leaf, _| {
+            let sk = hex!(
+                "3468b4c890255c983e3d5cbf5cb64c1ef7f6433a518f2f3151d6672f839a06ebcad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b"
+            )
+            .into();
+
+            leaf.signing_identity.signature_key =
+                hex!("cad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b").into();
+
+            Some(sk)
+        };
+
+        let commit_output = groups.get_mut(1).unwrap().commit(vec![]).await.unwrap();
+
+        process_commit(&mut groups, commit_output.commit_message, 1).await;
+
+        // Group 0 tries to use the fixed key too
+        groups[0].commit_modifiers.modify_leaf = |leaf, _| {
+            let sk = hex!(
+                "3468b4c890255c983e3d5cbf5cb64c1ef7f6433a518f2f3151d6672f839a06ebcad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b"
+            )
+            .into();
+
+            leaf.signing_identity.signature_key =
+                hex!("cad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b").into();
+
+            Some(sk)
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[7]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::DuplicateLeafData(_)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_incorrect_signature() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, _| {
+            leaf.signature[0] ^= 1;
+            None
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_not_supporting_used_context_extension() {
+        const EXT_TYPE: ExtensionType = ExtensionType::new(999);
+
+        // The new leaf of the committer doesn't support an extension set in group context
+        let extension = Extension::new(EXT_TYPE, vec![]);
+
+        let mut groups =
+            get_test_groups_with_features(3, vec![extension].into(), Default::default()).await;
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.capabilities = get_test_capabilities();
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[1]
+            .process_incoming_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::UnsupportedGroupExtension(EXT_TYPE)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn group_context_extension_unsupported_by_member_is_rejected() {
+        const EXT_TYPE: ExtensionType = ExtensionType::new(999);
+
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.extension_type(EXT_TYPE)
+        })
+        .await;
+
+        // Bob's capabilities don't include the new extension type
+        alice.join("bob").await;
+
+        let mut extensions = alice.context().extensions.clone();
+        extensions.set(Extension::new(EXT_TYPE, vec![]));
+
+        let res = alice
+            .commit_builder()
+            .set_group_context_ext(extensions)
+            .unwrap()
+            .build()
+            .await
+            .map(|_| ());
+
+        assert_matches!(
+            res,
+            Err(MlsError::GroupExtensionNotSupportedByMember {
+                extension_type: EXT_TYPE,
+                leaf_index: 1,
+            })
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_not_supporting_required_extension() {
+        // The new leaf of the committer doesn't support an extension required by group context
+
+        let extension = RequiredCapabilitiesExt {
+            extensions: vec![999.into()],
+            proposals: vec![],
+            credentials: vec![],
+        };
+
+        let extensions = vec![extension.into_extension().unwrap()];
+        let mut groups =
+            get_test_groups_with_features(3, extensions.into(), Default::default()).await;
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.capabilities = Capabilities::default();
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_incoming_message(commit_output.commit_message)
+            .await;
+
+        assert!(res.is_err());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_has_unsupported_credential() {
+        // The new leaf of the committer has a credential unsupported by another leaf
+        let mut groups =
+            get_test_groups_with_features(3, Default::default(), Default::default()).await;
+
+        for group in groups.iter_mut() {
+            group.config.0.identity_provider.allow_any_custom = true;
+        }
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.signing_identity.credential = Credential::Custom(CustomCredential::new(
+                CredentialType::new(43),
+                leaf.signing_identity
+                    .credential
+                    .as_basic()
+                    .unwrap()
+                    .identifier
+                    .to_vec(),
+            ));
+
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_incoming_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::CredentialTypeOfNewLeafIsUnsupported));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_not_supporting_credential_used_in_another_leaf() {
+        // The new leaf of the committer doesn't support another leaf's credential
+
+        let mut groups =
+            get_test_groups_with_features(3, Default::default(), Default::default()).await;
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.capabilities.credentials = vec![2.into()];
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_incoming_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::InUseCredentialTypeUnsupportedByNewLeaf));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_not_supporting_required_credential() {
+        // The new leaf of the committer doesn't support a credential required by group context
+
+        let extension = RequiredCapabilitiesExt {
+            extensions: vec![],
+            proposals: vec![],
+            credentials: vec![1.into()],
+        };
+
+        let extensions = vec![extension.into_extension().unwrap()];
+        let mut groups =
+            get_test_groups_with_features(3, extensions.into(), Default::default()).await;
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.capabilities.credentials = vec![2.into()];
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_incoming_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::RequiredCredentialNotFound(_)));
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn make_x509_external_senders_ext() -> ExternalSendersExt {
+        let (_, ext_sender_pk) = test_cipher_suite_provider(TEST_CIPHER_SUITE)
+            .signature_key_generate()
+            .await
+            .unwrap();
+
+        let ext_sender_id = SigningIdentity {
+            signature_key: ext_sender_pk,
+            credential: Credential::X509(CertificateChain::from(vec![random_bytes(32)])),
+        };
+
+        ExternalSendersExt::new(vec![ext_sender_id])
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg(not(target_arch = "wasm32"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_not_supporting_external_sender_credential_leads_to_rejected_commit() {
+        let ext_senders = make_x509_external_senders_ext()
+            .await
+            .into_extension()
+            .unwrap();
+
+        let mut alice = ClientBuilder::new()
+            .crypto_provider(TestCryptoProvider::new())
+            .identity_provider(
+                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
+            )
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .build()
+            .create_group(vec![ext_senders].into(), Default::default())
+            .await
+            .unwrap();
+
+        let bob = ClientBuilder::new()
+            .crypto_provider(TestCryptoProvider::new())
+            .identity_provider(
+                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
+            )
+            .with_random_signing_identity("bob", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let kp = bob
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let commit = alice
+            .commit_builder()
+            .add_member(kp)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let (mut bob, _) = bob
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        // New leaf supports only basic credentials (used by the group) but not X509 used by external sender
+        alice.commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.capabilities.credentials = vec![CredentialType::BASIC];
+            Some(sk.clone())
+        };
+
+        let commit = alice.commit(vec![]).await.unwrap();
+        let res = bob.process_incoming_message(commit.commit_message).await;
+
+        assert_matches!(
+            res,
+            Err(MlsError::RequiredCredentialNotFound(CredentialType::X509))
+        );
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg(not(target_arch = "wasm32"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn node_not_supporting_external_sender_credential_cannot_join_group() {
+        let ext_senders = make_x509_external_senders_ext()
+            .await
+            .into_extension()
+            .unwrap();
+
+        let mut alice = ClientBuilder::new()
+            .crypto_provider(TestCryptoProvider::new())
+            .identity_provider(
+                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
+            )
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .build()
+            .create_group(core::iter::once(ext_senders).collect(), Default::default())
+            .await
+            .unwrap();
+
+        let (_, bob_key_pkg) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let commit = alice
+            .commit_builder()
+            .add_member(bob_key_pkg)
+            .unwrap()
+            .build()
+            .await;
+
+        assert_matches!(
+            commit,
+            Err(MlsError::RequiredCredentialNotFound(CredentialType::X509))
+        );
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg(not(target_arch = "wasm32"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_senders_extension_is_rejected_if_member_does_not_support_credential_type() {
+        let mut alice = ClientBuilder::new()
+            .crypto_provider(TestCryptoProvider::new())
+            .identity_provider(
+                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
+            )
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .build()
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let (_, bob_key_pkg) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        alice
+            .commit_builder()
+            .add_member(bob_key_pkg)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+        assert_eq!(alice.roster().members_iter().count(), 2);
+
+        let ext_senders = make_x509_external_senders_ext()
+            .await
+            .into_extension()
+            .unwrap();
+
+        let res = alice
+            .commit_builder()
+            .set_group_context_ext(core::iter::once(ext_senders).collect())
+            .unwrap()
+            .build()
+            .await;
+
+        assert_matches!(
+            res,
+            Err(MlsError::RequiredCredentialNotFound(CredentialType::X509))
+        );
+    }
+
+    /*
+     * Edge case paths
+     */
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn committing_degenerate_path_succeeds() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+
+        groups[0].commit_modifiers.modify_tree = |tree: &mut TreeKemPublic| {
+            tree.update_node(get_test_25519_key(1u8), 1).unwrap();
+            tree.update_node(get_test_25519_key(1u8), 3).unwrap();
+        };
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.public_key = get_test_25519_key(1u8);
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[7]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert!(res.is_ok());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn inserting_key_in_filtered_node_fails() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+
+        let commit_output = groups[0]
+            .commit_builder()
+            .remove_member(1)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        groups[0].process_pending_commit().await.unwrap();
+
+        for group in groups.iter_mut().skip(2) {
+            group
+                .process_message(commit_output.commit_message.clone())
+                .await
+                .unwrap();
+        }
+
+        groups[0].commit_modifiers.modify_tree = |tree: &mut TreeKemPublic| {
+            tree.update_node(get_test_25519_key(1u8), 1).unwrap();
+        };
+
+        groups[0].commit_modifiers.modify_path = |path: Vec<UpdatePathNode>| {
+            let mut path = path;
+            let mut node = path[0].clone();
+            node.public_key = get_test_25519_key(1u8);
+            path.insert(0, node);
+            path
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[7]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        // We should get a path validation error, since the path is too long
+        assert_matches!(res, Err(MlsError::WrongPathLen));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_with_too_short_path_fails() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+
+        let commit_output = groups[0]
+            .commit_builder()
+            .remove_member(1)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        groups[0].process_pending_commit().await.unwrap();
+
+        for group in groups.iter_mut().skip(2) {
+            group
+                .process_message(commit_output.commit_message.clone())
+                .await
+                .unwrap();
+        }
+
+        groups[0].commit_modifiers.modify_path = |path: Vec<UpdatePathNode>| {
+            let mut path = path;
+            path.pop();
+            path
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[7]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert!(res.is_err());
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn update_proposal_can_change_credential() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"member").await;
+
+        let update = groups[0]
+            .propose_update_with_identity(secret_key, identity.clone(), vec![])
+            .await
+            .unwrap();
+
+        groups[1].process_message(update).await.unwrap();
+        let commit_output = groups[1].commit(vec![]).await.unwrap();
+
+        // Check that the credential was updated by in the committer's state.
+        groups[1].process_pending_commit().await.unwrap();
+        let new_member = groups[1].roster().member_with_index(0).unwrap();
+
+        assert_eq!(
+            new_member.signing_identity.credential,
+            get_test_basic_credential(b"member".to_vec())
+        );
+
+        assert_eq!(
+            new_member.signing_identity.signature_key,
+            identity.signature_key
+        );
+
+        // Check that the credential was updated in the updater's state.
+        groups[0]
+            .process_message(commit_output.commit_message)
+            .await
+            .unwrap();
+        let new_member = groups[0].roster().member_with_index(0).unwrap();
+
+        assert_eq!(
+            new_member.signing_identity.credential,
+            get_test_basic_credential(b"member".to_vec())
+        );
+
+        assert_eq!(
+            new_member.signing_identity.signature_key,
+            identity.signature_key
+        );
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn prune_uncached_update_signers_keeps_active_and_cached_keys() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+        let active_signer = groups[0].signer.clone();
+
+        let (first_identity, first_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"member").await;
+
+        groups[0]
+            .propose_update_with_identity(first_key.clone(), first_identity, vec![])
+            .await
+            .unwrap();
+
+        groups[0].clear_proposal_cache();
+
+        let (second_identity, second_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"member").await;
+
+        let update = groups[0]
+            .propose_update_with_identity(second_key.clone(), second_identity, vec![])
+            .await
+            .unwrap();
+
+        groups[0].prune_uncached_update_signers();
+
+        let held_signers = groups[0]
+            .pending_updates
+            .iter()
+            .filter_map(|(_, (_, signer))| signer.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(held_signers, vec![second_key.clone()]);
+        assert_eq!(groups[0].signer, active_signer);
+
+        groups[1].process_message(update).await.unwrap();
+        let commit = groups[1].commit(vec![]).await.unwrap().commit_message;
+
+        groups[0].process_message(commit).await.unwrap();
+        assert_eq!(groups[0].signer, second_key);
+
+        groups[0].prune_uncached_update_signers();
+        assert_eq!(groups[0].signer, second_key);
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn receiving_commit_with_old_adds_fails() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "foobar").await;
+
+        let proposal = groups[0].propose_add(key_package, vec![]).await.unwrap();
+
+        let commit = groups[0].commit(vec![]).await.unwrap().commit_message;
+
+        // 10 years from now
+        let future_time = MlsTime::now().seconds_since_epoch() + 10 * 365 * 24 * 3600;
+
+        let future_time =
+            MlsTime::from_duration_since_epoch(core::time::Duration::from_secs(future_time));
+
+        groups[1].process_incoming_message(proposal).await.unwrap();
+        let res = groups[1]
+            .process_incoming_message_with_time(commit, future_time)
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidLifetime));
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn proposal_authenticated_data_is_kept_through_commit() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+
+        let proposal = groups[1].propose_update(b"routing".to_vec()).await.unwrap();
+
+        let ReceivedMessage::Proposal(proposal_desc) =
+            groups[0].process_incoming_message(proposal).await.unwrap()
+        else {
+            panic!("expected proposal")
+        };
+
+        let proposal_ref = proposal_desc.proposal_ref;
+
+        let commit = groups[0].commit(vec![]).await.unwrap().commit_message;
+        let effect = groups[0].apply_pending_commit().await.unwrap().effect;
+
+        let CommitEffect::NewEpoch(new_epoch) = effect else {
+            panic!("expected new epoch")
+        };
+
+        assert_eq!(
+            new_epoch.proposal_authenticated_data(&proposal_ref),
+            Some(b"routing".as_slice())
+        );
+
+        let ReceivedMessage::Commit(commit_desc) =
+            groups[1].process_incoming_message(commit).await.unwrap()
+        else {
+            panic!("expected commit")
+        };
+
+        let CommitEffect::NewEpoch(new_epoch) = commit_desc.effect else {
+            panic!("expected new epoch")
+        };
+
+        assert_eq!(
+            new_epoch.proposal_authenticated_data(&proposal_ref),
+            Some(b"routing".as_slice())
+        );
+    }
+
+    #[derive(Clone, Debug)]
+    struct TestClock(Arc<AtomicU64>);
+
+    impl Clock for TestClock {
+        fn now(&self) -> MlsTime {
+            MlsTime::from(self.0.load(Ordering::SeqCst))
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn configured_clock_drives_lifetime_checks() {
+        let now = MlsTime::now().seconds_since_epoch();
+        let time = Arc::new(AtomicU64::new(now));
+        let clock = TestClock(time.clone());
+
+        let mut alice =
+            test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| b.clock(clock))
+                .await;
+
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        // 10 years from now
+        time.store(now + 10 * 365 * 24 * 3600, Ordering::SeqCst);
+
+        let res = alice
+            .commit_builder()
+            .add_member(key_package.clone())
+            .unwrap()
+            .build()
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::InvalidLifetime));
+
+        time.store(now, Ordering::SeqCst);
+
+        let res = alice
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await;
+
+        assert!(res.is_ok());
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn custom_proposal_setup() -> (TestGroup, TestGroup) {
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.custom_proposal_type(TEST_CUSTOM_PROPOSAL_TYPE)
+        })
+        .await;
+
+        let (bob, _) = alice
+            .join_with_custom_config("bob", true, |c| {
+                c.0.settings
+                    .custom_proposal_types
+                    .push(TEST_CUSTOM_PROPOSAL_TYPE)
+            })
+            .await
+            .unwrap();
+
+        (alice, bob)
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_by_value() {
+        let (mut alice, mut bob) = custom_proposal_setup().await;
+
+        let custom_proposal = CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![0, 1, 2]);
+
+        let commit = alice
+            .commit_builder()
+            .custom_proposal(custom_proposal.clone())
+            .build()
+            .await
+            .unwrap()
+            .commit_message;
+
+        let ReceivedMessage::Commit(CommitMessageDescription {
+            effect: CommitEffect::NewEpoch(new_epoch),
+            ..
+        }) = bob.process_incoming_message(commit).await.unwrap()
+        else {
+            panic!("unexpected commit effect");
+        };
+
+        assert_eq!(new_epoch.applied_proposals.len(), 1);
+
+        assert_eq!(
+            new_epoch.applied_proposals[0].proposal,
+            Proposal::Custom(custom_proposal)
+        );
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_by_reference() {
+        let (mut alice, mut bob) = custom_proposal_setup().await;
+
+        let custom_proposal = CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![0, 1, 2]);
+
+        let proposal = alice
+            .propose_custom(custom_proposal.clone(), vec![])
+            .await
+            .unwrap();
+
+        let recv_prop = bob.process_incoming_message(proposal).await.unwrap();
+
+        assert_matches!(recv_prop, ReceivedMessage::Proposal(ProposalMessageDescription { proposal: Proposal::Custom(c), ..})
+            if c == custom_proposal);
+
+        let commit = bob.commit(vec![]).await.unwrap().commit_message;
+
+        let ReceivedMessage::Commit(CommitMessageDescription {
+            effect: CommitEffect::NewEpoch(new_epoch),
+            ..
+        }) = bob.process_incoming_message(commit).await.unwrap()
+        else {
+            panic!("unexpected commit effect");
+        };
+
+        assert_eq!(new_epoch.applied_proposals.len(), 1);
+
+        assert_eq!(
+            new_epoch.applied_proposals[0].proposal,
+            Proposal::Custom(custom_proposal)
+        );
+    }
+
+    #[cfg(feature = "psk")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn can_join_with_psk() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let (bob, key_pkg) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let psk_id = ExternalPskId::new(vec![0]);
+        let psk = PreSharedKey::from(vec![0]);
+
+        alice
+            .config
+            .secret_store()
+            .insert(psk_id.clone(), psk.clone());
+
+        bob.config.secret_store().insert(psk_id.clone(), psk);
+
+        let commit = alice
+            .commit_builder()
+            .add_member(key_pkg)
+            .unwrap()
+            .add_external_psk(psk_id)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        bob.join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap();
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn invalid_update_does_not_prevent_other_updates() {
+        const EXTENSION_TYPE: ExtensionType = ExtensionType::new(33);
+
+        let group_extensions = ExtensionList::from(vec![RequiredCapabilitiesExt {
+            extensions: vec![EXTENSION_TYPE],
+            ..Default::default()
+        }
+        .into_extension()
+        .unwrap()]);
+
+        // Alice creates a group requiring support for an extension
+        let mut alice = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .extension_type(EXTENSION_TYPE)
+            .build()
+            .create_group(group_extensions.clone(), Default::default())
+            .await
+            .unwrap();
+
+        let (bob_signing_identity, bob_secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let bob_client = TestClientBuilder::new_for_test()
+            .signing_identity(
+                bob_signing_identity.clone(),
+                bob_secret_key.clone(),
+                TEST_CIPHER_SUITE,
+            )
+            .extension_type(EXTENSION_TYPE)
+            .build();
+
+        let carol_client = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("carol", TEST_CIPHER_SUITE)
+            .await
+            .extension_type(EXTENSION_TYPE)
+            .build();
+
+        let dave_client = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("dave", TEST_CIPHER_SUITE)
+            .await
+            .extension_type(EXTENSION_TYPE)
+            .build();
+
+        // Alice adds Bob, Carol and Dave to the group. They all support the mandatory extension.
+        let commit = alice
+            .commit_builder()
+            .add_member(
+                bob_client
+                    .generate_key_package_message(Default::default(), Default::default())
+                    .await
+                    .unwrap(),
+            )
+            .unwrap()
+            .add_member(
+                carol_client
+                    .generate_key_package_message(Default::default(), Default::default())
+                    .await
+                    .unwrap(),
+            )
+            .unwrap()
+            .add_member(
+                dave_client
+                    .generate_key_package_message(Default::default(), Default::default())
+                    .await
+                    .unwrap(),
+            )
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let mut bob = bob_client
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap()
+            .0;
+
+        bob.write_to_storage().await.unwrap();
+
+        // Bob reloads his group data, but with parameters that will cause his generated leaves to
+        // not support the mandatory extension.
+        let mut bob = TestClientBuilder::new_for_test()
+            .signing_identity(bob_signing_identity, bob_secret_key, TEST_CIPHER_SUITE)
+            .key_package_repo(bob.config.key_package_repo())
+            .group_state_storage(bob.config.group_state_storage())
+            .build()
+            .load_group(alice.group_id())
+            .await
+            .unwrap();
+
+        let mut carol = carol_client
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap()
+            .0;
+
+        let mut dave = dave_client
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap()
+            .0;
+
+        // Bob's updated leaf does not support the mandatory extension.
+        let bob_update = bob.propose_update(Vec::new()).await.unwrap();
+        let carol_update = carol.propose_update(Vec::new()).await.unwrap();
+        let dave_update = dave.propose_update(Vec::new()).await.unwrap();
+
+        // Alice receives the update proposals to be committed.
+        alice.process_incoming_message(bob_update).await.unwrap();
+        alice.process_incoming_message(carol_update).await.unwrap();
+        alice.process_incoming_message(dave_update).await.unwrap();
+
+        // Alice commits the update proposals.
+        alice.commit(Vec::new()).await.unwrap();
+
+        let CommitEffect::NewEpoch(new_epoch) = alice.apply_pending_commit().await.unwrap().effect
+        else {
+            panic!("unexpected commit effect");
+        };
+
+        let find_update_for = |id: &str| {
+            new_epoch
+                .applied_proposals
+                .iter()
+                .filter_map(|p| match p.proposal {
+                    Proposal::Update(ref u) => u.signing_identity().credential.as_basic(),
+                    _ => None,
+                })
+                .any(|c| c.identifier == id.as_bytes())
+        };
+
+        // Carol's and Dave's updates should be part of the commit.
+        assert!(find_update_for("carol"));
+        assert!(find_update_for("dave"));
+
+        // Bob's update should be rejected.
+        assert!(!find_update_for("bob"));
+
+        // Check that all members are still in the group.
+        let all_members_are_in = alice
+            .roster()
+            .members_iter()
+            .zip(["alice", "bob", "carol", "dave"])
+            .all(|(member, id)| {
+                member
+                    .signing_identity
+                    .credential
+                    .as_basic()
+                    .unwrap()
+                    .identifier
+                    == id.as_bytes()
+            });
+
+        assert!(all_members_are_in);
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_may_enforce_path() {
+        test_custom_proposal_mls_rules(true).await;
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_need_not_enforce_path() {
+        test_custom_proposal_mls_rules(false).await;
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn test_custom_proposal_mls_rules(path_required_for_custom: bool) {
+        let mls_rules = CustomMlsRules {
+            path_required_for_custom,
+            external_joiner_can_send_custom: true,
+        };
+
+        let mut alice = client_with_custom_rules(b"alice", mls_rules.clone())
+            .await
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let alice_pub_before = alice.current_user_leaf_node().unwrap().public_key.clone();
+
+        let kp = client_with_custom_rules(b"bob", mls_rules)
+            .await
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        alice
+            .commit_builder()
+            .custom_proposal(CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![]))
+            .add_member(kp)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let alice_pub_after = &alice.current_user_leaf_node().unwrap().public_key;
+
+        if path_required_for_custom {
+            assert_ne!(alice_pub_after, &alice_pub_before);
+        } else {
+            assert_eq!(alice_pub_after, &alice_pub_before);
+        }
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_by_value_in_external_join_may_be_allowed() {
+        test_custom_proposal_by_value_in_external_join(true).await
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_by_value_in_external_join_may_not_be_allowed() {
+        test_custom_proposal_by_value_in_external_join(false).await
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn test_custom_proposal_by_value_in_external_join(external_joiner_can_send_custom: bool) {
+        let mls_rules = CustomMlsRules {
+            path_required_for_custom: true,
+            external_joiner_can_send_custom,
+        };
+
+        let mut alice = client_with_custom_rules(b"alice", mls_rules.clone())
+            .await
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let group_info = alice
+            .group_info_message_allowing_ext_commit(true)
+            .await
+            .unwrap();
+
+        let commit = client_with_custom_rules(b"bob", mls_rules)
+            .await
+            .external_commit_builder()
+            .unwrap()
+            .with_custom_proposal(CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![]))
+            .build(group_info)
+            .await;
+
+        if external_joiner_can_send_custom {
+            let commit = commit.unwrap().1;
+            alice.process_incoming_message(commit).await.unwrap();
+        } else {
+            assert_matches!(commit.map(|_| ()), Err(MlsError::MlsRulesError(_)));
+        }
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_by_ref_in_external_join() {
+        let mls_rules = CustomMlsRules {
+            path_required_for_custom: true,
+            external_joiner_can_send_custom: true,
+        };
+
+        let mut alice = client_with_custom_rules(b"alice", mls_rules.clone())
+            .await
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let by_ref = CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![]);
+        let by_ref = alice.propose_custom(by_ref, vec![]).await.unwrap();
+
+        let group_info = alice
+            .group_info_message_allowing_ext_commit(true)
+            .await
+            .unwrap();
+
+        let (_, commit) = client_with_custom_rules(b"bob", mls_rules)
+            .await
+            .external_commit_builder()
+            .unwrap()
+            .with_received_custom_proposal(by_ref)
+            .build(group_info)
+            .await
+            .unwrap();
+
+        alice.process_incoming_message(commit).await.unwrap();
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn client_with_custom_rules(
+        name: &[u8],
+        mls_rules: CustomMlsRules,
+    ) -> Client<impl MlsConfig> {
+        let (signing_identity, signer) = get_test_signing_identity(TEST_CIPHER_SUITE, name).await;
+
+        ClientBuilder::new()
+            .crypto_provider(TestCryptoProvider::new())
+            .identity_provider(BasicWithCustomProvider::new(BasicIdentityProvider::new()))
+            .signing_identity(signing_identity, signer, TEST_CIPHER_SUITE)
+            .custom_proposal_type(TEST_CUSTOM_PROPOSAL_TYPE)
+            .mls_rules(mls_rules)
+            .build()
+    }
+
+    #[derive(Debug, Clone)]
+    struct CustomMlsRules {
+        path_required_for_custom: bool,
+        external_joiner_can_send_custom: bool,
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    impl ProposalBundle {
+        fn has_test_custom_proposal(&self) -> bool {
+            self.custom_proposal_types()
+                .any(|t| t == TEST_CUSTOM_PROPOSAL_TYPE)
+        }
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl crate::MlsRules for CustomMlsRules {
+        type Error = MlsError;
+
+        fn commit_options(
+            &self,
+            _: &Roster,
+            _: &GroupContext,
+            proposals: &ProposalBundle,
+        ) -> Result<CommitOptions, MlsError> {
+            Ok(CommitOptions::default().with_path_required(
+                !proposals.has_test_custom_proposal() || self.path_required_for_custom,
+            ))
+        }
+
+        fn encryption_options(
+            &self,
+            _: &Roster,
+            _: &GroupContext,
+        ) -> Result<crate::mls_rules::EncryptionOptions, MlsError> {
+            Ok(Default::default())
+        }
+
+        async fn filter_proposals(
+            &self,
+            _: CommitDirection,
+            sender: CommitSource,
+            _: &Roster,
+            _: &GroupContext,
+            proposals: ProposalBundle,
+        ) -> Result<ProposalBundle, MlsError> {
+            let is_external = matches!(sender, CommitSource::NewMember(_));
+            let has_custom = proposals.has_test_custom_proposal();
+            let allowed = !has_custom || !is_external || self.external_joiner_can_send_custom;
+
+            allowed.then_some(proposals).ok_or(MlsError::InvalidSender)
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct RejectAddsOnReceive;
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl crate::MlsRules for RejectAddsOnReceive {
+        type Error = MlsError;
+
+        fn commit_options(
+            &self,
+            _: &Roster,
+            _: &GroupContext,
+            _: &crate::mls_rules::ProposalBundle,
+        ) -> Result<CommitOptions, MlsError> {
+            Ok(Default::default())
+        }
+
+        fn encryption_options(
+            &self,
+            _: &Roster,
+            _: &GroupContext,
+        ) -> Result<crate::mls_rules::EncryptionOptions, MlsError> {
+            Ok(Default::default())
+        }
 
-        let commit = alice
-            .commit_builder()
-            .add_member(bob_key_pkg)
-            .unwrap()
-            .build()
-            .await;
+        async fn filter_proposals(
+            &self,
+            direction: crate::mls_rules::CommitDirection,
+            _: crate::mls_rules::CommitSource,
+            _: &Roster,
+            _: &GroupContext,
+            mut proposals: crate::mls_rules::ProposalBundle,
+        ) -> Result<crate::mls_rules::ProposalBundle, MlsError> {
+            if direction == crate::mls_rules::CommitDirection::Receive {
+                proposals.retain_by_type::<AddProposal, _, _>(|_| Ok::<_, MlsError>(false))?;
+            }
 
-        assert_matches!(
-            commit,
-            Err(MlsError::RequiredCredentialNotFound(CredentialType::X509))
-        );
+            Ok(proposals)
+        }
     }
 
     #[cfg(feature = "by_ref_proposal")]
-    #[cfg(not(target_arch = "wasm32"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn external_senders_extension_is_rejected_if_member_does_not_support_credential_type() {
-        let mut alice = ClientBuilder::new()
-            .crypto_provider(TestCryptoProvider::new())
-            .identity_provider(
-                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
-            )
-            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
-            .await
-            .build()
-            .create_group(Default::default(), Default::default())
+    async fn capability_downgrading_update_is_rejected() {
+        use crate::{
+            extension::test_utils::TEST_EXTENSION_TYPE, mls_rules::CapabilityMonotonicityRules,
+        };
+
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let (mut bob, _) = alice
+            .join_with_custom_config("bob", true, |c| {
+                c.0.settings
+                    .extension_types
+                    .push(TEST_EXTENSION_TYPE.into())
+            })
             .await
             .unwrap();
 
-        let (_, bob_key_pkg) =
-            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+        let (carol_identity, carol_secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"carol").await;
 
-        alice
+        let carol = TestClientBuilder::new_for_test()
+            .mls_rules(CapabilityMonotonicityRules::new(DefaultMlsRules::new()))
+            .signing_identity(carol_identity, carol_secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let carol_key_package = carol
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let commit = alice
             .commit_builder()
-            .add_member(bob_key_pkg)
+            .add_member(carol_key_package)
             .unwrap()
             .build()
             .await
             .unwrap();
 
         alice.apply_pending_commit().await.unwrap();
-        assert_eq!(alice.roster().members_iter().count(), 2);
+        bob.process_message(commit.commit_message).await.unwrap();
 
-        let ext_senders = make_x509_external_senders_ext()
+        let (mut carol_group, _) = carol
+            .join_group(commit.ratchet_tree, &commit.welcome_messages[0])
             .await
-            .into_extension()
             .unwrap();
 
-        let res = alice
-            .commit_builder()
-            .set_group_context_ext(core::iter::once(ext_senders).collect())
-            .unwrap()
-            .build()
-            .await;
+        // Bob's new leaf nodes stop advertising the extension type.
+        bob.config.0.settings.extension_types.clear();
+        let update = bob.propose_update(vec![]).await.unwrap();
 
-        assert_matches!(
-            res,
-            Err(MlsError::RequiredCredentialNotFound(CredentialType::X509))
-        );
-    }
+        alice.process_message(update.clone()).await.unwrap();
+        carol_group.process_incoming_message(update).await.unwrap();
 
-    /*
-     * Edge case paths
-     */
+        // Carol's own commit leaves out the update received by reference.
+        let output = carol_group.commit(vec![]).await.unwrap();
+        assert_eq!(output.unused_proposals.len(), 1);
+        carol_group.clear_pending_commit();
 
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn committing_degenerate_path_succeeds() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+        let downgrade = MlsError::CapabilityDowngrade(1).to_string();
 
-        groups[0].commit_modifiers.modify_tree = |tree: &mut TreeKemPublic| {
-            tree.update_node(get_test_25519_key(1u8), 1).unwrap();
-            tree.update_node(get_test_25519_key(1u8), 3).unwrap();
-        };
+        // Alice's commit including the update is rejected by Carol.
+        let commit = alice.commit(vec![]).await.unwrap();
+        alice.clear_pending_commit();
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.public_key = get_test_25519_key(1u8);
-            Some(sk.clone())
-        };
+        let res = carol_group
+            .process_incoming_message(commit.commit_message)
+            .await
+            .map(|_| ());
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        assert_matches!(res, Err(MlsError::MlsRulesError(e)) if e.to_string() == downgrade);
 
-        let res = groups[7]
-            .process_message(commit_output.commit_message)
-            .await;
+        // So is Bob's commit, whose update path carries the downgraded leaf node.
+        let commit = bob.commit(vec![]).await.unwrap();
 
-        assert!(res.is_ok());
+        let res = carol_group
+            .process_incoming_message(commit.commit_message)
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::MlsRulesError(e)) if e.to_string() == downgrade);
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn inserting_key_in_filtered_node_fails() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+    async fn receiver_is_notified_of_proposals_its_rules_would_reject() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
 
-        let commit_output = groups[0]
+        let (bob_identity, bob_secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let bob = TestClientBuilder::new_for_test()
+            .mls_rules(RejectAddsOnReceive)
+            .warn_on_foreign_rejected_proposals(true)
+            .signing_identity(bob_identity, bob_secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let bob_key_package = bob
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let commit = alice
             .commit_builder()
-            .remove_member(1)
+            .add_member(bob_key_package)
             .unwrap()
             .build()
             .await
             .unwrap();
 
-        groups[0].process_pending_commit().await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
 
-        for group in groups.iter_mut().skip(2) {
-            group
-                .process_message(commit_output.commit_message.clone())
-                .await
-                .unwrap();
-        }
+        let (mut bob_group, _) = bob
+            .join_group(commit.ratchet_tree, &commit.welcome_messages[0])
+            .await
+            .unwrap();
 
-        groups[0].commit_modifiers.modify_tree = |tree: &mut TreeKemPublic| {
-            tree.update_node(get_test_25519_key(1u8), 1).unwrap();
-        };
+        let (_, carol_key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
 
-        groups[0].commit_modifiers.modify_path = |path: Vec<UpdatePathNode>| {
-            let mut path = path;
-            let mut node = path[0].clone();
-            node.public_key = get_test_25519_key(1u8);
-            path.insert(0, node);
-            path
+        let commit = alice
+            .commit_builder()
+            .add_member(carol_key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let message = bob_group
+            .process_incoming_message(commit.commit_message)
+            .await
+            .unwrap();
+
+        let ReceivedMessage::Commit(description) = message else {
+            panic!("expected commit");
         };
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let CommitEffect::NewEpoch(new_epoch) = description.effect else {
+            panic!("expected new epoch");
+        };
 
-        let res = groups[7]
-            .process_message(commit_output.commit_message)
-            .await;
+        // The add is applied as committed and reported as rejected by the local rules.
+        assert_eq!(new_epoch.applied_proposals.len(), 1);
+        assert_eq!(new_epoch.rejected_proposals, new_epoch.applied_proposals);
+        assert_matches!(new_epoch.rejected_proposals[0].proposal, Proposal::Add(_));
 
-        // We should get a path validation error, since the path is too long
-        assert_matches!(res, Err(MlsError::WrongPathLen));
+        assert_eq!(bob_group.roster().members().len(), 3);
+        assert_eq!(bob_group.context(), alice.context());
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_with_too_short_path_fails() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+    async fn group_can_receive_commit_from_self() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
 
-        let commit_output = groups[0]
-            .commit_builder()
-            .remove_member(1)
-            .unwrap()
-            .build()
+        let commit = group.commit(vec![]).await.unwrap();
+
+        let update = group
+            .process_incoming_message(commit.commit_message)
             .await
             .unwrap();
 
-        groups[0].process_pending_commit().await.unwrap();
+        let ReceivedMessage::Commit(update) = update else {
+            panic!("expected commit message")
+        };
 
-        for group in groups.iter_mut().skip(2) {
-            group
-                .process_message(commit_output.commit_message.clone())
-                .await
-                .unwrap();
-        }
+        assert_eq!(update.committer, *group.private_tree.self_index);
+    }
 
-        groups[0].commit_modifiers.modify_path = |path: Vec<UpdatePathNode>| {
-            let mut path = path;
-            path.pop();
-            path
-        };
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn can_process_commit_when_pending_commit() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let commit = groups[0].commit(vec![]).await.unwrap().commit_message;
+        groups[1].commit(vec![]).await.unwrap();
 
-        let res = groups[7]
-            .process_message(commit_output.commit_message)
-            .await;
+        groups[1].process_incoming_message(commit).await.unwrap();
 
-        assert!(res.is_err());
+        let res = groups[1].apply_pending_commit().await;
+        assert_matches!(res, Err(MlsError::PendingCommitNotFound));
     }
 
     #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn update_proposal_can_change_credential() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
-        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"member").await;
+    async fn can_process_own_plaintext_proposal() {
+        can_process_own_proposal(false).await;
+    }
 
-        let update = groups[0]
-            .propose_update_with_identity(secret_key, identity.clone(), vec![])
-            .await
-            .unwrap();
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn can_process_own_ciphertext_proposal() {
+        can_process_own_proposal(true).await;
+    }
 
-        groups[1].process_message(update).await.unwrap();
-        let commit_output = groups[1].commit(vec![]).await.unwrap();
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn can_process_own_proposal(encrypt_proposal: bool) {
+        let (alice, _) = test_client_with_key_pkg_custom(
+            TEST_PROTOCOL_VERSION,
+            TEST_CIPHER_SUITE,
+            "alice",
+            Default::default(),
+            Default::default(),
+            |c| c.0.mls_rules.encryption_options.encrypt_control_messages = encrypt_proposal,
+        )
+        .await;
 
-        // Check that the credential was updated by in the committer's state.
-        groups[1].process_pending_commit().await.unwrap();
-        let new_member = groups[1].roster().member_with_index(0).unwrap();
+        let mut alice = TestGroup {
+            group: alice
+                .create_group(Default::default(), Default::default())
+                .await
+                .unwrap(),
+        };
 
-        assert_eq!(
-            new_member.signing_identity.credential,
-            get_test_basic_credential(b"member".to_vec())
-        );
+        let mut bob = alice.join("bob").await.0;
+        let mut alice = alice;
 
-        assert_eq!(
-            new_member.signing_identity.signature_key,
-            identity.signature_key
-        );
+        let upd = alice.propose_update(vec![]).await.unwrap();
+        alice.process_incoming_message(upd.clone()).await.unwrap();
 
-        // Check that the credential was updated in the updater's state.
-        groups[0]
-            .process_message(commit_output.commit_message)
-            .await
-            .unwrap();
-        let new_member = groups[0].roster().member_with_index(0).unwrap();
+        bob.process_incoming_message(upd).await.unwrap();
+        let commit = bob.commit(vec![]).await.unwrap().commit_message;
+        let update = alice.process_incoming_message(commit).await.unwrap();
 
-        assert_eq!(
-            new_member.signing_identity.credential,
-            get_test_basic_credential(b"member".to_vec())
-        );
+        let ReceivedMessage::Commit(CommitMessageDescription {
+            effect: CommitEffect::NewEpoch(new_epoch),
+            ..
+        }) = update
+        else {
+            panic!("unexpected commit effect")
+        };
 
-        assert_eq!(
-            new_member.signing_identity.signature_key,
-            identity.signature_key
-        );
+        assert_eq!(new_epoch.applied_proposals.len(), 1);
+        assert_eq!(new_epoch.applied_proposals[0].sender, Sender::Member(0));
     }
 
     #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn receiving_commit_with_old_adds_fails() {
+    async fn commit_clears_proposals() {
         let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
 
-        let key_package =
-            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "foobar").await;
-
-        let proposal = groups[0].propose_add(key_package, vec![]).await.unwrap();
-
-        let commit = groups[0].commit(vec![]).await.unwrap().commit_message;
-
-        // 10 years from now
-        let future_time = MlsTime::now().seconds_since_epoch() + 10 * 365 * 24 * 3600;
+        groups[0].propose_update(vec![]).await.unwrap();
 
-        let future_time =
-            MlsTime::from_duration_since_epoch(core::time::Duration::from_secs(future_time));
+        assert_eq!(groups[0].state.proposals.proposals.len(), 1);
+        assert_eq!(groups[0].state.proposals.own_proposals.len(), 1);
 
-        groups[1].process_incoming_message(proposal).await.unwrap();
-        let res = groups[1]
-            .process_incoming_message_with_time(commit, future_time)
-            .await;
+        let commit = groups[1].commit(vec![]).await.unwrap().commit_message;
+        groups[0].process_message(commit).await.unwrap();
 
-        assert_matches!(res, Err(MlsError::InvalidLifetime));
+        assert!(groups[0].state.proposals.proposals.is_empty());
+        assert!(groups[0].state.proposals.own_proposals.is_empty());
     }
 
-    #[cfg(feature = "custom_proposal")]
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn custom_proposal_setup() -> (TestGroup, TestGroup) {
-        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
-            b.custom_proposal_type(TEST_CUSTOM_PROPOSAL_TYPE)
-        })
-        .await;
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_required_is_true_when_proposals_pending() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
 
-        let (bob, _) = alice
-            .join_with_custom_config("bob", true, |c| {
-                c.0.settings
-                    .custom_proposal_types
-                    .push(TEST_CUSTOM_PROPOSAL_TYPE)
-            })
+        assert!(!group.commit_required());
+
+        group
+            .propose_group_context_extensions(ExtensionList::new(), vec![])
             .await
             .unwrap();
 
-        (alice, bob)
+        assert!(group.commit_required());
+
+        let res = group.encrypt_application_message(&[0u8; 32], vec![]).await;
+
+        assert_matches!(res, Err(MlsError::CommitRequired));
+
+        group.commit(vec![]).await.unwrap();
+        group.apply_pending_commit().await.unwrap();
+
+        assert!(!group.commit_required());
     }
 
-    #[cfg(feature = "custom_proposal")]
+    #[cfg(all(feature = "by_ref_proposal", feature = "private_message"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_by_value() {
-        let (mut alice, mut bob) = custom_proposal_setup().await;
+    async fn validate_proposal_message_checks_sender_authorization() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
 
-        let custom_proposal = CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![0, 1, 2]);
+        // A proposal removing the validating member is valid even though that
+        // member could not commit it.
+        let proposal = groups[1].propose_remove(0, vec![]).await.unwrap();
 
-        let commit = alice
-            .commit_builder()
-            .custom_proposal(custom_proposal.clone())
-            .build()
+        groups[0]
+            .validate_proposal_message(&proposal)
             .await
-            .unwrap()
-            .commit_message;
+            .unwrap();
 
-        let ReceivedMessage::Commit(CommitMessageDescription {
-            effect: CommitEffect::NewEpoch(new_epoch),
-            ..
-        }) = bob.process_incoming_message(commit).await.unwrap()
-        else {
-            panic!("unexpected commit effect");
-        };
+        assert!(!groups[0].commit_required());
 
-        assert_eq!(new_epoch.applied_proposals.len(), 1);
+        // Only new members joining by external commit may send external init.
+        let external_init = Proposal::ExternalInit(ExternalInit {
+            kem_output: vec![0; 32],
+        });
 
-        assert_eq!(
-            new_epoch.applied_proposals[0].proposal,
-            Proposal::Custom(custom_proposal)
-        );
+        let proposal = groups[1]
+            .make_plaintext(Content::Proposal(Box::new(external_init)))
+            .await;
+
+        let res = groups[0].validate_proposal_message(&proposal).await;
+
+        assert_matches!(res, Err(MlsError::InvalidProposalTypeForSender));
+        assert!(!groups[0].commit_required());
     }
 
-    #[cfg(feature = "custom_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_by_reference() {
-        let (mut alice, mut bob) = custom_proposal_setup().await;
-
-        let custom_proposal = CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![0, 1, 2]);
+    async fn validate_commit_does_not_modify_state() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+        let epoch = groups[1].current_epoch();
 
-        let proposal = alice
-            .propose_custom(custom_proposal.clone(), vec![])
+        let commit = groups[0]
+            .commit_builder()
+            .remove_member(2)
+            .unwrap()
+            .build()
             .await
-            .unwrap();
-
-        let recv_prop = bob.process_incoming_message(proposal).await.unwrap();
+            .unwrap()
+            .commit_message;
 
-        assert_matches!(recv_prop, ReceivedMessage::Proposal(ProposalMessageDescription { proposal: Proposal::Custom(c), ..})
-            if c == custom_proposal);
+        let mut tampered = commit.to_bytes().unwrap();
+        *tampered.last_mut().unwrap() ^= 1;
+        let tampered = MlsMessage::from_bytes(&tampered).unwrap();
 
-        let commit = bob.commit(vec![]).await.unwrap().commit_message;
+        let res = groups[1].validate_commit(&tampered).await;
+        assert!(res.is_err());
+        assert_eq!(groups[1].current_epoch(), epoch);
 
-        let ReceivedMessage::Commit(CommitMessageDescription {
-            effect: CommitEffect::NewEpoch(new_epoch),
-            ..
-        }) = bob.process_incoming_message(commit).await.unwrap()
-        else {
-            panic!("unexpected commit effect");
-        };
+        let description = groups[1].validate_commit(&commit).await.unwrap();
 
-        assert_eq!(new_epoch.applied_proposals.len(), 1);
+        assert_eq!(description.committer, 0);
+        assert_matches!(description.effect, CommitEffect::NewEpoch(_));
+        assert_eq!(groups[1].current_epoch(), epoch);
+        assert_eq!(groups[1].roster().members().len(), 3);
 
-        assert_eq!(
-            new_epoch.applied_proposals[0].proposal,
-            Proposal::Custom(custom_proposal)
-        );
+        let processed = groups[1].process_message(commit).await.unwrap();
+        assert_matches!(processed, ReceivedMessage::Commit(d) if d == description);
+        assert_eq!(groups[1].current_epoch(), epoch + 1);
     }
 
-    #[cfg(feature = "psk")]
+    // Testing with std is sufficient. Non-std creates incompatible storage and a lot of special cases.
+    #[cfg(feature = "std")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn can_join_with_psk() {
-        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+    async fn can_be_stored_without_tree() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let storage = group.config.group_state_storage().inner;
 
-        let (bob, key_pkg) =
-            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+        group.write_to_storage().await.unwrap();
+        let snapshot_with_tree = storage.lock().unwrap().drain().next().unwrap().1;
 
-        let psk_id = ExternalPskId::new(vec![0]);
-        let psk = PreSharedKey::from(vec![0]);
+        group.write_to_storage_without_ratchet_tree().await.unwrap();
+        let snapshot_without_tree = storage.lock().unwrap().iter().next().unwrap().1.clone();
 
-        alice
-            .config
-            .secret_store()
-            .insert(psk_id.clone(), psk.clone());
+        let tree = group.state.public_tree.nodes.mls_encode_to_vec().unwrap();
+        let empty_tree = Vec::<u8>::new().mls_encode_to_vec().unwrap();
 
-        bob.config.secret_store().insert(psk_id.clone(), psk);
+        assert_eq!(
+            snapshot_with_tree.state_data.len() - snapshot_without_tree.state_data.len(),
+            tree.len() - empty_tree.len()
+        );
 
-        let commit = alice
-            .commit_builder()
-            .add_member(key_pkg)
-            .unwrap()
-            .add_external_psk(psk_id)
-            .unwrap()
-            .build()
-            .await
-            .unwrap();
+        let exported_tree = group.export_tree();
 
-        bob.join_group(None, &commit.welcome_messages[0])
+        let restored = Client::new(group.config.clone(), None, None, TEST_PROTOCOL_VERSION)
+            .load_group_with_ratchet_tree(group.group_id(), exported_tree)
             .await
             .unwrap();
+
+        assert_eq!(restored.group_state(), group.group_state());
     }
 
-    #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn invalid_update_does_not_prevent_other_updates() {
-        const EXTENSION_TYPE: ExtensionType = ExtensionType::new(33);
-
-        let group_extensions = ExtensionList::from(vec![RequiredCapabilitiesExt {
-            extensions: vec![EXTENSION_TYPE],
-            ..Default::default()
-        }
-        .into_extension()
-        .unwrap()]);
+    async fn join_is_rejected_unless_inviter_is_allowed() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
 
-        // Alice creates a group requiring support for an extension
-        let mut alice = TestClientBuilder::new_for_test()
-            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
-            .await
-            .extension_type(EXTENSION_TYPE)
-            .build()
-            .create_group(group_extensions.clone(), Default::default())
+        let inviter = alice
+            .identity_provider()
+            .identity(
+                alice.current_member_signing_identity().unwrap(),
+                &alice.context().extensions,
+            )
             .await
             .unwrap();
 
-        let (bob_signing_identity, bob_secret_key) =
-            get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+        let res = alice
+            .join_with_custom_config("bob", false, |c| {
+                c.0.settings.allowed_inviters = Some(vec![b"mallory".to_vec()])
+            })
+            .await
+            .map(|_| ());
 
-        let bob_client = TestClientBuilder::new_for_test()
-            .signing_identity(
-                bob_signing_identity.clone(),
-                bob_secret_key.clone(),
-                TEST_CIPHER_SUITE,
-            )
-            .extension_type(EXTENSION_TYPE)
-            .build();
+        assert_matches!(res, Err(MlsError::UnauthorizedInviter));
 
-        let carol_client = TestClientBuilder::new_for_test()
-            .with_random_signing_identity("carol", TEST_CIPHER_SUITE)
+        let res = alice
+            .join_with_custom_config("carol", false, |c| {
+                c.0.settings.allowed_inviters = Some(vec![b"mallory".to_vec(), inviter.clone()])
+            })
             .await
-            .extension_type(EXTENSION_TYPE)
-            .build();
+            .map(|_| ());
 
-        let dave_client = TestClientBuilder::new_for_test()
-            .with_random_signing_identity("dave", TEST_CIPHER_SUITE)
+        assert_matches!(res, Ok(()));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn removed_member_identity_is_retrievable_after_commit() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (bob, _) = alice.join("bob").await;
+        let (mut carol, _) = alice.join("carol").await;
+
+        let bob_identity = alice
+            .identity_provider()
+            .identity(
+                bob.current_member_signing_identity().unwrap(),
+                &alice.context().extensions,
+            )
             .await
-            .extension_type(EXTENSION_TYPE)
-            .build();
+            .unwrap();
 
-        // Alice adds Bob, Carol and Dave to the group. They all support the mandatory extension.
         let commit = alice
             .commit_builder()
-            .add_member(
-                bob_client
-                    .generate_key_package_message(Default::default(), Default::default())
-                    .await
-                    .unwrap(),
-            )
-            .unwrap()
-            .add_member(
-                carol_client
-                    .generate_key_package_message(Default::default(), Default::default())
-                    .await
-                    .unwrap(),
-            )
-            .unwrap()
-            .add_member(
-                dave_client
-                    .generate_key_package_message(Default::default(), Default::default())
-                    .await
-                    .unwrap(),
-            )
+            .remove_member(bob.current_member_index())
             .unwrap()
             .build()
             .await
@@ -4036,129 +6824,130 @@ mod tests {
 
         alice.apply_pending_commit().await.unwrap();
 
-        let mut bob = bob_client
-            .join_group(None, &commit.welcome_messages[0])
-            .await
-            .unwrap()
-            .0;
-
-        bob.write_to_storage().await.unwrap();
+        let received = carol.process_message(commit.commit_message).await.unwrap();
 
-        // Bob reloads his group data, but with parameters that will cause his generated leaves to
-        // not support the mandatory extension.
-        let mut bob = TestClientBuilder::new_for_test()
-            .signing_identity(bob_signing_identity, bob_secret_key, TEST_CIPHER_SUITE)
-            .key_package_repo(bob.config.key_package_repo())
-            .group_state_storage(bob.config.group_state_storage())
-            .build()
-            .load_group(alice.group_id())
+        let ReceivedMessage::Commit(CommitMessageDescription {
+            effect: CommitEffect::NewEpoch(new_epoch),
+            ..
+        }) = received
+        else {
+            panic!("expected new epoch");
+        };
+
+        let identities = new_epoch
+            .removed_identities(&carol.identity_provider())
             .await
             .unwrap();
 
-        let mut carol = carol_client
-            .join_group(None, &commit.welcome_messages[0])
-            .await
-            .unwrap()
-            .0;
+        assert_eq!(identities, vec![bob_identity]);
 
-        let mut dave = dave_client
-            .join_group(None, &commit.welcome_messages[0])
-            .await
-            .unwrap()
-            .0;
+        let bob_signing_identity = bob.current_member_signing_identity().unwrap();
 
-        // Bob's updated leaf does not support the mandatory extension.
-        let bob_update = bob.propose_update(Vec::new()).await.unwrap();
-        let carol_update = carol.propose_update(Vec::new()).await.unwrap();
-        let dave_update = dave.propose_update(Vec::new()).await.unwrap();
+        for group in [&alice, &carol] {
+            let removed = group.last_removed();
+            assert_eq!(removed.len(), 1);
+            assert_eq!(&removed[0].signing_identity, bob_signing_identity);
+        }
 
-        // Alice receives the update proposals to be committed.
-        alice.process_incoming_message(bob_update).await.unwrap();
-        alice.process_incoming_message(carol_update).await.unwrap();
-        alice.process_incoming_message(dave_update).await.unwrap();
+        alice.commit(vec![]).await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
+        assert!(alice.last_removed().is_empty());
+    }
 
-        // Alice commits the update proposals.
-        alice.commit(Vec::new()).await.unwrap();
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn adding_key_package_of_other_cipher_suite_is_rejected() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, CipherSuite::CURVE25519_AES128).await;
 
-        let CommitEffect::NewEpoch(new_epoch) = alice.apply_pending_commit().await.unwrap().effect
-        else {
-            panic!("unexpected commit effect");
-        };
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, CipherSuite::P256_AES128, "bob").await;
 
-        let find_update_for = |id: &str| {
-            new_epoch
-                .applied_proposals
-                .iter()
-                .filter_map(|p| match p.proposal {
-                    Proposal::Update(ref u) => u.signing_identity().credential.as_basic(),
-                    _ => None,
-                })
-                .any(|c| c.identifier == id.as_bytes())
-        };
+        let res = group.commit_builder().add_member(key_package).map(|_| ());
 
-        // Carol's and Dave's updates should be part of the commit.
-        assert!(find_update_for("carol"));
-        assert!(find_update_for("dave"));
+        assert_matches!(
+            res,
+            Err(MlsError::KeyPackageCipherSuiteMismatch {
+                expected: CipherSuite::CURVE25519_AES128,
+                found: CipherSuite::P256_AES128,
+            })
+        );
+    }
 
-        // Bob's update should be rejected.
-        assert!(!find_update_for("bob"));
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn has_pending_commit_tracks_commit_lifecycle() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        assert!(!group.has_pending_commit());
 
-        // Check that all members are still in the group.
-        let all_members_are_in = alice
-            .roster()
-            .members_iter()
-            .zip(["alice", "bob", "carol", "dave"])
-            .all(|(member, id)| {
-                member
-                    .signing_identity
-                    .credential
-                    .as_basic()
-                    .unwrap()
-                    .identifier
-                    == id.as_bytes()
-            });
+        group.commit(vec![]).await.unwrap();
+        assert!(group.has_pending_commit());
 
-        assert!(all_members_are_in);
+        group.apply_pending_commit().await.unwrap();
+        assert!(!group.has_pending_commit());
+
+        group.commit(vec![]).await.unwrap();
+        group.clear_pending_commit();
+        assert!(!group.has_pending_commit());
     }
 
-    #[cfg(feature = "custom_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_may_enforce_path() {
-        test_custom_proposal_mls_rules(true).await;
+    async fn ratchet_tree_size_matches_exported_tree() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let initial_size = alice.ratchet_tree_size();
+
+        alice.join("bob").await;
+
+        let exported = alice.export_tree().to_bytes().unwrap();
+
+        assert_eq!(alice.ratchet_tree_size(), exported.len());
+        assert!(alice.ratchet_tree_size() > initial_size);
     }
 
-    #[cfg(feature = "custom_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_need_not_enforce_path() {
-        test_custom_proposal_mls_rules(false).await;
+    async fn confirmation_tag_verification_can_be_disabled() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        alice.commit_modifiers.modify_confirmation_tag = |_| ConfirmationTag::default();
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
+
+        let res = bob.clone().process_message(commit.clone()).await;
+        assert_matches!(res, Err(MlsError::InvalidConfirmationTag));
+
+        bob.config.0.settings.verify_confirmation_tag = false;
+        let epoch = bob.current_epoch();
+
+        bob.process_message(commit).await.unwrap();
+        assert_eq!(bob.current_epoch(), epoch + 1);
     }
 
-    #[cfg(feature = "custom_proposal")]
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn test_custom_proposal_mls_rules(path_required_for_custom: bool) {
-        let mls_rules = CustomMlsRules {
-            path_required_for_custom,
-            external_joiner_can_send_custom: true,
-        };
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn redelivered_commit_reports_epoch_mismatch() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+        let epoch = bob.current_epoch();
 
-        let mut alice = client_with_custom_rules(b"alice", mls_rules.clone())
-            .await
-            .create_group(Default::default(), Default::default())
-            .await
-            .unwrap();
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
+        alice.apply_pending_commit().await.unwrap();
 
-        let alice_pub_before = alice.current_user_leaf_node().unwrap().public_key.clone();
+        bob.process_message(commit.clone()).await.unwrap();
+        let res = bob.process_message(commit).await.map(|_| ());
 
-        let kp = client_with_custom_rules(b"bob", mls_rules)
-            .await
-            .generate_key_package_message(Default::default(), Default::default())
-            .await
-            .unwrap();
+        assert_matches!(
+            res,
+            Err(MlsError::CommitEpochMismatch { current, commit })
+                if current == epoch + 1 && commit == epoch
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_from_removed_member_reports_sender_not_in_group() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let commit = bob.commit(vec![]).await.unwrap().commit_message;
 
         alice
             .commit_builder()
-            .custom_proposal(CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![]))
-            .add_member(kp)
+            .remove_member(bob.current_member_index())
             .unwrap()
             .build()
             .await
@@ -4166,337 +6955,409 @@ mod tests {
 
         alice.apply_pending_commit().await.unwrap();
 
-        let alice_pub_after = &alice.current_user_leaf_node().unwrap().public_key;
+        let res = alice.process_incoming_message(commit).await.map(|_| ());
 
-        if path_required_for_custom {
-            assert_ne!(alice_pub_after, &alice_pub_before);
-        } else {
-            assert_eq!(alice_pub_after, &alice_pub_before);
-        }
+        assert_matches!(res, Err(MlsError::SenderNotInGroup(1)));
     }
 
-    #[cfg(feature = "custom_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_by_value_in_external_join_may_be_allowed() {
-        test_custom_proposal_by_value_in_external_join(true).await
+    async fn negotiated_parameters_match_creation_parameters() {
+        let alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        assert_eq!(
+            alice.negotiated_parameters(),
+            (TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE)
+        );
     }
 
-    #[cfg(feature = "custom_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_by_value_in_external_join_may_not_be_allowed() {
-        test_custom_proposal_by_value_in_external_join(false).await
+    async fn public_state_hash_is_shared_by_members_of_an_epoch() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let alice_hash = alice.public_state_hash().await.unwrap();
+        let bob_hash = bob.public_state_hash().await.unwrap();
+
+        assert_eq!(alice_hash, bob_hash);
+
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
+        alice.apply_pending_commit().await.unwrap();
+
+        let new_alice_hash = alice.public_state_hash().await.unwrap();
+        assert_ne!(new_alice_hash, alice_hash);
+
+        let stale_bob_hash = bob.public_state_hash().await.unwrap();
+        assert_ne!(new_alice_hash, stale_bob_hash);
+
+        bob.process_message(commit).await.unwrap();
+
+        let new_bob_hash = bob.public_state_hash().await.unwrap();
+        assert_eq!(new_alice_hash, new_bob_hash);
     }
 
-    #[cfg(feature = "custom_proposal")]
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn test_custom_proposal_by_value_in_external_join(external_joiner_can_send_custom: bool) {
-        let mls_rules = CustomMlsRules {
-            path_required_for_custom: true,
-            external_joiner_can_send_custom,
-        };
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn disallowed_proposal_type_is_rejected() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
 
-        let mut alice = client_with_custom_rules(b"alice", mls_rules.clone())
-            .await
-            .create_group(Default::default(), Default::default())
+        let (mut bob, _) = alice
+            .join_with_custom_config("bob", false, |c| {
+                c.0.settings.disallowed_proposal_types = vec![ProposalType::RE_INIT]
+            })
             .await
             .unwrap();
 
-        let group_info = alice
-            .group_info_message_allowing_ext_commit(true)
+        let res = bob
+            .commit_builder()
+            .reinit(
+                None,
+                TEST_PROTOCOL_VERSION,
+                TEST_CIPHER_SUITE,
+                Default::default(),
+            )
+            .unwrap()
+            .build()
             .await
-            .unwrap();
+            .map(|_| ());
 
-        let commit = client_with_custom_rules(b"bob", mls_rules)
-            .await
-            .external_commit_builder()
+        assert_matches!(
+            res,
+            Err(MlsError::DisallowedProposalType(t)) if t == ProposalType::RE_INIT
+        );
+
+        let commit = alice
+            .commit_builder()
+            .reinit(
+                None,
+                TEST_PROTOCOL_VERSION,
+                TEST_CIPHER_SUITE,
+                Default::default(),
+            )
             .unwrap()
-            .with_custom_proposal(CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![]))
-            .build(group_info)
-            .await;
+            .build()
+            .await
+            .unwrap();
 
-        if external_joiner_can_send_custom {
-            let commit = commit.unwrap().1;
-            alice.process_incoming_message(commit).await.unwrap();
-        } else {
-            assert_matches!(commit.map(|_| ()), Err(MlsError::MlsRulesError(_)));
-        }
+        let res = bob.process_message(commit.commit_message).await.map(|_| ());
+
+        assert_matches!(
+            res,
+            Err(MlsError::DisallowedProposalType(t)) if t == ProposalType::RE_INIT
+        );
     }
 
-    #[cfg(feature = "custom_proposal")]
+    #[cfg(feature = "psk")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_by_ref_in_external_join() {
-        let mls_rules = CustomMlsRules {
-            path_required_for_custom: true,
-            external_joiner_can_send_custom: true,
-        };
+    async fn same_group_as_tells_reinit_successor_apart() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+        let old_handle = alice.group.clone();
+        let group_id = alice.group_id().to_vec();
 
-        let mut alice = client_with_custom_rules(b"alice", mls_rules.clone())
-            .await
-            .create_group(Default::default(), Default::default())
+        let commit = alice
+            .commit_builder()
+            .reinit(
+                Some(group_id),
+                TEST_PROTOCOL_VERSION,
+                TEST_CIPHER_SUITE,
+                Default::default(),
+            )
+            .unwrap()
+            .build()
             .await
             .unwrap();
 
-        let by_ref = CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![]);
-        let by_ref = alice.propose_custom(by_ref, vec![]).await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
+        bob.process_message(commit.commit_message).await.unwrap();
 
-        let group_info = alice
-            .group_info_message_allowing_ext_commit(true)
-            .await
-            .unwrap();
+        assert_ne!(alice.current_epoch(), old_handle.current_epoch());
+        assert!(alice.same_group_as(&old_handle));
 
-        let (_, commit) = client_with_custom_rules(b"bob", mls_rules)
-            .await
-            .external_commit_builder()
-            .unwrap()
-            .with_received_custom_proposal(by_ref)
-            .build(group_info)
+        let restored = Group::from_snapshot(alice.config.clone(), old_handle.snapshot().unwrap())
             .await
             .unwrap();
 
-        alice.process_incoming_message(commit).await.unwrap();
-    }
+        assert!(alice.same_group_as(&restored));
 
-    #[cfg(feature = "custom_proposal")]
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn client_with_custom_rules(
-        name: &[u8],
-        mls_rules: CustomMlsRules,
-    ) -> Client<impl MlsConfig> {
-        let (signing_identity, signer) = get_test_signing_identity(TEST_CIPHER_SUITE, name).await;
+        let bob_reinit = bob.group.get_reinit_client(None, None).unwrap();
+        let key_package = bob_reinit.generate_key_package().await.unwrap();
 
-        ClientBuilder::new()
-            .crypto_provider(TestCryptoProvider::new())
-            .identity_provider(BasicWithCustomProvider::new(BasicIdentityProvider::new()))
-            .signing_identity(signing_identity, signer, TEST_CIPHER_SUITE)
-            .custom_proposal_type(TEST_CUSTOM_PROPOSAL_TYPE)
-            .mls_rules(mls_rules)
-            .build()
-    }
+        let (successor, _) = alice
+            .group
+            .clone()
+            .complete_reinit(None, None, vec![key_package], Default::default())
+            .await
+            .unwrap();
 
-    #[derive(Debug, Clone)]
-    struct CustomMlsRules {
-        path_required_for_custom: bool,
-        external_joiner_can_send_custom: bool,
+        assert_eq!(successor.group_id(), alice.group_id());
+        assert!(!successor.same_group_as(&alice.group));
+        assert!(!alice.same_group_as(&successor));
     }
 
-    #[cfg(feature = "custom_proposal")]
-    impl ProposalBundle {
-        fn has_test_custom_proposal(&self) -> bool {
-            self.custom_proposal_types()
-                .any(|t| t == TEST_CUSTOM_PROPOSAL_TYPE)
-        }
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn delete_exporter() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        group.export_secret(b"123", b"", 15).await.unwrap();
+
+        group.delete_exporter();
+        let res = group.export_secret(b"123", b"", 15).await;
+        assert_matches!(res, Err(MlsError::ExporterDeleted));
+
+        group.commit(vec![]).await.unwrap();
+        group.apply_pending_commit().await.unwrap();
+        group.export_secret(b"123", b"", 15).await.unwrap();
     }
 
-    #[cfg(feature = "custom_proposal")]
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
-    impl crate::MlsRules for CustomMlsRules {
-        type Error = MlsError;
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn epoch_binding_is_shared_by_members() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (bob, _) = alice.join("bob").await;
 
-        fn commit_options(
-            &self,
-            _: &Roster,
-            _: &GroupContext,
-            proposals: &ProposalBundle,
-        ) -> Result<CommitOptions, MlsError> {
-            Ok(CommitOptions::default().with_path_required(
-                !proposals.has_test_custom_proposal() || self.path_required_for_custom,
-            ))
-        }
+        let alice_binding = alice.derive_epoch_binding(b"app").await.unwrap();
+        let bob_binding = bob.derive_epoch_binding(b"app").await.unwrap();
 
-        fn encryption_options(
-            &self,
-            _: &Roster,
-            _: &GroupContext,
-        ) -> Result<crate::mls_rules::EncryptionOptions, MlsError> {
-            Ok(Default::default())
-        }
+        assert_eq!(alice_binding, bob_binding);
+        assert_eq!(
+            alice_binding.len(),
+            alice.cipher_suite_provider.kdf_extract_size()
+        );
 
-        async fn filter_proposals(
-            &self,
-            _: CommitDirection,
-            sender: CommitSource,
-            _: &Roster,
-            _: &GroupContext,
-            proposals: ProposalBundle,
-        ) -> Result<ProposalBundle, MlsError> {
-            let is_external = matches!(sender, CommitSource::NewMember(_));
-            let has_custom = proposals.has_test_custom_proposal();
-            let allowed = !has_custom || !is_external || self.external_joiner_can_send_custom;
+        alice.commit(vec![]).await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
 
-            allowed.then_some(proposals).ok_or(MlsError::InvalidSender)
-        }
+        let next_binding = alice.derive_epoch_binding(b"app").await.unwrap();
+        assert_ne!(next_binding, alice_binding);
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn group_can_receive_commit_from_self() {
-        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+    async fn epoch_binding_depends_on_label() {
+        let group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
 
-        let commit = group.commit(vec![]).await.unwrap();
+        let binding = group.derive_epoch_binding(b"app").await.unwrap();
+        let other_binding = group.derive_epoch_binding(b"other app").await.unwrap();
 
-        let update = group
-            .process_incoming_message(commit.commit_message)
+        assert_ne!(binding, other_binding);
+
+        let exported = group
+            .export_secret(b"app", b"", binding.len())
             .await
             .unwrap();
 
-        let ReceivedMessage::Commit(update) = update else {
-            panic!("expected commit message")
-        };
-
-        assert_eq!(update.committer, *group.private_tree.self_index);
+        assert_ne!(binding, exported);
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn can_process_commit_when_pending_commit() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+    async fn custom_welcome_hpke_label() {
+        let label = b"custom welcome".to_vec();
 
-        let commit = groups[0].commit(vec![]).await.unwrap().commit_message;
-        groups[1].commit(vec![]).await.unwrap();
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.welcome_hpke_label(label.clone())
+        })
+        .await;
 
-        groups[1].process_incoming_message(commit).await.unwrap();
+        let res = alice
+            .join_with_custom_config("bob", false, |c| {
+                c.0.settings.welcome_hpke_label = label.clone()
+            })
+            .await;
 
-        let res = groups[1].apply_pending_commit().await;
-        assert_matches!(res, Err(MlsError::PendingCommitNotFound));
-    }
+        assert!(res.is_ok());
 
-    #[cfg(feature = "by_ref_proposal")]
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn can_process_own_plaintext_proposal() {
-        can_process_own_proposal(false).await;
+        let res = alice
+            .join_with_custom_config("carol", false, |_| {})
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::CryptoProviderError(_)));
     }
 
     #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn can_process_own_ciphertext_proposal() {
-        can_process_own_proposal(true).await;
-    }
+    async fn custom_proposal_ref_label() {
+        let label = b"custom proposal reference".to_vec();
 
-    #[cfg(feature = "by_ref_proposal")]
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn can_process_own_proposal(encrypt_proposal: bool) {
-        let (alice, _) = test_client_with_key_pkg_custom(
-            TEST_PROTOCOL_VERSION,
-            TEST_CIPHER_SUITE,
-            "alice",
-            Default::default(),
-            Default::default(),
-            |c| c.0.mls_rules.encryption_options.encrypt_control_messages = encrypt_proposal,
-        )
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.proposal_ref_label(label.clone())
+        })
         .await;
 
-        let mut alice = TestGroup {
-            group: alice
-                .create_group(Default::default(), Default::default())
-                .await
-                .unwrap(),
+        let (mut bob, _) = alice
+            .join_with_custom_config("bob", true, |c| {
+                c.0.settings.proposal_ref_label = label.clone()
+            })
+            .await
+            .unwrap();
+
+        let proposal = bob.propose_update(vec![]).await.unwrap();
+
+        let ReceivedMessage::Proposal(proposal_desc) = alice
+            .process_incoming_message(proposal.clone())
+            .await
+            .unwrap()
+        else {
+            panic!("expected proposal message")
         };
 
-        let mut bob = alice.join("bob").await.0;
-        let mut alice = alice;
+        let auth_content = proposal.clone().into_plaintext().unwrap().into();
 
-        let upd = alice.propose_update(vec![]).await.unwrap();
-        alice.process_incoming_message(upd.clone()).await.unwrap();
+        let expected_ref = ProposalRef::from_content_with_label(
+            &alice.cipher_suite_provider,
+            &auth_content,
+            &label,
+        )
+        .await
+        .unwrap();
 
-        bob.process_incoming_message(upd).await.unwrap();
-        let commit = bob.commit(vec![]).await.unwrap().commit_message;
-        let update = alice.process_incoming_message(commit).await.unwrap();
+        assert_eq!(proposal_desc.proposal_ref, expected_ref);
 
-        let ReceivedMessage::Commit(CommitMessageDescription {
-            effect: CommitEffect::NewEpoch(new_epoch),
-            ..
-        }) = update
+        let default_ref = proposal
+            .into_proposal_reference(&alice.cipher_suite_provider)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_ne!(proposal_desc.proposal_ref.to_vec(), default_ref);
+
+        // Bob finds his own proposal by reference when processing Alice's commit
+        let commit = alice.commit(vec![]).await.unwrap();
+
+        let ReceivedMessage::Commit(commit_desc) = bob
+            .process_incoming_message(commit.commit_message)
+            .await
+            .unwrap()
         else {
-            panic!("unexpected commit effect")
+            panic!("expected commit message")
+        };
+
+        let CommitEffect::NewEpoch(new_epoch) = commit_desc.effect else {
+            panic!("expected new epoch")
         };
 
         assert_eq!(new_epoch.applied_proposals.len(), 1);
-        assert_eq!(new_epoch.applied_proposals[0].sender, Sender::Member(0));
     }
 
-    #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_clears_proposals() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+    async fn members_iterator_matches_roster() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 4).await;
 
-        groups[0].propose_update(vec![]).await.unwrap();
+        // Create a blank leaf in the middle of the tree
+        let commit = groups[0]
+            .commit_builder()
+            .remove_member(2)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
 
-        assert_eq!(groups[0].state.proposals.proposals.len(), 1);
-        assert_eq!(groups[0].state.proposals.own_proposals.len(), 1);
+        groups[0].apply_pending_commit().await.unwrap();
+        groups[1]
+            .process_message(commit.commit_message)
+            .await
+            .unwrap();
 
-        let commit = groups[1].commit(vec![]).await.unwrap().commit_message;
-        groups[0].process_message(commit).await.unwrap();
+        let group = &groups[1];
 
-        assert!(groups[0].state.proposals.proposals.is_empty());
-        assert!(groups[0].state.proposals.own_proposals.is_empty());
+        assert_eq!(group.members().count(), group.roster().members().len());
+        assert_eq!(group.member_count(), 3);
+        assert_eq!(
+            group.members().collect::<Vec<_>>(),
+            group.roster().members()
+        );
     }
 
-    #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_required_is_true_when_proposals_pending() {
-        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+    async fn blank_leaf_count_reflects_removals() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 5).await;
 
-        assert!(!group.commit_required());
+        assert_eq!(groups[0].blank_leaf_count(), 0);
+        assert_eq!(groups[0].tree_utilization(), 1.0);
 
-        group
-            .propose_group_context_extensions(ExtensionList::new(), vec![])
+        groups[0]
+            .commit_builder()
+            .remove_member(1)
+            .unwrap()
+            .remove_member(2)
+            .unwrap()
+            .build()
             .await
             .unwrap();
 
-        assert!(group.commit_required());
+        groups[0].apply_pending_commit().await.unwrap();
 
-        let res = group.encrypt_application_message(&[0u8; 32], vec![]).await;
+        assert_eq!(groups[0].blank_leaf_count(), 2);
+        assert_eq!(groups[0].tree_utilization(), 0.6);
 
-        assert_matches!(res, Err(MlsError::CommitRequired));
+        // Blank leaves at the right edge of the tree are truncated
+        groups[0]
+            .commit_builder()
+            .remove_member(4)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
 
-        group.commit(vec![]).await.unwrap();
-        group.apply_pending_commit().await.unwrap();
+        groups[0].apply_pending_commit().await.unwrap();
 
-        assert!(!group.commit_required());
+        assert_eq!(groups[0].blank_leaf_count(), 2);
+        assert_eq!(groups[0].tree_utilization(), 0.5);
     }
 
-    // Testing with std is sufficient. Non-std creates incompatible storage and a lot of special cases.
-    #[cfg(feature = "std")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn can_be_stored_without_tree() {
-        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
-        let storage = group.config.group_state_storage().inner;
-
-        group.write_to_storage().await.unwrap();
-        let snapshot_with_tree = storage.lock().unwrap().drain().next().unwrap().1;
-
-        group.write_to_storage_without_ratchet_tree().await.unwrap();
-        let snapshot_without_tree = storage.lock().unwrap().iter().next().unwrap().1.clone();
+    async fn leaf_node_extension_validator_rejects_invalid_extension() {
+        use crate::extension::test_utils::{TestExtension, TEST_EXTENSION_TYPE};
 
-        let tree = group.state.public_tree.nodes.mls_encode_to_vec().unwrap();
-        let empty_tree = Vec::<u8>::new().mls_encode_to_vec().unwrap();
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.leaf_node_extension_validator(|ext: &TestExtension| {
+                (ext.foo == 1)
+                    .then_some(())
+                    .ok_or_else(|| LeafNodeValidationError::new(MlsError::InvalidSignature))
+            })
+        })
+        .await;
 
-        assert_eq!(
-            snapshot_with_tree.state_data.len() - snapshot_without_tree.state_data.len(),
-            tree.len() - empty_tree.len()
-        );
+        let key_package_with = |foo: u8, name: &'static str| {
+            crate::client::test_utils::test_client_with_key_pkg_custom(
+                TEST_PROTOCOL_VERSION,
+                TEST_CIPHER_SUITE,
+                name,
+                Default::default(),
+                vec![TestExtension { foo }.into_extension().unwrap()].into(),
+                |c| {
+                    c.0.settings
+                        .extension_types
+                        .push(TEST_EXTENSION_TYPE.into())
+                },
+            )
+        };
 
-        let exported_tree = group.export_tree();
+        let (_, bad_key_package) = key_package_with(0, "bob").await;
 
-        let restored = Client::new(group.config.clone(), None, None, TEST_PROTOCOL_VERSION)
-            .load_group_with_ratchet_tree(group.group_id(), exported_tree)
+        let res = alice
+            .process_incoming_message(bad_key_package.clone())
             .await
-            .unwrap();
+            .map(|_| ());
 
-        assert_eq!(restored.group_state(), group.group_state());
-    }
+        assert_matches!(res, Err(MlsError::InvalidLeafNodeExtension { extension_type, .. })
+            if extension_type == TEST_EXTENSION_TYPE.into());
 
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn delete_exporter() {
-        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let res = alice
+            .commit_builder()
+            .add_member(bad_key_package)
+            .unwrap()
+            .build()
+            .await
+            .map(|_| ());
 
-        group.export_secret(b"123", b"", 15).await.unwrap();
+        assert_matches!(res, Err(MlsError::InvalidLeafNodeExtension { .. }));
 
-        group.delete_exporter();
-        let res = group.export_secret(b"123", b"", 15).await;
-        assert_matches!(res, Err(MlsError::ExporterDeleted));
+        let (_, good_key_package) = key_package_with(1, "carol").await;
 
-        group.commit(vec![]).await.unwrap();
-        group.apply_pending_commit().await.unwrap();
-        group.export_secret(b"123", b"", 15).await.unwrap();
+        alice
+            .commit_builder()
+            .add_member(good_key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
     }
 }
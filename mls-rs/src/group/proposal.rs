@@ -582,3 +582,23 @@ impl From<ProposalRef> for ProposalOrRef {
         Self::Reference(r)
     }
 }
+
+impl ProposalOrRef {
+    pub(crate) fn as_view(&self) -> ProposalOrRefView<'_> {
+        match self {
+            Self::Proposal(p) => ProposalOrRefView::Proposal(p),
+            #[cfg(feature = "by_ref_proposal")]
+            Self::Reference(r) => ProposalOrRefView::Reference(r),
+        }
+    }
+}
+
+/// Read-only view of an entry in the list of proposals of a commit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProposalOrRefView<'a> {
+    /// A proposal committed by value.
+    Proposal(&'a Proposal),
+    /// A reference to a proposal sent separately and committed by reference.
+    #[cfg(feature = "by_ref_proposal")]
+    Reference(&'a ProposalRef),
+}
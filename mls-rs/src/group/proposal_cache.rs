@@ -7,8 +7,8 @@ use alloc::vec::Vec;
 use super::{
     message_processor::ProvisionalState,
     mls_rules::{CommitDirection, CommitSource, MlsRules},
-    proposal_filter::prepare_proposals_for_mls_rules,
-    GroupState, ProposalOrRef,
+    proposal_filter::{filter_out_disallowed_proposals, prepare_proposals_for_mls_rules},
+    GroupState, ProposalOrRef, ProposalType,
 };
 use crate::{
     client::MlsError,
@@ -25,7 +25,7 @@ use crate::{
     MlsMessage,
 };
 
-use crate::tree_kem::leaf_node::LeafNode;
+use crate::tree_kem::{leaf_node::LeafNode, leaf_node_validator::LeafNodeExtensionValidators};
 
 #[cfg(feature = "by_ref_proposal")]
 use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
@@ -44,6 +44,10 @@ use core::fmt::{self, Debug};
 pub struct CachedProposal {
     pub(crate) proposal: Proposal,
     pub(crate) sender: Sender,
+    // Not encoded in snapshots of version 2 and earlier
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    #[cfg_attr(feature = "serde", serde(default, with = "mls_rs_core::vec_serde"))]
+    pub(crate) authenticated_data: Vec<u8>,
 }
 
 #[cfg(feature = "by_ref_proposal")]
@@ -114,8 +118,18 @@ impl ProposalCache {
         self.proposals.is_empty()
     }
 
-    pub fn insert(&mut self, proposal_ref: ProposalRef, proposal: Proposal, sender: Sender) {
-        let cached_proposal = CachedProposal { proposal, sender };
+    pub fn insert(
+        &mut self,
+        proposal_ref: ProposalRef,
+        proposal: Proposal,
+        sender: Sender,
+        authenticated_data: Vec<u8>,
+    ) {
+        let cached_proposal = CachedProposal {
+            proposal,
+            sender,
+            authenticated_data,
+        };
 
         #[cfg(feature = "std")]
         self.proposals.insert(proposal_ref, cached_proposal);
@@ -137,6 +151,7 @@ impl ProposalCache {
             proposal.proposal_ref.clone(),
             proposal.proposal.clone(),
             sender,
+            proposal.authenticated_data.clone(),
         );
 
         let message_hash = MessageHash::compute(cs, message).await?;
@@ -178,19 +193,7 @@ impl ProposalCache {
             match p {
                 ProposalOrRef::Proposal(p) => proposals.add(*p, sender, ProposalSource::ByValue),
                 ProposalOrRef::Reference(r) => {
-                    #[cfg(feature = "std")]
-                    let p = self
-                        .proposals
-                        .get(&r)
-                        .ok_or(MlsError::ProposalNotFound)?
-                        .clone();
-                    #[cfg(not(feature = "std"))]
-                    let p = self
-                        .proposals
-                        .iter()
-                        .find_map(|(rr, p)| (rr == &r).then_some(p))
-                        .ok_or(MlsError::ProposalNotFound)?
-                        .clone();
+                    let p = self.get(&r).ok_or(MlsError::ProposalNotFound)?.clone();
 
                     proposals.add(p.proposal, p.sender, ProposalSource::ByReference(r));
                 }
@@ -200,6 +203,16 @@ impl ProposalCache {
         Ok(proposals)
     }
 
+    pub fn get(&self, proposal_ref: &ProposalRef) -> Option<&CachedProposal> {
+        #[cfg(feature = "std")]
+        return self.proposals.get(proposal_ref);
+
+        #[cfg(not(feature = "std"))]
+        self.proposals
+            .iter()
+            .find_map(|(r, p)| (r == proposal_ref).then_some(p))
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn get_own<CS: CipherSuiteProvider>(
         &self,
@@ -254,8 +267,12 @@ impl GroupState {
         cipher_suite_provider: &CSP,
         psk_storage: &P,
         user_rules: &F,
+        disallowed_proposal_types: &[ProposalType],
+        #[cfg(feature = "psk")] max_psks_per_commit: Option<usize>,
+        leaf_node_extension_validators: &LeafNodeExtensionValidators,
         commit_time: Option<MlsTime>,
         direction: CommitDirection,
+        report_rejected_proposals: bool,
     ) -> Result<ProvisionalState, MlsError>
     where
         C: IdentityProvider,
@@ -285,11 +302,37 @@ impl GroupState {
 
         prepare_proposals_for_mls_rules(&mut proposals, direction, &self.public_tree)?;
 
+        // When reporting, the proposals committed by the sender are applied as committed and
+        // the ones the local rules would filter out are only reported.
+        let committed = (report_rejected_proposals && direction == CommitDirection::Receive)
+            .then(|| proposals.clone());
+
         proposals = user_rules
             .filter_proposals(direction, origin, &roster, &self.context, proposals)
             .await
             .map_err(|e| MlsError::MlsRulesError(e.into_any_error()))?;
 
+        let rejected_proposals = match committed {
+            Some(committed) => {
+                let rejected = rejected_proposals(&committed, &proposals);
+                proposals = committed;
+                rejected
+            }
+            None => Vec::new(),
+        };
+
+        filter_out_disallowed_proposals(&mut proposals, direction, disallowed_proposal_types)?;
+
+        // Bound the work done resolving PSKs before any of them is looked up
+        #[cfg(feature = "psk")]
+        if let Some(max_psks) = max_psks_per_commit {
+            let psk_count = proposals.psk_proposals().len();
+
+            if psk_count > max_psks {
+                return Err(MlsError::TooManyPsks(psk_count));
+            }
+        }
+
         let applier = ProposalApplier::new(
             &self.public_tree,
             cipher_suite_provider,
@@ -297,6 +340,8 @@ impl GroupState {
             external_leaf,
             identity_provider,
             psk_storage,
+            leaf_node_extension_validators,
+            direction,
         );
 
         #[cfg(feature = "by_ref_proposal")]
@@ -338,6 +383,7 @@ impl GroupState {
             external_init_index: applier_output.external_init_index,
             indexes_of_added_kpkgs: applier_output.indexes_of_added_kpkgs,
             unused_proposals,
+            rejected_proposals,
         })
     }
 }
@@ -352,6 +398,19 @@ impl Extend<(ProposalRef, CachedProposal)> for ProposalCache {
     }
 }
 
+fn rejected_proposals(
+    committed: &ProposalBundle,
+    accepted: &ProposalBundle,
+) -> Vec<crate::mls_rules::ProposalInfo<Proposal>> {
+    let accepted = accepted.clone().into_proposals().collect::<Vec<_>>();
+
+    committed
+        .clone()
+        .into_proposals()
+        .filter(|p| !accepted.contains(p))
+        .collect()
+}
+
 #[cfg(feature = "by_ref_proposal")]
 fn has_ref(proposals: &ProposalBundle, reference: &ProposalRef) -> bool {
     proposals
@@ -402,7 +461,11 @@ pub(crate) mod test_utils {
 
     impl CachedProposal {
         pub fn new(proposal: Proposal, sender: Sender) -> Self {
-            Self { proposal, sender }
+            Self {
+                proposal,
+                sender,
+                authenticated_data: Vec::new(),
+            }
         }
     }
 
@@ -516,7 +579,7 @@ pub(crate) mod test_utils {
         where
             S: Into<Sender>,
         {
-            self.cache.insert(r, p, proposer.into());
+            self.cache.insert(r, p, proposer.into(), Vec::new());
             self
         }
 
@@ -595,8 +658,13 @@ pub(crate) mod test_utils {
                     cipher_suite_provider,
                     psk_storage,
                     &user_rules,
+                    &[],
+                    #[cfg(feature = "psk")]
+                    None,
+                    &Default::default(),
                     None,
                     CommitDirection::Receive,
+                    false,
                 )
                 .await
         }
@@ -639,8 +707,13 @@ pub(crate) mod test_utils {
                     cipher_suite_provider,
                     psk_storage,
                     &user_rules,
+                    &[],
+                    #[cfg(feature = "psk")]
+                    None,
+                    &Default::default(),
                     None,
                     CommitDirection::Send,
+                    false,
                 )
                 .await
         }
@@ -887,6 +960,7 @@ mod tests {
             external_init_index: None,
             indexes_of_added_kpkgs: vec![LeafIndex(1)],
             unused_proposals: vec![],
+            rejected_proposals: vec![],
             applied_proposals: bundle,
         };
 
@@ -1124,7 +1198,12 @@ mod tests {
         let update_proposal_ref = make_proposal_ref(&update, LeafIndex(1)).await;
         let mut cache = test_proposal_cache_setup(test_proposals).await;
 
-        cache.insert(update_proposal_ref.clone(), update, Sender::Member(1));
+        cache.insert(
+            update_proposal_ref.clone(),
+            update,
+            Sender::Member(1),
+            Vec::new(),
+        );
 
         let provisional_state = cache
             .prepare_commit_default(
@@ -1242,7 +1321,12 @@ mod tests {
 
         let proposer = test_sender();
         let test_proposal_ref = make_proposal_ref(&test_proposal, LeafIndex(proposer)).await;
-        cache.insert(test_proposal_ref, test_proposal, Sender::Member(proposer));
+        cache.insert(
+            test_proposal_ref,
+            test_proposal,
+            Sender::Member(proposer),
+            Vec::new(),
+        );
 
         assert!(!cache.is_empty())
     }
@@ -1398,6 +1482,7 @@ mod tests {
             proposal_ref.clone(),
             proposal,
             Sender::Member(test_sender()),
+            Vec::new(),
         );
 
         let group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
@@ -1763,6 +1848,7 @@ mod tests {
             make_proposal_ref(&update, LeafIndex(2)).await,
             update,
             Sender::Member(2),
+            Vec::new(),
         );
 
         let mut tree = TreeKemPublic::new();
@@ -1962,7 +2048,7 @@ mod tests {
         where
             S: Into<Sender>,
         {
-            self.cache.insert(r, p, proposer.into());
+            self.cache.insert(r, p, proposer.into(), Vec::new());
             self
         }
 
@@ -3381,7 +3467,8 @@ mod tests {
 
         assert_matches!(
             res,
-            Err(MlsError::RequiredExtensionNotFound(v)) if v == 33.into()
+            Err(MlsError::MemberMissingRequiredCapability { leaf_index: 0, missing })
+                if matches!(*missing, MlsError::RequiredExtensionNotFound(v) if v == 33.into())
         );
     }
 
@@ -3396,7 +3483,37 @@ mod tests {
 
         assert_matches!(
             res,
-            Err(MlsError::RequiredExtensionNotFound(v)) if v == 33.into()
+            Err(MlsError::MemberMissingRequiredCapability { leaf_index: 0, missing })
+                if matches!(*missing, MlsError::RequiredExtensionNotFound(v) if v == 33.into())
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn required_capabilities_not_supported_by_other_member_names_member() {
+        let mut capabilities = get_test_capabilities();
+        capabilities.extensions.push(33.into());
+
+        let (leaf, secret, _) =
+            get_basic_test_node_capabilities(TEST_CIPHER_SUITE, "alice", capabilities).await;
+
+        let (mut tree, alice) =
+            TreeKemPublic::derive(leaf, secret, &BasicIdentityProvider, &Default::default())
+                .await
+                .map(|(tree, private)| (tree, private.self_index))
+                .unwrap();
+
+        let bob = add_member(&mut tree, "bob").await;
+
+        let res = CommitSender::new(&tree, alice, test_cipher_suite_provider(TEST_CIPHER_SUITE))
+            .with_additional([required_capabilities_proposal(33)])
+            .send()
+            .await;
+
+        assert_matches!(
+            res,
+            Err(MlsError::MemberMissingRequiredCapability { leaf_index, missing })
+                if leaf_index == *bob
+                    && matches!(*missing, MlsError::RequiredExtensionNotFound(v) if v == 33.into())
         );
     }
 
@@ -3749,9 +3866,10 @@ mod tests {
 
         assert_matches!(
             res,
-            Err(
-                MlsError::UnsupportedGroupExtension(v)
-            ) if v == 42.into()
+            Err(MlsError::GroupExtensionNotSupportedByMember {
+                extension_type,
+                leaf_index: 0,
+            }) if extension_type == 42.into()
         );
     }
 
@@ -3766,9 +3884,10 @@ mod tests {
 
         assert_matches!(
             res,
-            Err(
-                MlsError::UnsupportedGroupExtension(v)
-            ) if v == 42.into()
+            Err(MlsError::GroupExtensionNotSupportedByMember {
+                extension_type,
+                leaf_index: 0,
+            }) if extension_type == 42.into()
         );
     }
 
@@ -4095,6 +4214,128 @@ mod tests {
         assert_matches!(res, Err(MlsError::MlsRulesError(_)));
     }
 
+    #[derive(Debug)]
+    #[cfg_attr(feature = "std", derive(thiserror::Error))]
+    #[cfg_attr(feature = "std", error("group would be too small"))]
+    struct GroupTooSmallError;
+
+    impl mls_rs_core::error::IntoAnyError for GroupTooSmallError {
+        #[cfg(feature = "std")]
+        fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+            Ok(self.into())
+        }
+    }
+
+    struct MinimumSizeMlsRules;
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl MlsRules for MinimumSizeMlsRules {
+        type Error = GroupTooSmallError;
+
+        async fn filter_proposals(
+            &self,
+            _: CommitDirection,
+            _: CommitSource,
+            roster: &Roster,
+            _: &GroupContext,
+            proposals: ProposalBundle,
+        ) -> Result<ProposalBundle, Self::Error> {
+            if proposals.effects_preview(roster).member_count < 2 {
+                return Err(GroupTooSmallError);
+            }
+
+            Ok(proposals)
+        }
+
+        #[cfg_attr(coverage_nightly, coverage(off))]
+        fn commit_options(
+            &self,
+            _: &Roster,
+            _: &GroupContext,
+            _: &ProposalBundle,
+        ) -> Result<CommitOptions, Self::Error> {
+            Ok(Default::default())
+        }
+
+        #[cfg_attr(coverage_nightly, coverage(off))]
+        fn encryption_options(
+            &self,
+            _: &Roster,
+            _: &GroupContext,
+        ) -> Result<EncryptionOptions, Self::Error> {
+            Ok(Default::default())
+        }
+    }
+
+    fn remove_proposals(indices: &[u32]) -> Vec<Proposal> {
+        indices
+            .iter()
+            .map(|i| {
+                Proposal::Remove(RemoveProposal {
+                    to_remove: LeafIndex(*i),
+                })
+            })
+            .collect()
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn user_defined_filter_can_use_effects_preview() {
+        let (alice, mut tree) = new_tree("alice").await;
+        add_member(&mut tree, "bob").await;
+        add_member(&mut tree, "carol").await;
+
+        let res = CommitSender::new(&tree, alice, test_cipher_suite_provider(TEST_CIPHER_SUITE))
+            .with_additional(remove_proposals(&[1]))
+            .with_user_rules(MinimumSizeMlsRules)
+            .send()
+            .await;
+
+        assert!(res.is_ok());
+
+        let res = CommitSender::new(&tree, alice, test_cipher_suite_provider(TEST_CIPHER_SUITE))
+            .with_additional(remove_proposals(&[1, 2]))
+            .with_user_rules(MinimumSizeMlsRules)
+            .send()
+            .await;
+
+        #[cfg(feature = "std")]
+        assert_matches!(res, Err(MlsError::MlsRulesError(e)) if e.to_string() == GroupTooSmallError.to_string());
+
+        #[cfg(not(feature = "std"))]
+        assert_matches!(res, Err(MlsError::MlsRulesError(_)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn effects_preview_reuses_removed_leaves() {
+        let (alice, mut tree) = new_tree("alice").await;
+        add_member(&mut tree, "bob").await;
+        add_member(&mut tree, "carol").await;
+
+        let mut bundle = ProposalBundle::default();
+
+        for p in remove_proposals(&[1]) {
+            bundle.add(p, Sender::Member(*alice), ProposalSource::ByValue);
+        }
+
+        for _ in 0..2 {
+            let add = Proposal::Add(Box::new(AddProposal {
+                key_package: test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "dave")
+                    .await,
+            }));
+
+            bundle.add(add, Sender::Member(*alice), ProposalSource::ByValue);
+        }
+
+        let preview = bundle.effects_preview(&tree.roster());
+
+        assert_eq!(preview.removed_leaf_indices, vec![1]);
+        assert_eq!(preview.added_leaf_indices, vec![1, 3]);
+        assert_eq!(preview.member_count, 4);
+        assert!(preview.removes(1));
+        assert!(!preview.removes(2));
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn user_defined_filter_can_reject_incoming_commit() {
         let (alice, tree) = new_tree("alice").await;
@@ -12,9 +12,11 @@ pub mod filtering_lite;
 #[cfg(all(feature = "custom_proposal", not(feature = "by_ref_proposal")))]
 use filtering_lite as filtering;
 
-pub use bundle::{ProposalBundle, ProposalInfo, ProposalSource};
+pub use bundle::{EffectsPreview, ProposalBundle, ProposalInfo, ProposalSource};
 
-pub(crate) use filtering_common::{prepare_proposals_for_mls_rules, ProposalApplier};
+pub(crate) use filtering_common::{
+    filter_out_disallowed_proposals, prepare_proposals_for_mls_rules, ProposalApplier,
+};
 
 #[cfg(all(feature = "by_ref_proposal", test))]
 pub(crate) use filtering::proposer_can_propose;
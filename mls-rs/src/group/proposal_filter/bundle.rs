@@ -18,7 +18,9 @@ use crate::{
 };
 
 #[cfg(feature = "by_ref_proposal")]
-use crate::group::{proposal_cache::CachedProposal, LeafIndex, ProposalRef, UpdateProposal};
+use crate::group::{proposal_cache::CachedProposal, ProposalRef, UpdateProposal};
+
+use crate::group::{LeafIndex, Roster};
 
 #[cfg(feature = "psk")]
 use crate::group::PreSharedKeyProposal;
@@ -49,6 +51,28 @@ pub struct ProposalBundle {
     pub(crate) custom_proposals: Vec<ProposalInfo<CustomProposal>>,
 }
 
+/// Projected effect of a [`ProposalBundle`] on the group membership.
+///
+/// See [`ProposalBundle::effects_preview`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EffectsPreview {
+    /// Leaf indices that members added by the bundle would occupy, in the
+    /// order of the add proposals.
+    pub added_leaf_indices: Vec<u32>,
+    /// Leaf indices of members removed by the bundle.
+    pub removed_leaf_indices: Vec<u32>,
+    /// Number of members in the group after applying the bundle.
+    pub member_count: u32,
+}
+
+impl EffectsPreview {
+    /// Returns true if the member at `leaf_index` would be removed.
+    pub fn removes(&self, leaf_index: u32) -> bool {
+        self.removed_leaf_indices.contains(&leaf_index)
+    }
+}
+
 impl ProposalBundle {
     pub fn add(&mut self, proposal: Proposal, sender: Sender, source: ProposalSource) {
         match proposal {
@@ -379,6 +403,52 @@ impl ProposalBundle {
         &self.custom_proposals
     }
 
+    /// Preview the effect of applying the proposals in this bundle to the group
+    /// described by `roster`.
+    ///
+    /// Leaves freed by remove proposals are reused by add proposals following the
+    /// same placement rules that are used when the commit is applied. Proposals are
+    /// not validated, so the preview of an invalid bundle may not match the result
+    /// of the commit.
+    pub fn effects_preview(&self, roster: &Roster) -> EffectsPreview {
+        let tree = roster.public_tree;
+
+        let mut removed_leaf_indices = Vec::new();
+
+        for removal in &self.removals {
+            let index = removal.proposal.to_remove;
+
+            if tree.get_leaf_node(index).is_ok() && !removed_leaf_indices.contains(&*index) {
+                removed_leaf_indices.push(*index);
+            }
+        }
+
+        let is_free = |index: u32| {
+            tree.get_leaf_node(LeafIndex(index)).is_err() || removed_leaf_indices.contains(&index)
+        };
+
+        let mut added_leaf_indices = Vec::new();
+        let mut next_index = 0;
+
+        for _ in &self.additions {
+            while !is_free(next_index) {
+                next_index += 1;
+            }
+
+            added_leaf_indices.push(next_index);
+            next_index += 1;
+        }
+
+        let member_count = tree.occupied_leaf_count() - removed_leaf_indices.len() as u32
+            + added_leaf_indices.len() as u32;
+
+        EffectsPreview {
+            added_leaf_indices,
+            removed_leaf_indices,
+            member_count,
+        }
+    }
+
     pub(crate) fn group_context_extensions_proposal(&self) -> Option<&ProposalInfo<ExtensionList>> {
         self.group_context_extensions.first()
     }
@@ -188,7 +188,8 @@ where
             self.cipher_suite_provider,
             self.identity_provider,
             member_validation_context,
-        );
+        )
+        .with_extension_validators(self.leaf_node_extension_validators);
 
         let bad_indices: Vec<_> = wrap_iter(proposals.update_proposals())
             .zip(wrap_iter(proposals.update_proposal_senders()))
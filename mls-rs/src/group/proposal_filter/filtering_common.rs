@@ -9,7 +9,7 @@ use crate::{
     mls_rules::CommitDirection,
     time::MlsTime,
     tree_kem::{
-        leaf_node_validator::{LeafNodeValidator, ValidationContext},
+        leaf_node_validator::{LeafNodeExtensionValidators, LeafNodeValidator, ValidationContext},
         node::LeafIndex,
         TreeKemPublic,
     },
@@ -27,6 +27,7 @@ use crate::extension::ExternalSendersExt;
 
 use mls_rs_core::{error::IntoAnyError, identity::MemberValidationContext};
 
+use alloc::boxed::Box;
 use alloc::vec::Vec;
 use mls_rs_core::{identity::IdentityProvider, psk::PreSharedKeyStorage};
 
@@ -55,6 +56,8 @@ pub(crate) struct ProposalApplier<'a, C, P, CSP> {
     pub external_leaf: Option<&'a LeafNode>,
     pub identity_provider: &'a C,
     pub psk_storage: &'a P,
+    pub leaf_node_extension_validators: &'a LeafNodeExtensionValidators,
+    pub direction: CommitDirection,
 }
 
 #[derive(Debug)]
@@ -81,6 +84,8 @@ where
         external_leaf: Option<&'a LeafNode>,
         identity_provider: &'a C,
         psk_storage: &'a P,
+        leaf_node_extension_validators: &'a LeafNodeExtensionValidators,
+        direction: CommitDirection,
     ) -> Self {
         Self {
             original_tree,
@@ -89,6 +94,8 @@ where
             external_leaf,
             identity_provider,
             psk_storage,
+            leaf_node_extension_validators,
+            direction,
         }
     }
 
@@ -243,8 +250,13 @@ where
             output
                 .new_tree
                 .non_empty_leaves()
-                .try_for_each(|(_, leaf)| {
-                    leaf_validator.validate_required_capabilities(leaf)?;
+                .try_for_each(|(leaf_index, leaf)| {
+                    leaf_validator
+                        .validate_required_capabilities(leaf)
+                        .map_err(|e| MlsError::MemberMissingRequiredCapability {
+                            leaf_index: *leaf_index,
+                            missing: Box::new(e),
+                        })?;
 
                     #[cfg(feature = "by_ref_proposal")]
                     leaf_validator.validate_external_senders_ext_credentials(leaf)?;
@@ -255,18 +267,60 @@ where
             Ok(())
         };
 
+        // Every member must advertise support for each non-default extension of the new
+        // group context in its capabilities.
         let new_extensions_supported = group_context_extensions_proposal
             .proposal
             .iter()
             .map(|extension| extension.extension_type)
             .filter(|&ext_type| !ext_type.is_default())
-            .find(|ext_type| {
-                !output
+            .find_map(|ext_type| {
+                output
                     .new_tree
                     .non_empty_leaves()
-                    .all(|(_, leaf)| leaf.capabilities.extensions.contains(ext_type))
+                    .find(|(_, leaf)| !leaf.capabilities.extensions.contains(&ext_type))
+                    .map(|(leaf_index, _)| (ext_type, *leaf_index))
             })
-            .map_or(Ok(()), |ext| Err(MlsError::UnsupportedGroupExtension(ext)));
+            .map_or(Ok(()), |(extension_type, leaf_index)| {
+                Err(MlsError::GroupExtensionNotSupportedByMember {
+                    extension_type,
+                    leaf_index,
+                })
+            });
+
+        // Members rely on a group context extension if their leaf node contains an extension
+        // declared by the application as depending on it. RFC 9420 does not forbid removing such
+        // an extension, so this is only enforced when creating a commit.
+        let removed_extensions_unused = self
+            .original_context
+            .extensions
+            .iter()
+            .map(|extension| extension.extension_type)
+            .filter(|&ext_type| {
+                self.direction == CommitDirection::Send
+                    && !group_context_extensions_proposal
+                        .proposal
+                        .has_extension(ext_type)
+            })
+            .find_map(|ext_type| {
+                self.leaf_node_extension_validators
+                    .dependent_leaf_extensions(ext_type)
+                    .find_map(|leaf_ext_type| {
+                        output
+                            .new_tree
+                            .non_empty_leaves()
+                            .find(|(_, leaf)| leaf.extensions.has_extension(leaf_ext_type))
+                    })
+                    .map(|(leaf_index, _)| (ext_type, *leaf_index))
+            })
+            .map_or(Ok(()), |(extension_type, leaf_index)| {
+                Err(MlsError::RemovedGroupExtensionInUse {
+                    extension_type,
+                    leaf_index,
+                })
+            });
+
+        let new_extensions_supported = new_extensions_supported.and(removed_extensions_unused);
 
         #[cfg(not(feature = "by_ref_proposal"))]
         {
@@ -370,6 +424,43 @@ pub(crate) fn prepare_proposals_for_mls_rules(
     Ok(())
 }
 
+pub(crate) fn filter_out_disallowed_proposals(
+    proposals: &mut ProposalBundle,
+    direction: CommitDirection,
+    disallowed_types: &[ProposalType],
+) -> Result<(), MlsError> {
+    if disallowed_types.is_empty() {
+        return Ok(());
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    let strategy = FilterStrategy::from(direction);
+
+    #[cfg(not(feature = "by_ref_proposal"))]
+    let _ = direction;
+
+    let keep = |proposal_type: ProposalType, _by_ref: bool| {
+        let res = (!disallowed_types.contains(&proposal_type))
+            .then_some(())
+            .ok_or(MlsError::DisallowedProposalType(proposal_type));
+
+        #[cfg(feature = "by_ref_proposal")]
+        let keep = apply_strategy(strategy, _by_ref, res);
+
+        #[cfg(not(feature = "by_ref_proposal"))]
+        let keep = res.map(|_| true);
+
+        keep
+    };
+
+    proposals.retain(|p| keep(p.proposal.proposal_type(), p.is_by_reference()))?;
+
+    #[cfg(feature = "custom_proposal")]
+    proposals.retain_custom(|p| keep(p.proposal.proposal_type(), p.is_by_reference()))?;
+
+    Ok(())
+}
+
 #[cfg(feature = "psk")]
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 pub(crate) async fn filter_out_invalid_psks<P, CP>(
@@ -136,7 +136,8 @@ where
             self.cipher_suite_provider,
             self.identity_provider,
             member_validation_context,
-        );
+        )
+        .with_extension_validators(self.leaf_node_extension_validators);
 
         let adds = wrap_iter(proposals.add_proposals());
 
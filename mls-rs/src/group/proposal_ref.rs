@@ -17,6 +17,9 @@ use crate::hash_reference::HashReference;
 /// Unique identifier for a proposal message.
 pub struct ProposalRef(HashReference);
 
+/// Label used to compute proposal references as defined by RFC 9420.
+pub(crate) const PROPOSAL_REF_LABEL: &[u8] = b"MLS 1.0 Proposal Reference";
+
 impl Deref for ProposalRef {
     type Target = [u8];
 
@@ -27,16 +30,25 @@ impl Deref for ProposalRef {
 
 #[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen)]
 impl ProposalRef {
+    #[cfg(test)]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub(crate) async fn from_content<CS: CipherSuiteProvider>(
         cipher_suite_provider: &CS,
         content: &AuthenticatedContent,
+    ) -> Result<Self, MlsError> {
+        Self::from_content_with_label(cipher_suite_provider, content, PROPOSAL_REF_LABEL).await
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn from_content_with_label<CS: CipherSuiteProvider>(
+        cipher_suite_provider: &CS,
+        content: &AuthenticatedContent,
+        label: &[u8],
     ) -> Result<Self, MlsError> {
         let bytes = &content.mls_encode_to_vec()?;
 
         Ok(ProposalRef(
-            HashReference::compute(bytes, b"MLS 1.0 Proposal Reference", cipher_suite_provider)
-                .await?,
+            HashReference::compute(bytes, label, cipher_suite_provider).await?,
         ))
     }
 
@@ -144,6 +144,31 @@ where
         })
     }
 
+    /// Create the group resulting from a previously accepted
+    /// [`ReInitProposal`] in a single step.
+    ///
+    /// This is a shortcut for calling [`Group::get_reinit_client`] followed by
+    /// [`ReinitClient::commit`] and is meant to be used by the member that
+    /// initiates the new group. `new_key_packages` must contain fresh key
+    /// packages of all other members, generated with
+    /// [`ReinitClient::generate_key_package`]. The returned welcome messages
+    /// allow them to join with [`ReinitClient::join`].
+    ///
+    /// Returns [`MlsError::PendingReInitNotFound`] if no commit containing a
+    /// [`ReInitProposal`] was accepted in this group.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn complete_reinit(
+        self,
+        new_signer: Option<SignatureSecretKey>,
+        new_signing_identity: Option<SigningIdentity>,
+        new_key_packages: Vec<MlsMessage>,
+        new_leaf_node_extensions: ExtensionList,
+    ) -> Result<(Group<C>, Vec<MlsMessage>), MlsError> {
+        self.get_reinit_client(new_signer, new_signing_identity)?
+            .commit(new_key_packages, new_leaf_node_extensions)
+            .await
+    }
+
     fn resumption_psk_input(&self, usage: ResumptionPSKUsage) -> Result<PskSecretInput, MlsError> {
         let psk = self.epoch_secrets.resumption_secret.clone();
 
@@ -255,6 +280,7 @@ async fn resumption_create_group<C: ClientConfig + Clone>(
         new_group_params.extensions.clone(),
         leaf_node_extensions.clone(),
         signer,
+        Vec::new(),
     )
     .await?;
 
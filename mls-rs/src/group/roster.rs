@@ -4,7 +4,7 @@
 
 use super::*;
 
-pub use mls_rs_core::group::Member;
+pub use mls_rs_core::group::{LeafNodeSourceType, Member};
 
 pub(crate) fn member_from_leaf_node(leaf_node: &LeafNode, leaf_index: LeafIndex) -> Member {
     Member::new(
@@ -13,6 +13,8 @@ pub(crate) fn member_from_leaf_node(leaf_node: &LeafNode, leaf_index: LeafIndex)
         leaf_node.ungreased_capabilities(),
         leaf_node.ungreased_extensions(),
     )
+    .with_source(leaf_node.leaf_node_source.source_type())
+    .with_hpke_public_key(leaf_node.public_key.clone())
 }
 
 #[cfg_attr(
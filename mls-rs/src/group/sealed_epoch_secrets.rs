@@ -0,0 +1,250 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::vec::Vec;
+use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use mls_rs_core::{crypto::CipherSuiteProvider, error::IntoAnyError};
+use zeroize::Zeroizing;
+
+use crate::{client::MlsError, client_config::ClientConfig, tree_kem::node::NodeIndex};
+
+use super::{secret_tree::SecretTree, Group};
+
+/// Secrets used to decrypt the messages of a single epoch, sealed under a key
+/// chosen by the caller, see [`Group::export_epoch_secrets`].
+#[derive(Clone, Debug, PartialEq, MlsSize, MlsEncode, MlsDecode)]
+pub struct SealedEpochSecrets {
+    epoch: u64,
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    nonce: Vec<u8>,
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    ciphertext: Vec<u8>,
+}
+
+impl SealedEpochSecrets {
+    /// Epoch of the sealed secrets.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MlsError> {
+        self.mls_encode_to_vec().map_err(Into::into)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MlsError> {
+        Self::mls_decode(&mut &*bytes).map_err(Into::into)
+    }
+}
+
+#[derive(MlsSize, MlsEncode, MlsDecode)]
+struct EpochDecryptionSecrets {
+    secret_tree: SecretTree<NodeIndex>,
+}
+
+impl<C> Group<C>
+where
+    C: ClientConfig + Clone,
+{
+    /// Export the secrets used to decrypt messages of the current epoch,
+    /// sealed with the AEAD of the group's cipher suite under `wrapping_key`.
+    ///
+    /// This allows another device of the same user, holding this group at the
+    /// same epoch, to catch up with the messages of the epoch processed by this
+    /// group with [`Group::import_epoch_secrets`], without transferring a full
+    /// snapshot.
+    ///
+    /// # Warning
+    ///
+    /// The sealed secrets allow decrypting all messages of the current epoch
+    /// that were not yet decrypted by this group. `wrapping_key` must only be
+    /// known to devices of the same user.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn export_epoch_secrets(
+        &self,
+        wrapping_key: &[u8],
+    ) -> Result<SealedEpochSecrets, MlsError> {
+        self.check_wrapping_key(wrapping_key)?;
+
+        let secrets = EpochDecryptionSecrets {
+            secret_tree: self.epoch_secrets.secret_tree.clone(),
+        };
+
+        let plaintext = Zeroizing::new(secrets.mls_encode_to_vec()?);
+        let epoch = self.current_epoch();
+
+        let nonce = self
+            .cipher_suite_provider
+            .random_bytes_vec(self.cipher_suite_provider.aead_nonce_size())
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
+
+        let ciphertext = self
+            .cipher_suite_provider
+            .aead_seal(
+                wrapping_key,
+                &plaintext,
+                Some(&self.epoch_secrets_aad(epoch)),
+                &nonce,
+            )
+            .await
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
+
+        Ok(SealedEpochSecrets {
+            epoch,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    /// Import secrets exported by [`Group::export_epoch_secrets`] on another
+    /// device, moving the ratchets this group uses to decrypt messages of the
+    /// current epoch forward to the generations reached on that device.
+    ///
+    /// Ratchets are never moved back, so messages already decrypted by this
+    /// group can not be decrypted again. The ratchets of this group's own leaf,
+    /// used to encrypt the messages it sends, are not imported.
+    ///
+    /// Returns [`MlsError::InvalidEpoch`] if `sealed` was exported in another
+    /// epoch, and an error if it was not sealed under `wrapping_key` for this
+    /// group.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn import_epoch_secrets(
+        &mut self,
+        sealed: &SealedEpochSecrets,
+        wrapping_key: &[u8],
+    ) -> Result<(), MlsError> {
+        self.check_wrapping_key(wrapping_key)?;
+
+        if sealed.epoch != self.current_epoch() {
+            return Err(MlsError::InvalidEpoch);
+        }
+
+        let plaintext = self
+            .cipher_suite_provider
+            .aead_open(
+                wrapping_key,
+                &sealed.ciphertext,
+                Some(&self.epoch_secrets_aad(sealed.epoch)),
+                &sealed.nonce,
+            )
+            .await
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
+
+        let secrets = EpochDecryptionSecrets::mls_decode(&mut &**plaintext)?;
+        let own_leaf = NodeIndex::from(self.private_tree.self_index);
+
+        self.epoch_secrets
+            .secret_tree
+            .import_ratchets(&self.cipher_suite_provider, &secrets.secret_tree, &own_leaf)
+            .await
+    }
+
+    fn check_wrapping_key(&self, wrapping_key: &[u8]) -> Result<(), MlsError> {
+        (wrapping_key.len() == self.cipher_suite_provider.aead_key_size())
+            .then_some(())
+            .ok_or(MlsError::InvalidWrappingKeyLength(wrapping_key.len()))
+    }
+
+    fn epoch_secrets_aad(&self, epoch: u64) -> Vec<u8> {
+        [&epoch.to_be_bytes(), self.group_id()].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use assert_matches::assert_matches;
+
+    use crate::{
+        client::{
+            test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+            MlsError,
+        },
+        group::{test_utils::test_group, ReceivedMessage},
+    };
+
+    use super::SealedEpochSecrets;
+
+    const WRAPPING_KEY: [u8; 16] = [7; 16];
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn second_device_catches_up_with_imported_secrets() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+        let mut bob_device = bob.clone();
+
+        let first = alice
+            .encrypt_application_message(b"first", vec![])
+            .await
+            .unwrap();
+
+        let second = alice
+            .encrypt_application_message(b"second", vec![])
+            .await
+            .unwrap();
+
+        bob.process_message(first.clone()).await.unwrap();
+
+        let sealed = bob.export_epoch_secrets(&WRAPPING_KEY).await.unwrap();
+        let sealed = SealedEpochSecrets::from_bytes(&sealed.to_bytes().unwrap()).unwrap();
+
+        bob_device
+            .import_epoch_secrets(&sealed, &WRAPPING_KEY)
+            .await
+            .unwrap();
+
+        // The key for the first message was consumed by the first device.
+        let res = bob_device.process_message(first).await;
+        assert_matches!(res, Err(MlsError::KeyMissing(0)));
+
+        let received = bob_device.process_message(second.clone()).await.unwrap();
+        assert_matches!(received, ReceivedMessage::ApplicationMessage(m) if m.data() == b"second");
+
+        // The first device still decrypts the second message as well.
+        let received = bob.process_message(second).await.unwrap();
+        assert_matches!(received, ReceivedMessage::ApplicationMessage(_));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn import_does_not_rewind_ratchets() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (bob, _) = alice.join("bob").await;
+        let mut bob_device = bob.clone();
+
+        let message = alice
+            .encrypt_application_message(b"hello", vec![])
+            .await
+            .unwrap();
+
+        bob_device.process_message(message.clone()).await.unwrap();
+
+        // The first device has not decrypted the message yet.
+        let sealed = bob.export_epoch_secrets(&WRAPPING_KEY).await.unwrap();
+
+        bob_device
+            .import_epoch_secrets(&sealed, &WRAPPING_KEY)
+            .await
+            .unwrap();
+
+        let res = bob_device.process_message(message).await;
+        assert_matches!(res, Err(MlsError::KeyMissing(0)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn import_requires_wrapping_key_and_epoch() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let sealed = alice.export_epoch_secrets(&WRAPPING_KEY).await.unwrap();
+
+        let res = alice.import_epoch_secrets(&sealed, &[8; 16]).await;
+        assert_matches!(res, Err(MlsError::CryptoProviderError(_)));
+
+        let res = alice.import_epoch_secrets(&sealed, &[7; 3]).await;
+        assert_matches!(res, Err(MlsError::InvalidWrappingKeyLength(3)));
+
+        alice.commit(vec![]).await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
+
+        let res = alice.import_epoch_secrets(&sealed, &WRAPPING_KEY).await;
+        assert_matches!(res, Err(MlsError::InvalidEpoch));
+    }
+}
@@ -10,7 +10,13 @@ use core::{
 
 use zeroize::Zeroizing;
 
-use crate::{client::MlsError, map::LargeMap, tree_kem::math::TreeIndex, CipherSuiteProvider};
+use crate::{
+    client::MlsError,
+    crypto::{aead_key_size, aead_nonce_size, AeadContext},
+    map::LargeMap,
+    tree_kem::math::TreeIndex,
+    CipherSuiteProvider,
+};
 
 use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 use mls_rs_core::error::IntoAnyError;
@@ -250,6 +256,50 @@ impl<T: TreeIndex> SecretTree<T> {
         Ok(res)
     }
 
+    /// Advance the `key_type` ratchet of `leaf_index` by `count` generations.
+    ///
+    /// Fails if the resulting generation is further than `MAX_RATCHET_BACK_HISTORY`
+    /// from the start of the epoch, as a receiver that has not yet processed any
+    /// message from this leaf would reject it.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn skip_generations<P: CipherSuiteProvider>(
+        &mut self,
+        cipher_suite: &P,
+        leaf_index: T,
+        key_type: KeyType,
+        count: u32,
+    ) -> Result<(), MlsError> {
+        let mut ratchet = self.take_leaf_ratchet(cipher_suite, &leaf_index).await?;
+
+        let generation = match key_type {
+            KeyType::Handshake => ratchet.handshake.generation,
+            KeyType::Application => ratchet.application.generation,
+        };
+
+        let mut res = match generation.checked_add(count) {
+            Some(new_generation) if new_generation <= MAX_RATCHET_BACK_HISTORY => Ok(()),
+            new_generation => Err(MlsError::InvalidFutureGeneration(
+                new_generation.unwrap_or(u32::MAX),
+            )),
+        };
+
+        for _ in 0..count {
+            if res.is_err() {
+                break;
+            }
+
+            res = ratchet
+                .next_message_key(cipher_suite, key_type)
+                .await
+                .map(|_| ());
+        }
+
+        self.known_secrets
+            .set_node(leaf_index, SecretTreeNode::Ratchet(ratchet));
+
+        res
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn message_key_generation<P: CipherSuiteProvider>(
         &mut self,
@@ -271,6 +321,48 @@ impl<T: TreeIndex> SecretTree<T> {
     }
 }
 
+impl<T: TreeIndex> SecretTree<T> {
+    /// Advance the leaf ratchets of this tree to the generations reached in `other`, a copy of
+    /// this tree on which more messages were processed.
+    ///
+    /// Ratchets are only moved forward, so keys already consumed by this tree stay consumed.
+    /// The ratchets of `own_leaf`, used to encrypt messages sent from this tree, are left
+    /// untouched.
+    #[cfg(feature = "private_message")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn import_ratchets<P: CipherSuiteProvider>(
+        &mut self,
+        cipher_suite: &P,
+        other: &SecretTree<T>,
+        own_leaf: &T,
+    ) -> Result<(), MlsError> {
+        if other.leaf_count != self.leaf_count {
+            return Err(MlsError::InvalidEpoch);
+        }
+
+        for (leaf_index, node) in other.known_secrets.inner.iter() {
+            // Leaves without a ratchet in `other` did not advance.
+            let SecretTreeNode::Ratchet(imported) = node else {
+                continue;
+            };
+
+            if leaf_index == own_leaf {
+                continue;
+            }
+
+            let mut ratchet = self.take_leaf_ratchet(cipher_suite, leaf_index).await?;
+
+            ratchet.application.import(&imported.application);
+            ratchet.handshake.import(&imported.handshake);
+
+            self.known_secrets
+                .set_node(leaf_index.clone(), SecretTreeNode::Ratchet(ratchet));
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Copy)]
 pub enum KeyType {
     Handshake,
@@ -407,6 +499,27 @@ impl SecretKeyRatchet {
         })
     }
 
+    /// Move this ratchet forward to the generation of `other`, if it is ahead. Keys of
+    /// generations this ratchet has already passed are not imported.
+    #[cfg(feature = "private_message")]
+    fn import(&mut self, other: &SecretKeyRatchet) {
+        if other.generation <= self.generation {
+            return;
+        }
+
+        #[cfg(feature = "out_of_order")]
+        self.history.extend(
+            other
+                .history
+                .iter()
+                .filter(|(generation, _)| **generation >= self.generation)
+                .map(|(generation, key)| (*generation, key.clone())),
+        );
+
+        self.secret = other.secret.clone();
+        self.generation = other.generation;
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn get_message_key<P: CipherSuiteProvider>(
         &mut self,
@@ -459,14 +572,14 @@ impl SecretKeyRatchet {
                 .derive_secret(
                     cipher_suite_provider,
                     b"nonce",
-                    cipher_suite_provider.aead_nonce_size(),
+                    aead_nonce_size(cipher_suite_provider, AeadContext::Framing),
                 )
                 .await?,
             key: self
                 .derive_secret(
                     cipher_suite_provider,
                     b"key",
-                    cipher_suite_provider.aead_key_size(),
+                    aead_key_size(cipher_suite_provider, AeadContext::Framing),
                 )
                 .await?,
             generation,
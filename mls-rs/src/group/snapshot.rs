@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 use alloc::boxed::Box;
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
 
 use crate::{
     client::MlsError,
@@ -33,9 +33,101 @@ use mls_rs_core::identity::IdentityProvider;
 
 use super::PendingCommit;
 
+#[cfg(feature = "external_client")]
+use crate::external_client::ExternalSnapshot;
+
 pub(crate) use legacy::LegacyPendingCommit;
 
+#[cfg(feature = "prior_epoch")]
+use crate::group::epoch::PriorEpoch;
+
+#[cfg(any(
+    feature = "snapshot_json",
+    feature = "snapshot_bincode",
+    feature = "snapshot_cbor"
+))]
+use mls_rs_core::error::IntoAnyError;
+
+/// Serialization format of a group snapshot exported with
+/// [`Group::export_snapshot`].
+///
+/// The format is recorded in the first byte of the exported snapshot, so that
+/// [`Client::import_snapshot`](crate::Client::import_snapshot) detects it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+#[non_exhaustive]
+pub enum SnapshotFormat {
+    /// Compact MLS binary encoding, also used to write the group to
+    /// [`GroupStateStorage`](crate::GroupStateStorage).
+    Mls = 0,
+    /// Self-describing JSON encoding, useful for debugging.
+    #[cfg(feature = "snapshot_json")]
+    Json = 1,
+    /// Compact [bincode](https://docs.rs/bincode) encoding.
+    #[cfg(feature = "snapshot_bincode")]
+    Bincode = 2,
+    /// Self-describing binary [CBOR](https://www.rfc-editor.org/rfc/rfc8949) encoding.
+    #[cfg(feature = "snapshot_cbor")]
+    Cbor = 3,
+}
+
+impl SnapshotFormat {
+    fn from_tag(tag: u8) -> Result<Self, MlsError> {
+        match tag {
+            0 => Ok(Self::Mls),
+            #[cfg(feature = "snapshot_json")]
+            1 => Ok(Self::Json),
+            #[cfg(feature = "snapshot_bincode")]
+            2 => Ok(Self::Bincode),
+            #[cfg(feature = "snapshot_cbor")]
+            3 => Ok(Self::Cbor),
+            _ => Err(MlsError::UnsupportedSnapshotFormat(tag)),
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "snapshot_json",
+    feature = "snapshot_bincode",
+    feature = "snapshot_cbor"
+))]
+#[derive(Debug)]
+struct SnapshotFormatError(Box<dyn std::error::Error + Send + Sync>);
+
+#[cfg(any(
+    feature = "snapshot_json",
+    feature = "snapshot_bincode",
+    feature = "snapshot_cbor"
+))]
+impl IntoAnyError for SnapshotFormatError {
+    fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+        Ok(self.0)
+    }
+}
+
+#[cfg(any(
+    feature = "snapshot_json",
+    feature = "snapshot_bincode",
+    feature = "snapshot_cbor"
+))]
+fn snapshot_format_error<E: std::error::Error + Send + Sync + 'static>(e: E) -> MlsError {
+    MlsError::SerializationError(SnapshotFormatError(e.into()).into_any_error())
+}
+
+/// Version of the encoding of [`FullGroupState`], encoded before the state
+/// so that it can be checked before decoding the rest.
+#[cfg(feature = "prior_epoch")]
+pub(crate) const FULL_GROUP_STATE_VERSION: u16 = 1;
+
+#[cfg(feature = "prior_epoch")]
 #[derive(Debug, PartialEq, Clone, MlsEncode, MlsDecode, MlsSize)]
+pub(crate) struct FullGroupState {
+    pub(crate) version: u16,
+    pub(crate) snapshot: Snapshot,
+    pub(crate) prior_epochs: Vec<PriorEpoch>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct Snapshot {
     version: u16,
@@ -47,6 +139,74 @@ pub(crate) struct Snapshot {
     pending_updates: SmallMap<HpkePublicKey, (HpkeSecretKey, Option<SignatureSecretKey>)>,
     pending_commit_snapshot: PendingCommitSnapshot,
     signer: SignatureSecretKey,
+    // Only encoded starting with version 2. Starting with version 3, cached
+    // proposals in `state` include their authenticated data.
+    #[cfg_attr(feature = "serde", serde(default, with = "mls_rs_core::vec_serde"))]
+    origin_hash: Vec<u8>,
+}
+
+impl MlsSize for Snapshot {
+    fn mls_encoded_len(&self) -> usize {
+        let len = self.version.mls_encoded_len()
+            + self.state.mls_encoded_len()
+            + self.private_tree.mls_encoded_len()
+            + self.epoch_secrets.mls_encoded_len()
+            + self.key_schedule.mls_encoded_len()
+            + self.pending_commit_snapshot.mls_encoded_len()
+            + self.signer.mls_encoded_len();
+
+        #[cfg(feature = "by_ref_proposal")]
+        let len = len + self.pending_updates.mls_encoded_len();
+
+        if self.version >= 2 {
+            len + mls_rs_codec::byte_vec::mls_encoded_len(&self.origin_hash)
+        } else {
+            len
+        }
+    }
+}
+
+impl MlsEncode for Snapshot {
+    fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), mls_rs_codec::Error> {
+        self.version.mls_encode(writer)?;
+        self.state.mls_encode(writer)?;
+        self.private_tree.mls_encode(writer)?;
+        self.epoch_secrets.mls_encode(writer)?;
+        self.key_schedule.mls_encode(writer)?;
+        #[cfg(feature = "by_ref_proposal")]
+        self.pending_updates.mls_encode(writer)?;
+        self.pending_commit_snapshot.mls_encode(writer)?;
+        self.signer.mls_encode(writer)?;
+
+        if self.version >= 2 {
+            mls_rs_codec::byte_vec::mls_encode(&self.origin_hash, writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl MlsDecode for Snapshot {
+    fn mls_decode(reader: &mut &[u8]) -> Result<Self, mls_rs_codec::Error> {
+        let version = u16::mls_decode(reader)?;
+
+        Ok(Self {
+            version,
+            state: RawGroupState::mls_decode_versioned(reader, version < 3)?,
+            private_tree: TreeKemPrivate::mls_decode(reader)?,
+            epoch_secrets: EpochSecrets::mls_decode(reader)?,
+            key_schedule: KeySchedule::mls_decode(reader)?,
+            #[cfg(feature = "by_ref_proposal")]
+            pending_updates: MlsDecode::mls_decode(reader)?,
+            pending_commit_snapshot: PendingCommitSnapshot::mls_decode(reader)?,
+            signer: SignatureSecretKey::mls_decode(reader)?,
+            origin_hash: if version >= 2 {
+                mls_rs_codec::byte_vec::mls_decode(reader)?
+            } else {
+                Vec::new()
+            },
+        })
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Default, MlsSize, MlsEncode, MlsDecode)]
@@ -91,7 +251,7 @@ impl PendingCommitSnapshot {
     }
 }
 
-#[derive(Debug, MlsEncode, MlsDecode, MlsSize, PartialEq, Clone)]
+#[derive(Debug, MlsEncode, MlsSize, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct RawGroupState {
     pub(crate) context: GroupContext,
@@ -106,6 +266,43 @@ pub(crate) struct RawGroupState {
 }
 
 impl RawGroupState {
+    /// Decode a state whose cached proposals were encoded without their
+    /// authenticated data if `legacy_proposals` is set, in which case the
+    /// authenticated data of each proposal is empty.
+    pub(crate) fn mls_decode_versioned(
+        reader: &mut &[u8],
+        legacy_proposals: bool,
+    ) -> Result<Self, mls_rs_codec::Error> {
+        let context = GroupContext::mls_decode(reader)?;
+
+        #[cfg(feature = "by_ref_proposal")]
+        let proposals = if legacy_proposals {
+            Vec::<(ProposalRef, legacy::LegacyCachedProposal)>::mls_decode(reader)?
+                .into_iter()
+                .fold(SmallMap::default(), |mut proposals, (proposal_ref, p)| {
+                    proposals.insert(proposal_ref, p.into());
+                    proposals
+                })
+        } else {
+            MlsDecode::mls_decode(reader)?
+        };
+
+        #[cfg(not(feature = "by_ref_proposal"))]
+        let _ = legacy_proposals;
+
+        Ok(Self {
+            context,
+            #[cfg(feature = "by_ref_proposal")]
+            proposals,
+            #[cfg(feature = "by_ref_proposal")]
+            own_proposals: MlsDecode::mls_decode(reader)?,
+            public_tree: TreeKemPublic::mls_decode(reader)?,
+            interim_transcript_hash: InterimTranscriptHash::mls_decode(reader)?,
+            pending_reinit: Option::mls_decode(reader)?,
+            confirmation_tag: ConfirmationTag::mls_decode(reader)?,
+        })
+    }
+
     pub(crate) fn export(state: &GroupState) -> Self {
         #[cfg(feature = "tree_index")]
         let public_tree = state.public_tree.clone();
@@ -213,6 +410,183 @@ where
         self.state_repo.write_to_storage(snapshot).await
     }
 
+    /// Delete the group, removing its current state and all prior epochs from
+    /// the [`GroupStateStorage`](crate::GroupStateStorage) in use by the group.
+    ///
+    /// The group is consumed and all secrets it holds in memory, including
+    /// those of retained prior epochs that were not written to storage, are
+    /// zeroized before the storage is asked to delete the group.
+    ///
+    /// Returns [`MlsError::GroupDeletionUnsupported`] if the storage does not
+    /// implement [`GroupStateStorage::delete_group`](crate::GroupStateStorage::delete_group).
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn delete(mut self) -> Result<(), MlsError> {
+        let group_id = self.context().group_id.clone();
+        self.clear_secrets();
+
+        self.state_repo
+            .delete_group(&group_id)
+            .await?
+            .then_some(())
+            .ok_or(MlsError::GroupDeletionUnsupported)
+    }
+
+    fn clear_secrets(&mut self) {
+        self.epoch_secrets.clear();
+        self.private_tree.secret_keys.clear();
+        self.key_schedule = Default::default();
+
+        #[cfg(feature = "by_ref_proposal")]
+        self.pending_updates.clear();
+
+        #[cfg(feature = "psk")]
+        {
+            self.previous_psk = None;
+        }
+    }
+
+    /// Export the full state of the group, including the secrets of all prior
+    /// epochs retained by the group, for migrating the group to another device
+    /// of the same member with [`Client::import_full_state`](crate::Client::import_full_state).
+    ///
+    /// The output contains the private keys of the local member and all
+    /// retained epoch secrets. It must only be transferred over a channel
+    /// that is trusted for the local member's own secrets.
+    ///
+    /// The output starts with a version number of its format, and
+    /// [`Client::import_full_state`](crate::Client::import_full_state) rejects
+    /// versions it does not support.
+    #[cfg(feature = "prior_epoch")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn export_full_state(&self) -> Result<Vec<u8>, MlsError> {
+        let full_state = FullGroupState {
+            version: FULL_GROUP_STATE_VERSION,
+            snapshot: self.snapshot()?,
+            prior_epochs: self.state_repo.prior_epochs().await?,
+        };
+
+        Ok(full_state.mls_encode_to_vec()?)
+    }
+
+    #[cfg(feature = "prior_epoch")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn from_full_state(config: C, data: &[u8]) -> Result<Self, MlsError> {
+        let version = u16::mls_decode(&mut &*data)?;
+
+        if version != FULL_GROUP_STATE_VERSION {
+            return Err(MlsError::UnsupportedFullStateVersion(version));
+        }
+
+        let full_state = FullGroupState::mls_decode(&mut &*data)?;
+        let mut group = Self::from_snapshot(config, full_state.snapshot).await?;
+
+        group.current_user_leaf_node()?;
+
+        let tree_hash = group
+            .state
+            .public_tree
+            .tree_hash(&group.cipher_suite_provider)
+            .await?;
+
+        if tree_hash != group.state.context.tree_hash {
+            return Err(MlsError::TreeHashMismatch);
+        }
+
+        let interim_transcript_hash = InterimTranscriptHash::create(
+            &group.cipher_suite_provider,
+            &group.state.context.confirmed_transcript_hash,
+            &group.state.confirmation_tag,
+        )
+        .await?;
+
+        if interim_transcript_hash != group.state.interim_transcript_hash {
+            return Err(MlsError::InconsistentGroupState);
+        }
+
+        for epoch in full_state.prior_epochs {
+            if epoch.epoch_id() >= group.current_epoch() {
+                return Err(MlsError::InvalidEpoch);
+            }
+
+            group.state_repo.insert(epoch).await?;
+        }
+
+        group.write_to_storage().await?;
+
+        Ok(group)
+    }
+
+    /// Create a snapshot of the public state of this group that can be loaded
+    /// by an [`ExternalClient`](crate::external_client::ExternalClient) with
+    /// [`load_group`](crate::external_client::ExternalClient::load_group).
+    ///
+    /// The snapshot contains the group context, the public ratchet tree, the
+    /// transcript hashes and the proposals received by reference. It contains
+    /// no secret of this group or of this member, which allows provisioning an
+    /// observer for this group.
+    #[cfg(feature = "external_client")]
+    pub fn to_external_snapshot(&self) -> ExternalSnapshot {
+        let state = RawGroupState::export(&self.state);
+
+        // Descriptions of our own proposals are only needed by this member
+        #[cfg(feature = "by_ref_proposal")]
+        let state = RawGroupState {
+            own_proposals: Default::default(),
+            ..state
+        };
+
+        ExternalSnapshot::from_raw_state(state)
+    }
+
+    /// Export the current state of the group, including its secrets, in the
+    /// given `format`.
+    ///
+    /// The snapshot contains the same data as written to the
+    /// [`GroupStorageProvider`](crate::GroupStateStorage) by
+    /// [`Group::write_to_storage`] and can be loaded with
+    /// [`Client::import_snapshot`](crate::Client::import_snapshot). Prior epochs
+    /// are not included.
+    pub fn export_snapshot(&self, format: SnapshotFormat) -> Result<Vec<u8>, MlsError> {
+        let snapshot = self.snapshot()?;
+        let mut data = vec![format as u8];
+
+        match format {
+            SnapshotFormat::Mls => snapshot.mls_encode(&mut data)?,
+            #[cfg(feature = "snapshot_json")]
+            SnapshotFormat::Json => {
+                serde_json::to_writer(&mut data, &snapshot).map_err(snapshot_format_error)?
+            }
+            #[cfg(feature = "snapshot_bincode")]
+            SnapshotFormat::Bincode => {
+                bincode::serialize_into(&mut data, &snapshot).map_err(snapshot_format_error)?
+            }
+            #[cfg(feature = "snapshot_cbor")]
+            SnapshotFormat::Cbor => {
+                ciborium::into_writer(&snapshot, &mut data).map_err(snapshot_format_error)?
+            }
+        }
+
+        Ok(data)
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn from_exported_snapshot(config: C, data: &[u8]) -> Result<Self, MlsError> {
+        let mut data = data;
+        let format = SnapshotFormat::from_tag(u8::mls_decode(&mut data)?)?;
+
+        let snapshot = match format {
+            SnapshotFormat::Mls => Snapshot::mls_decode(&mut data)?,
+            #[cfg(feature = "snapshot_json")]
+            SnapshotFormat::Json => serde_json::from_slice(data).map_err(snapshot_format_error)?,
+            #[cfg(feature = "snapshot_bincode")]
+            SnapshotFormat::Bincode => bincode::deserialize(data).map_err(snapshot_format_error)?,
+            #[cfg(feature = "snapshot_cbor")]
+            SnapshotFormat::Cbor => ciborium::from_reader(data).map_err(snapshot_format_error)?,
+        };
+
+        Self::from_snapshot(config, snapshot).await
+    }
+
     pub(crate) fn snapshot(&self) -> Result<Snapshot, MlsError> {
         Ok(Snapshot {
             state: RawGroupState::export(&self.state),
@@ -222,8 +596,9 @@ where
             pending_updates: self.pending_updates.clone(),
             pending_commit_snapshot: self.pending_commit.clone(),
             epoch_secrets: self.epoch_secrets.clone(),
-            version: 1,
+            version: 3,
             signer: self.signer.clone(),
+            origin_hash: self.origin_hash.clone(),
         })
     }
 
@@ -243,6 +618,8 @@ where
             config.group_state_storage(),
             config.key_package_repo(),
             None,
+            #[cfg(feature = "prior_epoch")]
+            config.max_retained_epochs(),
         )?;
 
         Ok(Group {
@@ -261,12 +638,17 @@ where
             pending_commit: snapshot.pending_commit_snapshot,
             #[cfg(test)]
             commit_modifiers: Default::default(),
+            #[cfg(all(feature = "private_message", any(test, feature = "test_util")))]
+            encryption_modifiers: Default::default(),
             epoch_secrets: snapshot.epoch_secrets,
             state_repo,
             cipher_suite_provider,
             #[cfg(feature = "psk")]
             previous_psk: None,
             signer: snapshot.signer,
+            origin_hash: snapshot.origin_hash,
+            staged_proposals: Vec::new(),
+            last_removed: Vec::new(),
         })
     }
 }
@@ -274,8 +656,30 @@ where
 mod legacy {
     use crate::{group::AuthenticatedContent, tree_kem::path_secret::PathSecret};
 
+    #[cfg(feature = "by_ref_proposal")]
+    use crate::group::{Proposal, Sender};
+
     use super::*;
 
+    /// Cached proposal as encoded before its authenticated data was kept.
+    #[cfg(feature = "by_ref_proposal")]
+    #[derive(MlsDecode)]
+    pub(crate) struct LegacyCachedProposal {
+        proposal: Proposal,
+        sender: Sender,
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    impl From<LegacyCachedProposal> for CachedProposal {
+        fn from(legacy: LegacyCachedProposal) -> Self {
+            Self {
+                proposal: legacy.proposal,
+                sender: legacy.sender,
+                authenticated_data: Vec::new(),
+            }
+        }
+    }
+
     #[derive(Clone, PartialEq, Debug, MlsEncode, MlsDecode, MlsSize)]
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub(crate) struct LegacyPendingCommit {
@@ -326,24 +730,30 @@ pub(crate) mod test_utils {
             pending_commit_snapshot: Default::default(),
             version: 1,
             signer: vec![].into(),
+            origin_hash: vec![],
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use alloc::vec;
-    use mls_rs_core::group::{GroupState, GroupStateStorage};
+    use alloc::vec::Vec;
+    use mls_rs_core::group::{EpochRecord, GroupState, GroupStateStorage};
 
     use crate::{
         client::test_utils::{TestClientBuilder, TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        client_config::ClientConfig,
         group::{
+            key_schedule::KeySchedule,
+            secret_tree::SecretTree,
             test_utils::{test_group, TestGroup},
             Group,
         },
         storage_provider::in_memory::InMemoryGroupStateStorage,
     };
 
+    use super::{MlsError, Snapshot, SnapshotFormat};
+
     #[cfg(all(feature = "std", feature = "by_ref_proposal"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn legacy_interop() {
@@ -376,6 +786,68 @@ mod tests {
             .unwrap();
     }
 
+    #[cfg(all(feature = "std", feature = "by_ref_proposal"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn snapshot_with_legacy_cached_proposals_can_be_decoded() {
+        use crate::group::test_utils::test_n_member_group;
+        use mls_rs_codec::{MlsDecode, MlsEncode};
+
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+
+        let proposal = groups[1].propose_update(b"routing".to_vec()).await.unwrap();
+        groups[0].process_incoming_message(proposal).await.unwrap();
+
+        let snapshot = groups[0].snapshot().unwrap();
+        let state = &snapshot.state;
+
+        // Version 2 encoding, in which cached proposals have no authenticated data
+        let mut bytes = 2u16.mls_encode_to_vec().unwrap();
+        state.context.mls_encode(&mut bytes).unwrap();
+
+        state
+            .proposals
+            .iter()
+            .map(|(r, p)| (r.clone(), (p.proposal.clone(), p.sender)))
+            .collect::<Vec<_>>()
+            .mls_encode(&mut bytes)
+            .unwrap();
+
+        state.own_proposals.mls_encode(&mut bytes).unwrap();
+        state.public_tree.mls_encode(&mut bytes).unwrap();
+        state
+            .interim_transcript_hash
+            .mls_encode(&mut bytes)
+            .unwrap();
+        state.pending_reinit.mls_encode(&mut bytes).unwrap();
+        state.confirmation_tag.mls_encode(&mut bytes).unwrap();
+        snapshot.private_tree.mls_encode(&mut bytes).unwrap();
+        snapshot.epoch_secrets.mls_encode(&mut bytes).unwrap();
+        snapshot.key_schedule.mls_encode(&mut bytes).unwrap();
+        snapshot.pending_updates.mls_encode(&mut bytes).unwrap();
+        snapshot
+            .pending_commit_snapshot
+            .mls_encode(&mut bytes)
+            .unwrap();
+        snapshot.signer.mls_encode(&mut bytes).unwrap();
+        mls_rs_codec::byte_vec::mls_encode(&snapshot.origin_hash, &mut bytes).unwrap();
+
+        let decoded = Snapshot::mls_decode(&mut &*bytes).unwrap();
+
+        assert_eq!(decoded.state.proposals.len(), 1);
+
+        for (proposal_ref, cached) in decoded.state.proposals.iter() {
+            let original = state.proposals.get(proposal_ref).unwrap();
+
+            assert_eq!(original.authenticated_data, b"routing".to_vec());
+            assert_eq!(cached.proposal, original.proposal);
+            assert!(cached.authenticated_data.is_empty());
+        }
+
+        // Snapshots are now written with the authenticated data
+        let decoded = Snapshot::mls_decode(&mut &*snapshot.mls_encode_to_vec().unwrap()).unwrap();
+        assert_eq!(decoded, snapshot);
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn snapshot_restore(group: TestGroup) {
         let snapshot = group.snapshot().unwrap();
@@ -422,6 +894,167 @@ mod tests {
         snapshot_restore(group).await
     }
 
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn exported_snapshot_round_trip(format: SnapshotFormat) {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        group.commit(vec![]).await.unwrap();
+
+        let data = group.export_snapshot(format).unwrap();
+        assert_eq!(data[0], format as u8);
+
+        let group_restored = Group::from_exported_snapshot(group.config.clone(), &data)
+            .await
+            .unwrap();
+
+        assert!(Group::equal_group_state(&group, &group_restored));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn exported_snapshot_round_trip_mls() {
+        exported_snapshot_round_trip(SnapshotFormat::Mls).await
+    }
+
+    #[cfg(feature = "snapshot_json")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn exported_snapshot_round_trip_json() {
+        exported_snapshot_round_trip(SnapshotFormat::Json).await
+    }
+
+    #[cfg(feature = "snapshot_bincode")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn exported_snapshot_round_trip_bincode() {
+        exported_snapshot_round_trip(SnapshotFormat::Bincode).await
+    }
+
+    #[cfg(feature = "snapshot_cbor")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn exported_snapshot_round_trip_cbor() {
+        exported_snapshot_round_trip(SnapshotFormat::Cbor).await
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn exported_snapshot_with_unknown_format_is_rejected() {
+        let group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let mut data = group.export_snapshot(SnapshotFormat::Mls).unwrap();
+        data[0] = 0xff;
+
+        let res = Group::from_exported_snapshot(group.config.clone(), &data)
+            .await
+            .map(|_| ());
+
+        assert_matches::assert_matches!(res, Err(MlsError::UnsupportedSnapshotFormat(0xff)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn delete_removes_stored_state() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        group.write_to_storage().await.unwrap();
+
+        group.commit(vec![]).await.unwrap();
+        group.apply_pending_commit().await.unwrap();
+        group.write_to_storage().await.unwrap();
+
+        let group_id = group.group_id().to_vec();
+        let storage = group.config.group_state_storage();
+
+        assert_eq!(storage.stored_groups(), vec![group_id.clone()]);
+
+        group.group.delete().await.unwrap();
+
+        assert!(storage.stored_groups().is_empty());
+
+        let state = storage.state(&group_id).await.unwrap();
+        assert_eq!(state, None);
+
+        let max_epoch_id = storage.max_epoch_id(&group_id).await.unwrap();
+        assert_eq!(max_epoch_id, None);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn delete_clears_secrets() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        group.commit(vec![]).await.unwrap();
+        group.apply_pending_commit().await.unwrap();
+
+        let prior_epochs = group.state_repo.prior_epochs().await.unwrap();
+        assert!(!prior_epochs.is_empty());
+
+        let group_id = group.group_id().to_vec();
+        let group = &mut group.group;
+
+        group.clear_secrets();
+        let deleted = group.state_repo.delete_group(&group_id).await.unwrap();
+        assert!(deleted);
+
+        assert!(group.epoch_secrets.sender_data_secret.is_empty());
+        assert!(group.private_tree.secret_keys.is_empty());
+        assert_eq!(group.key_schedule, KeySchedule::default());
+        let prior_epochs = group.state_repo.prior_epochs().await.unwrap();
+        assert!(prior_epochs.is_empty());
+
+        #[cfg(feature = "psk")]
+        assert!(group.epoch_secrets.resumption_secret.is_empty());
+
+        #[cfg(feature = "private_message")]
+        assert_eq!(group.epoch_secrets.secret_tree, SecretTree::empty());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn delete_with_unsupported_storage_fails() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        group.write_to_storage().await.unwrap();
+
+        let storage = NoDeleteStorage(group.config.group_state_storage());
+
+        let group = TestClientBuilder::new_for_test()
+            .group_state_storage(storage.clone())
+            .build()
+            .load_group(group.group_id())
+            .await
+            .unwrap();
+
+        let res = group.delete().await;
+
+        assert_matches::assert_matches!(res, Err(MlsError::GroupDeletionUnsupported));
+        assert_eq!(storage.0.stored_groups().len(), 1);
+    }
+
+    #[derive(Clone)]
+    struct NoDeleteStorage(InMemoryGroupStateStorage);
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl GroupStateStorage for NoDeleteStorage {
+        type Error = <InMemoryGroupStateStorage as GroupStateStorage>::Error;
+
+        async fn state(&self, group_id: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+            self.0.state(group_id).await
+        }
+
+        async fn epoch(
+            &self,
+            group_id: &[u8],
+            epoch_id: u64,
+        ) -> Result<Option<Vec<u8>>, Self::Error> {
+            self.0.epoch(group_id, epoch_id).await
+        }
+
+        async fn write(
+            &mut self,
+            state: GroupState,
+            epoch_inserts: Vec<EpochRecord>,
+            epoch_updates: Vec<EpochRecord>,
+        ) -> Result<(), Self::Error> {
+            self.0.write(state, epoch_inserts, epoch_updates).await
+        }
+
+        async fn max_epoch_id(&self, group_id: &[u8]) -> Result<Option<u64>, Self::Error> {
+            self.0.max_epoch_id(group_id).await
+        }
+    }
+
     #[cfg(feature = "serde")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn serde() {
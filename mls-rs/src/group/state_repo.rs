@@ -39,6 +39,7 @@ where
     group_id: Vec<u8>,
     storage: S,
     key_package_repo: K,
+    max_retained_epochs: Option<usize>,
 }
 
 impl<S, K> Debug for GroupStateRepository<S, K>
@@ -59,6 +60,7 @@ where
             )
             .field("storage", &self.storage)
             .field("key_package_repo", &self.key_package_repo)
+            .field("max_retained_epochs", &self.max_retained_epochs)
             .finish()
     }
 }
@@ -74,6 +76,9 @@ where
         key_package_repo: K,
         // Set to `None` if restoring from snapshot; set to `Some` when joining a group.
         key_package_to_remove: Option<KeyPackageRef>,
+        // Prior epochs beyond this count are evicted and can no longer be read,
+        // even if the underlying storage still holds them.
+        max_retained_epochs: Option<usize>,
     ) -> Result<GroupStateRepository<S, K>, MlsError> {
         Ok(GroupStateRepository {
             group_id,
@@ -81,6 +86,22 @@ where
             pending_key_package_removal: key_package_to_remove,
             pending_commit: Default::default(),
             key_package_repo,
+            max_retained_epochs,
+        })
+    }
+
+    /// The oldest epoch id that can be read given the most recent prior epoch
+    /// `max_epoch_id`, or `None` if retention is not limited.
+    pub fn min_epoch_id(&self, max_epoch_id: u64) -> Option<u64> {
+        self.max_retained_epochs
+            .map(|max| (max_epoch_id + 1).saturating_sub(max as u64))
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn is_evicted(&self, epoch_id: u64) -> Result<bool, MlsError> {
+        Ok(match self.find_max_id().await? {
+            Some(max) => matches!(self.min_epoch_id(max), Some(min) if epoch_id < min),
+            None => false,
         })
     }
 
@@ -102,6 +123,10 @@ where
         &self,
         psk_id: &ResumptionPsk,
     ) -> Result<Option<PreSharedKey>, MlsError> {
+        if psk_id.psk_group_id.0 == self.group_id && self.is_evicted(psk_id.psk_epoch).await? {
+            return Ok(None);
+        }
+
         // Search the local inserts cache
         if let Some(min) = self.pending_commit.inserts.front().map(|e| e.epoch_id()) {
             if psk_id.psk_epoch >= min {
@@ -140,6 +165,10 @@ where
         &mut self,
         epoch_id: u64,
     ) -> Result<Option<&mut PriorEpoch>, MlsError> {
+        if self.is_evicted(epoch_id).await? {
+            return Ok(None);
+        }
+
         // Search the local inserts cache
         if let Some(min) = self.pending_commit.inserts.front().map(|e| e.epoch_id()) {
             if epoch_id >= min {
@@ -188,6 +217,17 @@ where
 
         self.pending_commit.inserts.push_back(epoch);
 
+        if let Some(max) = self.max_retained_epochs {
+            // The most recent epoch is always kept to track the next expected id.
+            while self.pending_commit.inserts.len() > max.max(1) {
+                self.pending_commit.inserts.pop_front();
+            }
+
+            if let Some(min) = self.min_epoch_id(epoch_id) {
+                self.pending_commit.updates.retain(|e| e.epoch_id() >= min);
+            }
+        }
+
         Ok(())
     }
 
@@ -230,7 +270,76 @@ where
         Ok(())
     }
 
-    #[cfg(any(feature = "psk", feature = "private_message"))]
+    /// Remove all data stored for this group, including pending prior epochs
+    /// that were not written yet.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn delete_group(&mut self, group_id: &[u8]) -> Result<bool, MlsError> {
+        self.pending_commit.inserts.clear();
+        self.pending_commit.updates.clear();
+
+        self.storage
+            .delete_group(group_id)
+            .await
+            .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))
+    }
+
+    /// All prior epochs currently retained for this group, including the ones
+    /// that are not written to storage yet, ordered by increasing epoch id.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn prior_epochs(&self) -> Result<Vec<PriorEpoch>, MlsError> {
+        let mut epochs = Vec::new();
+
+        let mut next_id = self
+            .storage
+            .max_epoch_id(&self.group_id)
+            .await
+            .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?;
+
+        let min_id = match self.find_max_id().await? {
+            Some(max) => self.min_epoch_id(max),
+            None => None,
+        };
+
+        while let Some(epoch_id) = next_id.filter(|id| min_id.map_or(true, |min| *id >= min)) {
+            let epoch = match self.find_pending(epoch_id) {
+                Some(i) => Some(self.pending_commit.updates[i].clone()),
+                None => self
+                    .storage
+                    .epoch(&self.group_id, epoch_id)
+                    .await
+                    .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?
+                    .map(|epoch| PriorEpoch::mls_decode(&mut &*epoch))
+                    .transpose()?,
+            };
+
+            let Some(epoch) = epoch else {
+                break;
+            };
+
+            epochs.push(epoch);
+            next_id = epoch_id.checked_sub(1);
+        }
+
+        epochs.reverse();
+        epochs.extend(self.pending_commit.inserts.iter().cloned());
+
+        Ok(epochs)
+    }
+
+    /// Epoch authenticators of the prior epochs currently retained for this
+    /// group, ordered by increasing epoch id. Prior epochs stored without
+    /// their authenticator are skipped.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn prior_epoch_authenticators(&self) -> Result<Vec<(u64, Vec<u8>)>, MlsError> {
+        Ok(self
+            .prior_epochs()
+            .await?
+            .into_iter()
+            .filter(|epoch| !epoch.authenticator.is_empty())
+            .map(|epoch| (epoch.epoch_id(), epoch.authenticator.to_vec()))
+            .collect())
+    }
+
     fn find_pending(&self, epoch_id: u64) -> Option<usize> {
         self.pending_commit
             .updates
@@ -266,6 +375,7 @@ mod tests {
                 .unwrap(),
             InMemoryKeyPackageStorage::default(),
             None,
+            None,
         )
         .unwrap()
     }
@@ -561,6 +671,7 @@ mod tests {
             InMemoryGroupStateStorage::new(),
             key_package_repo,
             Some(key_package.reference.clone()),
+            None,
         )
         .unwrap();
 
@@ -570,4 +681,41 @@ mod tests {
 
         assert!(repo.key_package_repo.get(&key_package.reference).is_none());
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn prior_epochs_stored_without_authenticator_are_skipped() {
+        let mut test_repo = test_group_state_repo(3);
+
+        let legacy = test_epoch(0);
+        let mut data = Vec::new();
+        legacy.context.mls_encode(&mut data).unwrap();
+        legacy.self_index.mls_encode(&mut data).unwrap();
+        legacy.secrets.mls_encode(&mut data).unwrap();
+        legacy.signature_public_keys.mls_encode(&mut data).unwrap();
+
+        let decoded = PriorEpoch::mls_decode(&mut &*data).unwrap();
+        assert!(decoded.authenticator.is_empty());
+
+        test_repo
+            .storage
+            .write(
+                GroupState {
+                    id: TEST_GROUP.to_vec(),
+                    data: vec![],
+                },
+                vec![EpochRecord::new(0, data)],
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        test_repo.insert(test_epoch(1)).await.unwrap();
+
+        let authenticators = test_repo.prior_epoch_authenticators().await.unwrap();
+
+        assert_eq!(
+            authenticators,
+            vec![(1, test_epoch(1).authenticator.to_vec())]
+        );
+    }
 }
@@ -65,6 +65,15 @@ where
 
         Ok(())
     }
+
+    /// Remove all data stored for the group with `group_id`.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn delete_group(&mut self, group_id: &[u8]) -> Result<bool, MlsError> {
+        self.storage
+            .delete_group(group_id)
+            .await
+            .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))
+    }
 }
 
 #[cfg(test)]
@@ -471,6 +471,15 @@ impl MessageProcessor for GroupWithoutKeySchedule {
         self.inner.psk_storage()
     }
 
+    #[cfg(feature = "by_ref_proposal")]
+    fn proposal_ref_label(&self) -> Vec<u8> {
+        self.inner.proposal_ref_label()
+    }
+
+    fn current_time(&self) -> Option<MlsTime> {
+        self.inner.current_time()
+    }
+
     fn removal_proposal(
         &self,
         provisional_state: &ProvisionalState,
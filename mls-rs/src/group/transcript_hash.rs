@@ -15,11 +15,14 @@ use mls_rs_core::{
 
 use crate::{
     client::MlsError,
-    group::{framing::FramedContent, MessageSignature},
-    WireFormat,
+    group::{
+        framing::{Content, FramedContent},
+        MessageSignature,
+    },
+    MlsMessage, WireFormat,
 };
 
-use super::{AuthenticatedContent, ConfirmationTag};
+use super::{transcript_hashes, AuthenticatedContent, ConfirmationTag};
 
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 pub(crate) async fn create<P: CipherSuiteProvider>(
@@ -105,6 +108,45 @@ impl InterimTranscriptHash {
     }
 }
 
+/// Replay a sequence of `commits` on top of the `initial_interim` transcript
+/// hash and return the resulting interim and confirmed transcript hashes.
+///
+/// This allows a party that recorded the commits of a group, such as an
+/// auditor, to verify the transcript hash chain independently of the group
+/// state. Commits must be sent as public messages, in the order in which
+/// they were applied.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+pub async fn recompute_transcript_hashes<P: CipherSuiteProvider>(
+    cipher_suite_provider: &P,
+    initial_interim: &[u8],
+    commits: &[MlsMessage],
+) -> Result<(Vec<u8>, ConfirmedTranscriptHash), MlsError> {
+    let mut interim = InterimTranscriptHash::from(initial_interim.to_vec());
+    let mut confirmed = None;
+
+    for commit in commits {
+        let auth_content: AuthenticatedContent = commit
+            .clone()
+            .into_plaintext()
+            .ok_or(MlsError::UnexpectedMessageType)?
+            .into();
+
+        if !matches!(auth_content.content.content, Content::Commit(_)) {
+            return Err(MlsError::UnexpectedMessageType);
+        }
+
+        let (next_interim, next_confirmed) =
+            transcript_hashes(cipher_suite_provider, &interim, &auth_content).await?;
+
+        interim = next_interim;
+        confirmed = Some(next_confirmed);
+    }
+
+    let confirmed = confirmed.ok_or(MlsError::CommitRequired)?;
+
+    Ok((interim.0, confirmed))
+}
+
 // Test vectors come from the MLS interop repository and contain a proposal by reference.
 #[cfg(feature = "by_ref_proposal")]
 #[cfg(test)]
@@ -138,6 +180,13 @@ mod tests {
     #[cfg(not(mls_build_async))]
     use super::InterimTranscriptHash;
 
+    use crate::{
+        client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        group::test_utils::test_group,
+    };
+
+    use super::recompute_transcript_hashes;
+
     #[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
     struct TestCase {
         pub cipher_suite: u16,
@@ -257,4 +306,29 @@ mod tests {
     fn generate_test_vector() -> Vec<TestCase> {
         panic!("Tests cannot be generated in async mode");
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn replaying_commit_history_reproduces_transcript_hashes() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let initial_interim = alice.state.interim_transcript_hash.to_vec();
+
+        let mut commits = Vec::new();
+
+        let (_bob, commit) = alice.join("bob").await;
+        commits.push(commit);
+
+        for _ in 0..2 {
+            let commit = alice.commit(Vec::new()).await.unwrap().commit_message;
+            alice.apply_pending_commit().await.unwrap();
+            commits.push(commit);
+        }
+
+        let (interim, confirmed) =
+            recompute_transcript_hashes(&alice.cipher_suite_provider, &initial_interim, &commits)
+                .await
+                .unwrap();
+
+        assert_eq!(confirmed, alice.context().confirmed_transcript_hash);
+        assert_eq!(interim, alice.state.interim_transcript_hash.to_vec());
+    }
 }
@@ -11,7 +11,7 @@ use mls_rs_core::{
 use crate::{
     cipher_suite::CipherSuite,
     client::MlsError,
-    extension::RatchetTreeExt,
+    extension::{ExtensionType, RatchetTreeExt},
     key_package::KeyPackageGeneration,
     protocol_version::ProtocolVersion,
     signer::Signable,
@@ -81,10 +81,12 @@ pub(crate) async fn validate_tree_and_info_joiner<C: CipherSuiteProvider, I: Ide
     msg_version: ProtocolVersion,
     group_info: &GroupInfo,
     tree: Option<ExportedTree<'_>>,
+    max_tree_size: Option<usize>,
     id_provider: &I,
     cs: &C,
 ) -> Result<TreeKemPublic, MlsError> {
-    let public_tree = validate_tree_joiner(group_info, tree, id_provider, cs).await?;
+    let public_tree =
+        validate_tree_joiner(group_info, tree, max_tree_size, id_provider, cs).await?;
 
     let signer = &public_tree
         .get_leaf_node(group_info.signer)?
@@ -99,9 +101,23 @@ pub(crate) async fn validate_tree_and_info_joiner<C: CipherSuiteProvider, I: Ide
 pub(crate) async fn validate_tree_joiner<C: CipherSuiteProvider, I: IdentityProvider>(
     group_info: &GroupInfo,
     tree: Option<ExportedTree<'_>>,
+    max_tree_size: Option<usize>,
     id_provider: &I,
     cs: &C,
 ) -> Result<TreeKemPublic, MlsError> {
+    // Reject oversized embedded trees before decoding them
+    let embedded_tree_size = group_info
+        .extensions
+        .iter()
+        .find(|ext| ext.extension_type == ExtensionType::RATCHET_TREE)
+        .map(|ext| ext.extension_data.len());
+
+    if let Some((size, max_size)) = embedded_tree_size.zip(max_tree_size) {
+        if size > max_size {
+            return Err(MlsError::RatchetTreeTooLarge(size));
+        }
+    }
+
     let tree = match group_info.extensions.get_as::<RatchetTreeExt>()? {
         Some(ext) => ext.tree_data,
         None => tree.ok_or(MlsError::RatchetTreeNotFound)?,
@@ -5,6 +5,9 @@
 /// Basic credential identity provider.
 pub mod basic;
 
+/// Identity provider built from plain callbacks.
+pub mod callback;
+
 /// X.509 certificate identity provider.
 #[cfg(feature = "x509")]
 pub mod x509 {
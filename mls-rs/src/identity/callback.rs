@@ -0,0 +1,233 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+
+#[cfg(not(target_has_atomic = "ptr"))]
+use portable_atomic_util::Arc;
+
+#[cfg(mls_build_async)]
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use mls_rs_core::{
+    error::IntoAnyError,
+    extension::ExtensionList,
+    identity::{CredentialType, IdentityProvider, MemberValidationContext, SigningIdentity},
+    time::MlsTime,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[cfg_attr(feature = "std", error("identity callback failed: {0}"))]
+/// Error returned by the callbacks of a [`CallbackIdentityProvider`].
+pub struct CallbackIdentityError(pub String);
+
+impl IntoAnyError for CallbackIdentityError {
+    #[cfg(feature = "std")]
+    fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+        Ok(self.into())
+    }
+}
+
+type ValidateFn =
+    dyn Fn(&SigningIdentity, Option<MlsTime>) -> Result<(), CallbackIdentityError> + Send + Sync;
+
+type IdentityFn = dyn Fn(&SigningIdentity) -> Result<Vec<u8>, CallbackIdentityError> + Send + Sync;
+
+type SuccessorFn =
+    dyn Fn(&SigningIdentity, &SigningIdentity) -> Result<bool, CallbackIdentityError> + Send + Sync;
+
+/// [`IdentityProvider`] built from plain synchronous callbacks.
+///
+/// This is intended for language bindings, where implementing the generic
+/// and possibly async [`IdentityProvider`] trait is impractical. The
+/// callbacks can be function pointers or closures. They do not receive the
+/// group context extensions.
+///
+/// In async builds the callbacks are called directly from the async trait
+/// methods, so they should return quickly.
+#[derive(Clone)]
+pub struct CallbackIdentityProvider {
+    validate: Arc<ValidateFn>,
+    identity: Arc<IdentityFn>,
+    valid_successor: Option<Arc<SuccessorFn>>,
+    supported_types: Vec<CredentialType>,
+}
+
+impl CallbackIdentityProvider {
+    /// Create a provider supporting `supported_types`.
+    ///
+    /// `validate` is used to validate both members and external senders, and
+    /// `identity` returns the unique identifier of a signing identity.
+    pub fn new<V, I>(supported_types: Vec<CredentialType>, validate: V, identity: I) -> Self
+    where
+        V: Fn(&SigningIdentity, Option<MlsTime>) -> Result<(), CallbackIdentityError>
+            + Send
+            + Sync
+            + 'static,
+        I: Fn(&SigningIdentity) -> Result<Vec<u8>, CallbackIdentityError> + Send + Sync + 'static,
+    {
+        Self {
+            validate: Arc::new(validate),
+            identity: Arc::new(identity),
+            valid_successor: None,
+            supported_types,
+        }
+    }
+
+    /// Set the callback deciding if a successor can remove its predecessor
+    /// as part of an external commit.
+    ///
+    /// By default, this is allowed if both have the same identity.
+    pub fn with_valid_successor<S>(self, valid_successor: S) -> Self
+    where
+        S: Fn(&SigningIdentity, &SigningIdentity) -> Result<bool, CallbackIdentityError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self {
+            valid_successor: Some(Arc::new(valid_successor)),
+            ..self
+        }
+    }
+}
+
+impl Debug for CallbackIdentityProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackIdentityProvider")
+            .field("supported_types", &self.supported_types)
+            .finish()
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl IdentityProvider for CallbackIdentityProvider {
+    type Error = CallbackIdentityError;
+
+    async fn validate_member(
+        &self,
+        signing_identity: &SigningIdentity,
+        timestamp: Option<MlsTime>,
+        _context: MemberValidationContext<'_>,
+    ) -> Result<(), Self::Error> {
+        (self.validate)(signing_identity, timestamp)
+    }
+
+    async fn validate_external_sender(
+        &self,
+        signing_identity: &SigningIdentity,
+        timestamp: Option<MlsTime>,
+        _extensions: Option<&ExtensionList>,
+    ) -> Result<(), Self::Error> {
+        (self.validate)(signing_identity, timestamp)
+    }
+
+    async fn identity(
+        &self,
+        signing_identity: &SigningIdentity,
+        _extensions: &ExtensionList,
+    ) -> Result<Vec<u8>, Self::Error> {
+        (self.identity)(signing_identity)
+    }
+
+    async fn valid_successor(
+        &self,
+        predecessor: &SigningIdentity,
+        successor: &SigningIdentity,
+        _extensions: &ExtensionList,
+    ) -> Result<bool, Self::Error> {
+        match &self.valid_successor {
+            Some(valid_successor) => valid_successor(predecessor, successor),
+            None => Ok((self.identity)(predecessor)? == (self.identity)(successor)?),
+        }
+    }
+
+    fn supported_types(&self) -> Vec<CredentialType> {
+        self.supported_types.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+    use assert_matches::assert_matches;
+    use mls_rs_core::identity::{BasicCredential, SigningIdentity};
+
+    use crate::{
+        client::{
+            test_utils::{
+                test_client_with_key_pkg, TestClientBuilder, TEST_CIPHER_SUITE,
+                TEST_PROTOCOL_VERSION,
+            },
+            MlsError,
+        },
+        identity::test_utils::get_test_signing_identity,
+    };
+
+    use super::{CallbackIdentityError, CallbackIdentityProvider};
+
+    fn basic_identity(signing_identity: &SigningIdentity) -> Result<&[u8], CallbackIdentityError> {
+        signing_identity
+            .credential
+            .as_basic()
+            .map(|c| &c.identifier[..])
+            .ok_or_else(|| CallbackIdentityError("not a basic credential".to_string()))
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn callback_provider_drives_group_add() {
+        let provider = CallbackIdentityProvider::new(
+            vec![BasicCredential::credential_type()],
+            |signing_identity, _| match basic_identity(signing_identity)? {
+                b"mallory" => Err(CallbackIdentityError("banned".to_string())),
+                _ => Ok(()),
+            },
+            |signing_identity| basic_identity(signing_identity).map(<[u8]>::to_vec),
+        );
+
+        let (alice_identity, secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let alice = TestClientBuilder::new_for_test()
+            .identity_provider(provider)
+            .signing_identity(alice_identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let mut group = alice
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let (_, bob) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        group
+            .commit_builder()
+            .add_member(bob)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        group.apply_pending_commit().await.unwrap();
+        assert_eq!(group.roster().members().len(), 2);
+
+        let (_, mallory) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "mallory").await;
+
+        let res = group.commit_builder().add_member(mallory);
+
+        let res = match res {
+            Ok(builder) => builder.build().await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+
+        assert_matches!(res, Err(MlsError::IdentityProviderError(_)));
+    }
+}
@@ -0,0 +1,339 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use mls_rs_core::{
+    crypto::{CryptoProvider, SignatureSecretKey},
+    error::IntoAnyError,
+    identity::{IdentityProvider, MemberValidationContext},
+    time::Clock,
+};
+
+use crate::{
+    cipher_suite::CipherSuite,
+    client::MlsError,
+    client_config::ClockRef,
+    identity::{basic::BasicIdentityProvider, SigningIdentity},
+    protocol_version::ProtocolVersion,
+    time::system_time,
+    tree_kem::{Capabilities, Lifetime},
+    ExtensionList,
+};
+
+const DEFAULT_LIFETIME_IN_S: u64 = 365 * 24 * 3600;
+
+use super::{KeyPackageGeneration, KeyPackageGenerator};
+
+/// Builder used to generate a [`KeyPackage`](crate::KeyPackage) without
+/// creating a [`Client`](crate::Client).
+///
+/// The secret keys of the key package are returned as part of the resulting
+/// [`KeyPackageGeneration`] and are not stored anywhere. They can be exported
+/// with [`KeyPackageGeneration::to_storage`] and inserted in the
+/// [`KeyPackageStorage`](crate::KeyPackageStorage) of the client that will
+/// later join a group using this key package.
+#[derive(Clone, Debug)]
+pub struct KeyPackageBuilder<C, I = BasicIdentityProvider> {
+    crypto_provider: C,
+    identity_provider: Option<I>,
+    signing_identity: Option<SigningIdentity>,
+    signing_key: Option<SignatureSecretKey>,
+    cipher_suite: Option<CipherSuite>,
+    protocol_version: ProtocolVersion,
+    lifetime: Option<Lifetime>,
+    clock: Option<ClockRef>,
+    capabilities: Capabilities,
+    leaf_node_extensions: ExtensionList,
+    key_package_extensions: ExtensionList,
+}
+
+impl<C: CryptoProvider> KeyPackageBuilder<C> {
+    /// Create a new builder using `crypto_provider` to generate keys.
+    pub fn new(crypto_provider: C) -> Self {
+        Self {
+            crypto_provider,
+            identity_provider: None,
+            signing_identity: None,
+            signing_key: None,
+            cipher_suite: None,
+            protocol_version: ProtocolVersion::MLS_10,
+            lifetime: None,
+            clock: None,
+            capabilities: Default::default(),
+            leaf_node_extensions: Default::default(),
+            key_package_extensions: Default::default(),
+        }
+    }
+}
+
+impl<C, I> KeyPackageBuilder<C, I>
+where
+    C: CryptoProvider,
+    I: IdentityProvider,
+{
+    /// Set the identity provider used to validate the signing identity before
+    /// generating the key package. No validation is performed by default.
+    pub fn identity_provider<Ip>(self, identity_provider: Ip) -> KeyPackageBuilder<C, Ip>
+    where
+        Ip: IdentityProvider,
+    {
+        KeyPackageBuilder {
+            crypto_provider: self.crypto_provider,
+            identity_provider: Some(identity_provider),
+            signing_identity: self.signing_identity,
+            signing_key: self.signing_key,
+            cipher_suite: self.cipher_suite,
+            protocol_version: self.protocol_version,
+            lifetime: self.lifetime,
+            clock: self.clock,
+            capabilities: self.capabilities,
+            leaf_node_extensions: self.leaf_node_extensions,
+            key_package_extensions: self.key_package_extensions,
+        }
+    }
+
+    /// Set the signing identity of the key package. This must be called.
+    pub fn signing_identity(self, signing_identity: SigningIdentity) -> Self {
+        Self {
+            signing_identity: Some(signing_identity),
+            ..self
+        }
+    }
+
+    /// Set the key used to sign the key package. This must be called.
+    pub fn signing_key(self, signing_key: SignatureSecretKey) -> Self {
+        Self {
+            signing_key: Some(signing_key),
+            ..self
+        }
+    }
+
+    /// Set the cipher suite of the key package. This must be called.
+    pub fn cipher_suite(self, cipher_suite: CipherSuite) -> Self {
+        Self {
+            cipher_suite: Some(cipher_suite),
+            ..self
+        }
+    }
+
+    /// Set the protocol version of the key package. Defaults to
+    /// [`ProtocolVersion::MLS_10`].
+    pub fn protocol_version(self, protocol_version: ProtocolVersion) -> Self {
+        Self {
+            protocol_version,
+            ..self
+        }
+    }
+
+    /// Set the lifetime of the key package. Defaults to one year starting at
+    /// the time of generation.
+    pub fn lifetime(self, lifetime: Lifetime) -> Self {
+        Self {
+            lifetime: Some(lifetime),
+            ..self
+        }
+    }
+
+    /// Set the [`Clock`] used to compute the default lifetime of the key
+    /// package. The system clock is used by default.
+    pub fn clock<T>(self, clock: T) -> Self
+    where
+        T: Clock + Clone + 'static,
+    {
+        Self {
+            clock: Some(ClockRef::new(clock)),
+            ..self
+        }
+    }
+
+    /// Set the capabilities advertised by the leaf node of the key package.
+    pub fn capabilities(self, capabilities: Capabilities) -> Self {
+        Self {
+            capabilities,
+            ..self
+        }
+    }
+
+    /// Set the extensions of the leaf node of the key package.
+    pub fn leaf_extensions(self, leaf_node_extensions: ExtensionList) -> Self {
+        Self {
+            leaf_node_extensions,
+            ..self
+        }
+    }
+
+    /// Set the extensions of the key package.
+    pub fn key_package_extensions(self, key_package_extensions: ExtensionList) -> Self {
+        Self {
+            key_package_extensions,
+            ..self
+        }
+    }
+
+    /// Generate a new key package along with its secret keys.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn build(self) -> Result<KeyPackageGeneration, MlsError> {
+        let signing_identity = self
+            .signing_identity
+            .ok_or(MlsError::SigningIdentityNotSet)?;
+
+        let signing_key = self.signing_key.ok_or(MlsError::SigningKeyNotSet)?;
+        let cipher_suite = self.cipher_suite.ok_or(MlsError::CipherSuiteNotSet)?;
+
+        let cipher_suite_provider = self
+            .crypto_provider
+            .cipher_suite_provider(cipher_suite)
+            .ok_or(MlsError::UnsupportedCipherSuite(cipher_suite))?;
+
+        if let Some(identity_provider) = &self.identity_provider {
+            identity_provider
+                .validate_member(&signing_identity, None, MemberValidationContext::None)
+                .await
+                .map_err(|e| MlsError::IdentityProviderError(e.into_any_error()))?;
+        }
+
+        let lifetime = match (self.lifetime, self.clock.map(|clock| clock.now())) {
+            (Some(lifetime), _) => lifetime,
+            (None, Some(now)) => Lifetime::seconds_from(now, DEFAULT_LIFETIME_IN_S)?,
+            (None, None) => match system_time() {
+                Some(now) => Lifetime::seconds_from(now, DEFAULT_LIFETIME_IN_S)?,
+                None => Lifetime::seconds(DEFAULT_LIFETIME_IN_S)?,
+            },
+        };
+
+        let generator = KeyPackageGenerator {
+            protocol_version: self.protocol_version,
+            cipher_suite_provider: &cipher_suite_provider,
+            signing_identity: &signing_identity,
+            signing_key: &signing_key,
+        };
+
+        generator
+            .generate(
+                lifetime,
+                self.capabilities,
+                self.key_package_extensions,
+                self.leaf_node_extensions,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use crate::{
+        client::{
+            test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+            MlsError,
+        },
+        crypto::test_utils::{test_cipher_suite_provider, TestCryptoProvider},
+        identity::{basic::BasicIdentityProvider, test_utils::get_test_signing_identity},
+        key_package::validate_key_package_properties,
+        time::{Clock, MlsTime},
+        tree_kem::{
+            leaf_node_validator::{LeafNodeValidator, ValidationContext},
+            Lifetime,
+        },
+        LeafNodeSource,
+    };
+
+    use super::KeyPackageBuilder;
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_package_builder_generates_valid_key_package() {
+        let (signing_identity, signing_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let generated = KeyPackageBuilder::new(TestCryptoProvider::new())
+            .identity_provider(BasicIdentityProvider)
+            .signing_identity(signing_identity.clone())
+            .signing_key(signing_key)
+            .cipher_suite(TEST_CIPHER_SUITE)
+            .protocol_version(TEST_PROTOCOL_VERSION)
+            .build()
+            .await
+            .unwrap();
+
+        let cipher_suite_provider = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        validate_key_package_properties(
+            generated.key_package(),
+            TEST_PROTOCOL_VERSION,
+            &cipher_suite_provider,
+        )
+        .await
+        .unwrap();
+
+        LeafNodeValidator::new_for_test(&cipher_suite_provider, &BasicIdentityProvider)
+            .check_if_valid(
+                &generated.key_package().leaf_node,
+                ValidationContext::Add(None),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            generated.key_package().signing_identity(),
+            &signing_identity
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_package_builder_requires_signer() {
+        let (signing_identity, signing_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let res = KeyPackageBuilder::new(TestCryptoProvider::new())
+            .cipher_suite(TEST_CIPHER_SUITE)
+            .signing_key(signing_key)
+            .build()
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::SigningIdentityNotSet));
+
+        let res = KeyPackageBuilder::new(TestCryptoProvider::new())
+            .cipher_suite(TEST_CIPHER_SUITE)
+            .signing_identity(signing_identity)
+            .build()
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::SigningKeyNotSet));
+    }
+
+    #[derive(Clone)]
+    struct FixedClock(MlsTime);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> MlsTime {
+            self.0
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_package_builder_default_lifetime_uses_clock() {
+        let (signing_identity, signing_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let now = MlsTime::from(1_000_000);
+
+        let generated = KeyPackageBuilder::new(TestCryptoProvider::new())
+            .signing_identity(signing_identity)
+            .signing_key(signing_key)
+            .cipher_suite(TEST_CIPHER_SUITE)
+            .clock(FixedClock(now))
+            .build()
+            .await
+            .unwrap();
+
+        let expected = Lifetime::seconds_from(now, super::DEFAULT_LIFETIME_IN_S).unwrap();
+
+        assert_matches!(
+            &generated.key_package().leaf_node.leaf_node_source,
+            LeafNodeSource::KeyPackage(lifetime) if lifetime == &expected
+        );
+    }
+}
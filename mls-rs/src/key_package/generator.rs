@@ -43,6 +43,16 @@ pub struct KeyPackageGeneration {
 }
 
 impl KeyPackageGeneration {
+    /// The generated key package.
+    pub fn key_package(&self) -> &KeyPackage {
+        &self.key_package
+    }
+
+    /// Reference of the generated key package.
+    pub fn reference(&self) -> &KeyPackageRef {
+        &self.reference
+    }
+
     pub fn to_storage(&self) -> Result<(Vec<u8>, KeyPackageData), MlsError> {
         let id = self.reference.to_vec();
 
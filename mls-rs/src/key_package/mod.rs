@@ -5,10 +5,12 @@
 use crate::cipher_suite::CipherSuite;
 use crate::client::MlsError;
 use crate::crypto::HpkePublicKey;
+use crate::extension::RequiredCapabilitiesExt;
 use crate::hash_reference::HashReference;
 use crate::identity::SigningIdentity;
 use crate::protocol_version::ProtocolVersion;
 use crate::signer::Signable;
+use crate::time::MlsTime;
 use crate::tree_kem::leaf_node::{LeafNode, LeafNodeSource};
 use crate::CipherSuiteProvider;
 use alloc::vec::Vec;
@@ -25,10 +27,15 @@ mod validator;
 pub(crate) use validator::*;
 
 pub(crate) mod generator;
+pub use generator::KeyPackageGeneration;
 pub(crate) use generator::*;
 
+mod builder;
+pub use builder::KeyPackageBuilder;
+
 #[non_exhaustive]
 #[derive(Clone, MlsSize, MlsEncode, MlsDecode, PartialEq)]
+#[mls_codec(error_context)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(
     all(feature = "ffi", not(test)),
@@ -130,6 +137,75 @@ impl KeyPackage {
         ))
     }
 
+    /// Check if this key package can be used to add a member to a group using
+    /// `cipher_suite`, `protocol_version` and `group_context_extensions`.
+    ///
+    /// An error describing the first incompatibility found is returned if the
+    /// cipher suite or protocol version do not match, or if the leaf node does not
+    /// support the group context extensions or the capabilities required by the
+    /// group. Signatures and credentials are not verified.
+    pub fn check_compatibility(
+        &self,
+        cipher_suite: CipherSuite,
+        protocol_version: ProtocolVersion,
+        group_context_extensions: &ExtensionList,
+    ) -> Result<(), MlsError> {
+        if self.cipher_suite != cipher_suite {
+            return Err(MlsError::CipherSuiteMismatch);
+        }
+
+        if self.version != protocol_version {
+            return Err(MlsError::ProtocolVersionMismatch);
+        }
+
+        let capabilities = &self.leaf_node.capabilities;
+
+        if let Some(required) = group_context_extensions.get_as::<RequiredCapabilitiesExt>()? {
+            if let Some(ext) = required
+                .extensions
+                .iter()
+                .find(|ext| !capabilities.extensions.contains(ext))
+            {
+                return Err(MlsError::RequiredExtensionNotFound(*ext));
+            }
+
+            if let Some(proposal) = required
+                .proposals
+                .iter()
+                .find(|proposal| !capabilities.proposals.contains(proposal))
+            {
+                return Err(MlsError::RequiredProposalNotFound(*proposal));
+            }
+
+            if let Some(credential) = required
+                .credentials
+                .iter()
+                .find(|credential| !capabilities.credentials.contains(credential))
+            {
+                return Err(MlsError::RequiredCredentialNotFound(*credential));
+            }
+        }
+
+        group_context_extensions
+            .iter()
+            .map(|ext| ext.extension_type)
+            .find(|ext_type| !ext_type.is_default() && !capabilities.extensions.contains(ext_type))
+            .map_or(Ok(()), |ext_type| {
+                Err(MlsError::UnsupportedGroupExtension(ext_type))
+            })
+    }
+
+    /// Returns true if [`KeyPackage::check_compatibility`] succeeds.
+    pub fn is_compatible_with(
+        &self,
+        cipher_suite: CipherSuite,
+        protocol_version: ProtocolVersion,
+        group_context_extensions: &ExtensionList,
+    ) -> bool {
+        self.check_compatibility(cipher_suite, protocol_version, group_context_extensions)
+            .is_ok()
+    }
+
     pub fn expiration(&self) -> Result<u64, MlsError> {
         if let LeafNodeSource::KeyPackage(lifetime) = &self.leaf_node.leaf_node_source {
             Ok(lifetime.not_after)
@@ -137,6 +213,22 @@ impl KeyPackage {
             Err(MlsError::InvalidLeafNodeSource)
         }
     }
+
+    /// The `not_before` and `not_after` times of the lifetime of this key package.
+    ///
+    /// This can be used to schedule generating a new key package before this one
+    /// expires. Returns `None` if the leaf node source is not a key package, in
+    /// which case the leaf node has no lifetime.
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn validity_window(&self) -> Option<(MlsTime, MlsTime)> {
+        match &self.leaf_node.leaf_node_source {
+            LeafNodeSource::KeyPackage(lifetime) => Some((
+                MlsTime::from(lifetime.not_before),
+                MlsTime::from(lifetime.not_after),
+            )),
+            _ => None,
+        }
+    }
 }
 
 impl Signable<'_> for KeyPackage {
@@ -239,8 +331,9 @@ pub(crate) mod test_utils {
 #[cfg(test)]
 mod tests {
     use crate::{
-        client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        client::test_utils::{TestClientBuilder, TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
         crypto::test_utils::{test_cipher_suite_provider, try_test_cipher_suite_provider},
+        identity::test_utils::get_test_signing_identity,
     };
 
     use super::{test_utils::test_key_package, *};
@@ -327,4 +420,97 @@ mod tests {
             }
         }
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_package_compatibility() {
+        let key_package = test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "test").await;
+        let extensions = ExtensionList::new();
+
+        assert!(key_package.is_compatible_with(
+            TEST_CIPHER_SUITE,
+            TEST_PROTOCOL_VERSION,
+            &extensions
+        ));
+
+        let other_cipher_suite = CipherSuite::all()
+            .find(|cs| cs != &TEST_CIPHER_SUITE)
+            .unwrap();
+
+        let res =
+            key_package.check_compatibility(other_cipher_suite, TEST_PROTOCOL_VERSION, &extensions);
+
+        assert_matches!(res, Err(MlsError::CipherSuiteMismatch));
+
+        let mut required = ExtensionList::new();
+
+        required
+            .set_from(RequiredCapabilitiesExt {
+                extensions: vec![999.into()],
+                ..Default::default()
+            })
+            .unwrap();
+
+        let res =
+            key_package.check_compatibility(TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION, &required);
+
+        assert_matches!(res, Err(MlsError::RequiredExtensionNotFound(ext)) if ext == 999.into());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn validity_window_spans_configured_lifetime() {
+        let lifetime = 7 * 24 * 3600;
+
+        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"test").await;
+
+        let client = TestClientBuilder::new_for_test()
+            .key_package_lifetime(lifetime)
+            .signing_identity(identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let mut key_package = client
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap()
+            .into_key_package()
+            .unwrap();
+
+        let (not_before, not_after) = key_package.validity_window().unwrap();
+
+        assert_eq!(
+            not_after.seconds_since_epoch() - not_before.seconds_since_epoch(),
+            lifetime
+        );
+
+        assert_eq!(
+            not_after.seconds_since_epoch(),
+            key_package.expiration().unwrap()
+        );
+
+        key_package.leaf_node.leaf_node_source = LeafNodeSource::Update;
+
+        assert_eq!(key_package.validity_window(), None);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn truncated_key_package_error_names_field() {
+        let key_package = test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "test").await;
+        let encoded = key_package.mls_encode_to_vec().unwrap();
+
+        // Cut the message a few bytes into the leaf node
+        let leaf_node_start = key_package.version.mls_encoded_len()
+            + key_package.cipher_suite.mls_encoded_len()
+            + key_package.hpke_init_key.mls_encoded_len();
+
+        let res = KeyPackage::mls_decode(&mut &encoded[..leaf_node_start + 5]);
+
+        assert_matches!(
+            res,
+            Err(mls_rs_codec::Error::Context { location: "KeyPackage.leaf_node", ref source })
+                if matches!(source.root_cause(), mls_rs_codec::Error::UnexpectedEOF)
+        );
+
+        let err: MlsError = res.unwrap_err().into();
+
+        assert!(format!("{err}").contains("failed to deserialize KeyPackage.leaf_node"));
+    }
 }
@@ -151,6 +151,8 @@ mod key_package;
 pub(crate) mod map;
 /// Pre-shared key support.
 pub mod psk;
+/// Retry policies for identity providers and PSK stores.
+pub mod retry;
 mod signer;
 /// Storage providers to use with
 /// [`ClientBuilder`](client_builder::ClientBuilder).
@@ -168,9 +170,10 @@ pub use mls_rs_core::{
 pub mod mls_rules {
     pub use crate::group::{
         mls_rules::{
-            CommitDirection, CommitOptions, CommitSource, DefaultMlsRules, EncryptionOptions,
+            CapabilityMonotonicityRules, CommitDirection, CommitOptions, CommitSource,
+            DefaultMlsRules, EncryptionOptions,
         },
-        proposal_filter::{ProposalBundle, ProposalInfo, ProposalSource},
+        proposal_filter::{EffectsPreview, ProposalBundle, ProposalInfo, ProposalSource},
     };
 
     #[cfg(feature = "by_ref_proposal")]
@@ -186,7 +189,8 @@ pub use crate::{
         mls_rules::MlsRules,
         Group,
     },
-    key_package::{KeyPackage, KeyPackageRef},
+    key_package::{KeyPackage, KeyPackageBuilder, KeyPackageGeneration, KeyPackageRef},
+    tree_kem::leaf_node::{LeafNode, LeafNodeSource},
 };
 
 /// Error types.
@@ -198,7 +202,17 @@ pub mod error {
 
 /// WASM compatible timestamp.
 pub mod time {
+    pub use crate::client_config::ClockRef;
     pub use mls_rs_core::time::*;
+
+    /// Current system time, if a system clock is available.
+    pub(crate) fn system_time() -> Option<MlsTime> {
+        #[cfg(feature = "std")]
+        return Some(MlsTime::now());
+
+        #[cfg(not(feature = "std"))]
+        None
+    }
 }
 
 mod tree_kem;
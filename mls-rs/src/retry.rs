@@ -0,0 +1,571 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+#[cfg(mls_build_async)]
+use alloc::boxed::Box;
+use alloc::{sync::Arc, vec::Vec};
+use core::{
+    fmt::{self, Debug},
+    ops::{Deref, DerefMut},
+};
+use mls_rs_core::{
+    error::IntoAnyError,
+    extension::ExtensionList,
+    identity::{CredentialType, IdentityProvider, MemberValidationContext, SigningIdentity},
+    psk::{ExternalPskId, PreSharedKey, PreSharedKeyStorage},
+    time::MlsTime,
+};
+
+/// Policy deciding if a failed call to a provider should be attempted again.
+///
+/// See [`ClientBuilder::retry_policy`](crate::client_builder::ClientBuilder::retry_policy).
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+pub trait RetryPolicy: Send + Sync {
+    /// Called after the `attempt`-th call (starting at 1) to a provider
+    /// failed. `is_transient` is the classification of the error returned
+    /// by [`IntoAnyError::is_transient`]. Returning `true` makes the call
+    /// again, returning `false` surfaces the error of the last attempt.
+    ///
+    /// Implementations can wait before returning in order to back off.
+    async fn should_retry(&self, attempt: u32, is_transient: bool) -> bool;
+}
+
+/// Policy that never retries a failed call.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoRetry;
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl RetryPolicy for NoRetry {
+    async fn should_retry(&self, _attempt: u32, _is_transient: bool) -> bool {
+        false
+    }
+}
+
+/// Policy that makes a call failing with a transient error at most
+/// `max_attempts` times in total, without waiting between attempts.
+#[derive(Clone, Copy, Debug)]
+pub struct MaxAttempts(pub u32);
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl RetryPolicy for MaxAttempts {
+    async fn should_retry(&self, attempt: u32, is_transient: bool) -> bool {
+        is_transient && attempt < self.0
+    }
+}
+
+/// Type-erased [`RetryPolicy`] stored in the client configuration, set with
+/// [`ClientBuilder::retry_policy`](crate::client_builder::ClientBuilder::retry_policy).
+#[derive(Clone)]
+pub struct RetryPolicyRef(Arc<dyn RetryPolicy>);
+
+impl RetryPolicyRef {
+    pub(crate) fn new<R: RetryPolicy + 'static>(policy: R) -> Self {
+        Self(Arc::new(policy))
+    }
+}
+
+impl Default for RetryPolicyRef {
+    fn default() -> Self {
+        Self::new(NoRetry)
+    }
+}
+
+impl Debug for RetryPolicyRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicyRef").finish_non_exhaustive()
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl RetryPolicy for RetryPolicyRef {
+    async fn should_retry(&self, attempt: u32, is_transient: bool) -> bool {
+        self.0.should_retry(attempt, is_transient).await
+    }
+}
+
+#[cfg(mls_build_async)]
+macro_rules! retry {
+    ($policy:expr, $call:expr) => {{
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+
+            match $call.await {
+                Err(e) => {
+                    let is_transient = e.is_transient();
+
+                    if !$policy.should_retry(attempt, is_transient).await {
+                        break Err(e);
+                    }
+                }
+                res => break res,
+            }
+        }
+    }};
+}
+
+#[cfg(not(mls_build_async))]
+macro_rules! retry {
+    ($policy:expr, $call:expr) => {{
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+
+            match $call {
+                Err(e) => {
+                    let is_transient = e.is_transient();
+
+                    if !$policy.should_retry(attempt, is_transient) {
+                        break Err(e);
+                    }
+                }
+                res => break res,
+            }
+        }
+    }};
+}
+
+/// Provider wrapped in a retry layer by the client configuration.
+///
+/// [`Client::to_builder`](crate::Client::to_builder) uses this to recover the
+/// wrapped provider and its policy, so that the rebuilt client has a single
+/// retry layer with the same policy.
+pub trait RetryLayer {
+    type Inner;
+
+    /// Policy applied by this layer.
+    fn policy(&self) -> RetryPolicyRef;
+
+    /// Remove the retry layer, returning the wrapped provider.
+    fn into_inner(self) -> Self::Inner;
+}
+
+/// [`IdentityProvider`] retrying failed calls to the wrapped provider
+/// according to a [`RetryPolicy`].
+#[derive(Clone, Debug)]
+pub struct RetryingIdentityProvider<I, R = RetryPolicyRef> {
+    inner: I,
+    policy: R,
+}
+
+impl<I, R> RetryingIdentityProvider<I, R> {
+    pub fn new(inner: I, policy: R) -> Self {
+        Self { inner, policy }
+    }
+
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<I> RetryLayer for RetryingIdentityProvider<I> {
+    type Inner = I;
+
+    fn policy(&self) -> RetryPolicyRef {
+        self.policy.clone()
+    }
+
+    fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<I, R> Deref for RetryingIdentityProvider<I, R> {
+    type Target = I;
+
+    fn deref(&self) -> &I {
+        &self.inner
+    }
+}
+
+impl<I, R> DerefMut for RetryingIdentityProvider<I, R> {
+    fn deref_mut(&mut self) -> &mut I {
+        &mut self.inner
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl<I, R> IdentityProvider for RetryingIdentityProvider<I, R>
+where
+    I: IdentityProvider,
+    I::Error: Send,
+    R: RetryPolicy,
+{
+    type Error = I::Error;
+
+    async fn validate_member(
+        &self,
+        signing_identity: &SigningIdentity,
+        timestamp: Option<MlsTime>,
+        context: MemberValidationContext<'_>,
+    ) -> Result<(), Self::Error> {
+        retry!(
+            self.policy,
+            self.inner
+                .validate_member(signing_identity, timestamp, context)
+        )
+    }
+
+    async fn validate_external_sender(
+        &self,
+        signing_identity: &SigningIdentity,
+        timestamp: Option<MlsTime>,
+        extensions: Option<&ExtensionList>,
+    ) -> Result<(), Self::Error> {
+        retry!(
+            self.policy,
+            self.inner
+                .validate_external_sender(signing_identity, timestamp, extensions)
+        )
+    }
+
+    async fn identity(
+        &self,
+        signing_identity: &SigningIdentity,
+        extensions: &ExtensionList,
+    ) -> Result<Vec<u8>, Self::Error> {
+        retry!(
+            self.policy,
+            self.inner.identity(signing_identity, extensions)
+        )
+    }
+
+    async fn valid_successor(
+        &self,
+        predecessor: &SigningIdentity,
+        successor: &SigningIdentity,
+        extensions: &ExtensionList,
+    ) -> Result<bool, Self::Error> {
+        retry!(
+            self.policy,
+            self.inner
+                .valid_successor(predecessor, successor, extensions)
+        )
+    }
+
+    fn supported_types(&self) -> Vec<CredentialType> {
+        self.inner.supported_types()
+    }
+}
+
+/// [`PreSharedKeyStorage`] retrying failed lookups of the wrapped storage
+/// according to a [`RetryPolicy`].
+#[derive(Clone, Debug)]
+pub struct RetryingPskStore<P, R = RetryPolicyRef> {
+    inner: P,
+    policy: R,
+}
+
+impl<P, R> RetryingPskStore<P, R> {
+    pub fn new(inner: P, policy: R) -> Self {
+        Self { inner, policy }
+    }
+
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P> RetryLayer for RetryingPskStore<P> {
+    type Inner = P;
+
+    fn policy(&self) -> RetryPolicyRef {
+        self.policy.clone()
+    }
+
+    fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P, R> Deref for RetryingPskStore<P, R> {
+    type Target = P;
+
+    fn deref(&self) -> &P {
+        &self.inner
+    }
+}
+
+impl<P, R> DerefMut for RetryingPskStore<P, R> {
+    fn deref_mut(&mut self) -> &mut P {
+        &mut self.inner
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl<P, R> PreSharedKeyStorage for RetryingPskStore<P, R>
+where
+    P: PreSharedKeyStorage,
+    P::Error: Send,
+    R: RetryPolicy,
+{
+    type Error = P::Error;
+
+    async fn get(&self, id: &ExternalPskId) -> Result<Option<PreSharedKey>, Self::Error> {
+        retry!(self.policy, self.inner.get(id))
+    }
+
+    async fn contains(&self, id: &ExternalPskId) -> Result<bool, Self::Error> {
+        retry!(self.policy, self.inner.contains(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    use crate::{
+        client::test_utils::{TestClientBuilder, TEST_CIPHER_SUITE},
+        client_config::ClientConfig,
+        identity::{basic::BasicIdentityProvider, test_utils::get_test_signing_identity},
+    };
+
+    #[derive(Debug, PartialEq)]
+    enum FlakyError {
+        Unavailable,
+        Rejected,
+    }
+
+    impl IntoAnyError for FlakyError {
+        fn is_transient(&self) -> bool {
+            *self == FlakyError::Unavailable
+        }
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct FlakyIdentityProvider {
+        failures: Arc<AtomicU32>,
+        rejecting: Arc<AtomicBool>,
+        calls: Arc<AtomicU32>,
+    }
+
+    impl FlakyIdentityProvider {
+        fn failing(failures: u32) -> Self {
+            let provider = Self::default();
+            provider.failures.store(failures, Ordering::SeqCst);
+            provider
+        }
+
+        fn rejecting() -> Self {
+            let provider = Self::default();
+            provider.rejecting.store(true, Ordering::SeqCst);
+            provider
+        }
+
+        fn call(&self) -> Result<(), FlakyError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+
+            if self.rejecting.load(Ordering::SeqCst) {
+                return Err(FlakyError::Rejected);
+            }
+
+            let failures = self.failures.load(Ordering::SeqCst);
+
+            if failures > 0 {
+                self.failures.store(failures - 1, Ordering::SeqCst);
+                Err(FlakyError::Unavailable)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl IdentityProvider for FlakyIdentityProvider {
+        type Error = FlakyError;
+
+        async fn validate_member(
+            &self,
+            _: &SigningIdentity,
+            _: Option<MlsTime>,
+            _: MemberValidationContext<'_>,
+        ) -> Result<(), FlakyError> {
+            self.call()
+        }
+
+        async fn validate_external_sender(
+            &self,
+            _: &SigningIdentity,
+            _: Option<MlsTime>,
+            _: Option<&ExtensionList>,
+        ) -> Result<(), FlakyError> {
+            self.call()
+        }
+
+        async fn identity(
+            &self,
+            signing_identity: &SigningIdentity,
+            extensions: &ExtensionList,
+        ) -> Result<Vec<u8>, FlakyError> {
+            self.call()?;
+
+            BasicIdentityProvider
+                .identity(signing_identity, extensions)
+                .await
+                .map_err(|_| FlakyError::Rejected)
+        }
+
+        async fn valid_successor(
+            &self,
+            _: &SigningIdentity,
+            _: &SigningIdentity,
+            _: &ExtensionList,
+        ) -> Result<bool, FlakyError> {
+            self.call().map(|_| true)
+        }
+
+        fn supported_types(&self) -> Vec<CredentialType> {
+            BasicIdentityProvider.supported_types()
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn flaky_provider_succeeds_on_second_attempt() {
+        let (signing_identity, _) = get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let flaky = FlakyIdentityProvider::failing(1);
+        let provider = RetryingIdentityProvider::new(flaky.clone(), MaxAttempts(2));
+
+        let res = provider
+            .validate_member(&signing_identity, None, MemberValidationContext::None)
+            .await;
+
+        assert_matches!(res, Ok(()));
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn valid_successor_is_retried() {
+        let (signing_identity, _) = get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let flaky = FlakyIdentityProvider::failing(1);
+        let provider = RetryingIdentityProvider::new(flaky.clone(), MaxAttempts(2));
+
+        let res = provider
+            .valid_successor(&signing_identity, &signing_identity, &Default::default())
+            .await;
+
+        assert_matches!(res, Ok(true));
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn exhausted_retries_surface_error() {
+        let (signing_identity, _) = get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let flaky = FlakyIdentityProvider::failing(3);
+        let provider = RetryingIdentityProvider::new(flaky.clone(), MaxAttempts(2));
+
+        let res = provider
+            .identity(&signing_identity, &Default::default())
+            .await;
+
+        assert_matches!(res, Err(FlakyError::Unavailable));
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 2);
+
+        let provider = RetryingIdentityProvider::new(flaky.clone(), NoRetry);
+
+        let res = provider
+            .identity(&signing_identity, &Default::default())
+            .await;
+
+        assert_matches!(res, Err(FlakyError::Unavailable));
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn definitive_errors_are_not_retried() {
+        let (signing_identity, _) = get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let rejecting = FlakyIdentityProvider::rejecting();
+        let provider = RetryingIdentityProvider::new(rejecting.clone(), MaxAttempts(5));
+
+        let res = provider
+            .validate_member(&signing_identity, None, MemberValidationContext::None)
+            .await;
+
+        assert_matches!(res, Err(FlakyError::Rejected));
+        assert_eq!(rejecting.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn client_retries_flaky_identity_provider() {
+        let flaky = FlakyIdentityProvider::failing(1);
+
+        let client = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .identity_provider(flaky.clone())
+            .retry_policy(MaxAttempts(2))
+            .build();
+
+        client
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        assert!(flaky.calls.load(Ordering::SeqCst) > 1);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn retry_policy_applies_to_identity_provider_set_afterwards() {
+        let flaky = FlakyIdentityProvider::failing(1);
+
+        let client = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .retry_policy(MaxAttempts(2))
+            .identity_provider(flaky.clone())
+            .build();
+
+        client
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        assert!(flaky.calls.load(Ordering::SeqCst) > 1);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn to_builder_keeps_a_single_retry_layer() {
+        let (signing_identity, _) = get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+        let flaky = FlakyIdentityProvider::failing(3);
+
+        let client = TestClientBuilder::new_for_test()
+            .identity_provider(flaky.clone())
+            .retry_policy(MaxAttempts(2))
+            .build();
+
+        let provider: RetryingIdentityProvider<FlakyIdentityProvider> =
+            client.to_builder().build().config.identity_provider();
+
+        let res = provider
+            .validate_member(&signing_identity, None, MemberValidationContext::None)
+            .await;
+
+        assert_matches!(res, Err(FlakyError::Unavailable));
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 2);
+
+        let res = provider
+            .validate_member(&signing_identity, None, MemberValidationContext::None)
+            .await;
+
+        assert_matches!(res, Ok(()));
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 4);
+    }
+}
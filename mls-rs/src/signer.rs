@@ -8,7 +8,9 @@ use mls_rs_codec::{MlsEncode, MlsSize};
 use mls_rs_core::error::IntoAnyError;
 
 use crate::client::MlsError;
-use crate::crypto::{CipherSuiteProvider, SignaturePublicKey, SignatureSecretKey};
+use crate::crypto::{
+    sign_label_prefix, CipherSuiteProvider, SignaturePublicKey, SignatureSecretKey,
+};
 
 #[derive(Clone, MlsSize, MlsEncode)]
 struct SignContent {
@@ -28,9 +30,9 @@ impl Debug for SignContent {
 }
 
 impl SignContent {
-    pub fn new(label: &str, content: Vec<u8>) -> Self {
+    pub fn new(prefix: &[u8], label: &str, content: Vec<u8>) -> Self {
         Self {
-            label: [b"MLS 1.0 ", label.as_bytes()].concat(),
+            label: [prefix, b"MLS 1.0 ", label.as_bytes()].concat(),
             content,
         }
     }
@@ -62,7 +64,11 @@ pub(crate) trait Signable<'a> {
         signer: &SignatureSecretKey,
         context: &Self::SigningContext,
     ) -> Result<(), MlsError> {
-        let sign_content = SignContent::new(Self::SIGN_LABEL, self.signable_content(context)?);
+        let sign_content = SignContent::new(
+            sign_label_prefix(signature_provider),
+            Self::SIGN_LABEL,
+            self.signable_content(context)?,
+        );
 
         let signature = signature_provider
             .sign(signer, &sign_content.mls_encode_to_vec()?)
@@ -80,7 +86,11 @@ pub(crate) trait Signable<'a> {
         public_key: &SignaturePublicKey,
         context: &Self::SigningContext,
     ) -> Result<(), MlsError> {
-        let sign_content = SignContent::new(Self::SIGN_LABEL, self.signable_content(context)?);
+        let sign_content = SignContent::new(
+            sign_label_prefix(signature_provider),
+            Self::SIGN_LABEL,
+            self.signable_content(context)?,
+        );
 
         signature_provider
             .verify(
@@ -193,6 +193,11 @@ impl GroupStateStorage for InMemoryGroupStateStorage {
             .and_then(|data| data.get_epoch(epoch_id).map(|ep| ep.data.clone())))
     }
 
+    async fn delete_group(&mut self, group_id: &[u8]) -> Result<bool, Self::Error> {
+        InMemoryGroupStateStorage::delete_group(self, group_id);
+        Ok(true)
+    }
+
     async fn write(
         &mut self,
         state: GroupState,
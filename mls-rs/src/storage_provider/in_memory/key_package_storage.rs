@@ -109,4 +109,8 @@ impl KeyPackageStorage for InMemoryKeyPackageStorage {
     async fn get(&self, id: &[u8]) -> Result<Option<KeyPackageData>, Self::Error> {
         Ok(self.get(id))
     }
+
+    async fn ids(&self) -> Result<Option<Vec<Vec<u8>>>, Self::Error> {
+        Ok(Some(self.lock().keys().cloned().collect()))
+    }
 }
@@ -31,9 +31,9 @@ impl Debug for EncryptContext<'_> {
 }
 
 impl<'a> EncryptContext<'a> {
-    pub fn new(label: &str, context: &'a [u8]) -> Self {
+    pub fn new(label: &[u8], context: &'a [u8]) -> Self {
         Self {
-            label: [b"MLS 1.0 ", label.as_bytes()].concat(),
+            label: label.to_vec(),
             context,
         }
     }
@@ -55,7 +55,22 @@ pub(crate) trait HpkeEncryptable: Sized {
         public_key: &HpkePublicKey,
         context: &[u8],
     ) -> Result<HpkeCiphertext, MlsError> {
-        let context = EncryptContext::new(Self::ENCRYPT_LABEL, context)
+        let label = [b"MLS 1.0 ", Self::ENCRYPT_LABEL.as_bytes()].concat();
+
+        self.encrypt_with_label(cipher_suite_provider, public_key, &label, context)
+            .await
+    }
+
+    /// Encrypt using `label` as the full label instead of `"MLS 1.0 "`
+    /// followed by [`Self::ENCRYPT_LABEL`].
+    async fn encrypt_with_label<P: CipherSuiteProvider>(
+        &self,
+        cipher_suite_provider: &P,
+        public_key: &HpkePublicKey,
+        label: &[u8],
+        context: &[u8],
+    ) -> Result<HpkeCiphertext, MlsError> {
+        let context = EncryptContext::new(label, context)
             .mls_encode_to_vec()
             .map(Zeroizing::new)?;
 
@@ -74,7 +89,30 @@ pub(crate) trait HpkeEncryptable: Sized {
         context: &[u8],
         ciphertext: &HpkeCiphertext,
     ) -> Result<Self, MlsError> {
-        let context = EncryptContext::new(Self::ENCRYPT_LABEL, context).mls_encode_to_vec()?;
+        let label = [b"MLS 1.0 ", Self::ENCRYPT_LABEL.as_bytes()].concat();
+
+        Self::decrypt_with_label(
+            cipher_suite_provider,
+            secret_key,
+            public_key,
+            &label,
+            context,
+            ciphertext,
+        )
+        .await
+    }
+
+    /// Decrypt using `label` as the full label instead of `"MLS 1.0 "`
+    /// followed by [`Self::ENCRYPT_LABEL`].
+    async fn decrypt_with_label<P: CipherSuiteProvider>(
+        cipher_suite_provider: &P,
+        secret_key: &HpkeSecretKey,
+        public_key: &HpkePublicKey,
+        label: &[u8],
+        context: &[u8],
+        ciphertext: &HpkeCiphertext,
+    ) -> Result<Self, MlsError> {
+        let context = EncryptContext::new(label, context).mls_encode_to_vec()?;
 
         let plaintext = cipher_suite_provider
             .hpke_open(ciphertext, secret_key, public_key, &context, None)
@@ -5,11 +5,12 @@
 use super::{parent_hash::ParentHash, Capabilities, Lifetime};
 use crate::client::MlsError;
 use crate::crypto::{CipherSuiteProvider, HpkePublicKey, HpkeSecretKey, SignatureSecretKey};
-use crate::{identity::SigningIdentity, signer::Signable, ExtensionList};
+use crate::{identity::SigningIdentity, signer::Signable, ExtensionList, KeyPackage};
 use alloc::vec::Vec;
 use core::fmt::{self, Debug};
 use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 use mls_rs_core::error::IntoAnyError;
+use mls_rs_core::group::LeafNodeSourceType;
 
 #[derive(Debug, Clone, MlsSize, MlsEncode, MlsDecode, PartialEq, Eq)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -21,6 +22,17 @@ pub enum LeafNodeSource {
     Commit(ParentHash) = 3u8,
 }
 
+impl LeafNodeSource {
+    /// The kind of this source, without the associated data.
+    pub fn source_type(&self) -> LeafNodeSourceType {
+        match self {
+            LeafNodeSource::KeyPackage(_) => LeafNodeSourceType::KeyPackage,
+            LeafNodeSource::Update => LeafNodeSourceType::Update,
+            LeafNodeSource::Commit(_) => LeafNodeSourceType::Commit,
+        }
+    }
+}
+
 #[derive(Clone, MlsSize, MlsEncode, MlsDecode, PartialEq)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -59,6 +71,27 @@ pub struct ConfigProperties {
 }
 
 impl LeafNode {
+    /// Extract the leaf node embedded in `key_package`.
+    ///
+    /// This is the leaf that is inserted into the tree when `key_package`
+    /// is added to a group. The key package is checked to be internally
+    /// consistent: its leaf node must have a key package source and its
+    /// init key must differ from the leaf encryption key. Signatures are
+    /// not verified.
+    pub fn from_key_package(key_package: &KeyPackage) -> Result<LeafNode, MlsError> {
+        let leaf_node = &key_package.leaf_node;
+
+        if !matches!(leaf_node.leaf_node_source, LeafNodeSource::KeyPackage(_)) {
+            return Err(MlsError::InvalidLeafNodeSource);
+        }
+
+        if key_package.hpke_init_key.as_ref() == leaf_node.public_key.as_ref() {
+            return Err(MlsError::InitLeafKeyEquality);
+        }
+
+        Ok(leaf_node.clone())
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn generate<CSP>(
         cipher_suite_provider: &CSP,
@@ -410,11 +443,12 @@ mod tests {
     use super::test_utils::*;
     use super::*;
 
-    use crate::client::test_utils::TEST_CIPHER_SUITE;
+    use crate::client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION};
     use crate::crypto::test_utils::test_cipher_suite_provider;
     use crate::crypto::test_utils::TestCryptoProvider;
     use crate::group::test_utils::random_bytes;
     use crate::identity::test_utils::get_test_signing_identity;
+    use crate::key_package::test_utils::test_key_package;
     use assert_matches::assert_matches;
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
@@ -692,4 +726,40 @@ mod tests {
 
         assert_matches!(res, Err(MlsError::InvalidSignature));
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn leaf_node_from_key_package() {
+        let key_package = test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+
+        let leaf_node = LeafNode::from_key_package(&key_package).unwrap();
+
+        assert_eq!(
+            leaf_node.mls_encode_to_vec().unwrap(),
+            key_package.leaf_node.mls_encode_to_vec().unwrap()
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn leaf_node_from_key_package_requires_key_package_source() {
+        let mut key_package =
+            test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+
+        key_package.leaf_node.leaf_node_source = LeafNodeSource::Update;
+
+        let res = LeafNode::from_key_package(&key_package);
+
+        assert_matches!(res, Err(MlsError::InvalidLeafNodeSource));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn leaf_node_from_key_package_requires_distinct_keys() {
+        let mut key_package =
+            test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+
+        key_package.hpke_init_key = key_package.leaf_node.public_key.clone();
+
+        let res = LeafNode::from_key_package(&key_package);
+
+        assert_matches!(res, Err(MlsError::InitLeafKeyEquality));
+    }
 }
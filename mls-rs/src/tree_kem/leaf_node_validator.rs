@@ -6,6 +6,11 @@ use super::leaf_node::{LeafNode, LeafNodeSigningContext, LeafNodeSource};
 use crate::client::MlsError;
 use crate::CipherSuiteProvider;
 use crate::{signer::Signable, time::MlsTime};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use mls_rs_core::error::AnyError;
+use mls_rs_core::extension::{ExtensionType, MlsExtension};
 use mls_rs_core::identity::MemberValidationContext;
 use mls_rs_core::{error::IntoAnyError, identity::IdentityProvider};
 
@@ -38,6 +43,106 @@ impl ValidationContext<'_> {
     }
 }
 
+/// Error returned by a leaf node extension validator registered with
+/// [`ClientBuilder::leaf_node_extension_validator`](crate::client_builder::ClientBuilder::leaf_node_extension_validator).
+#[derive(Debug)]
+pub struct LeafNodeValidationError(AnyError);
+
+impl LeafNodeValidationError {
+    pub fn new<E: IntoAnyError>(error: E) -> Self {
+        Self(error.into_any_error())
+    }
+}
+
+trait ExtensionValidator: Fn(&LeafNode) -> Result<(), MlsError> + Send + Sync {
+    fn clone_box(&self) -> Box<dyn ExtensionValidator>;
+}
+
+impl<F> ExtensionValidator for F
+where
+    F: Fn(&LeafNode) -> Result<(), MlsError> + Clone + Send + Sync + 'static,
+{
+    fn clone_box(&self) -> Box<dyn ExtensionValidator> {
+        Box::new(self.clone())
+    }
+}
+
+/// Application defined validators of leaf node extensions, run in addition
+/// to the standard leaf node checks, along with the group context extensions
+/// that leaf node extensions depend on.
+#[derive(Default)]
+pub struct LeafNodeExtensionValidators {
+    validators: Vec<Box<dyn ExtensionValidator>>,
+    group_extension_dependencies: Vec<(ExtensionType, ExtensionType)>,
+}
+
+impl LeafNodeExtensionValidators {
+    pub(crate) fn push<E, F>(&mut self, validator: F)
+    where
+        E: MlsExtension,
+        F: Fn(&E) -> Result<(), LeafNodeValidationError> + Clone + Send + Sync + 'static,
+    {
+        self.validators.push(Box::new(move |leaf_node: &LeafNode| {
+            let Some(extension) = leaf_node.extensions.get_as::<E>()? else {
+                return Ok(());
+            };
+
+            validator(&extension).map_err(|e| MlsError::InvalidLeafNodeExtension {
+                extension_type: E::extension_type(),
+                error: e.0,
+            })
+        }));
+    }
+
+    pub(crate) fn push_group_extension_dependency(
+        &mut self,
+        leaf_extension: ExtensionType,
+        group_extension: ExtensionType,
+    ) {
+        self.group_extension_dependencies
+            .push((leaf_extension, group_extension));
+    }
+
+    pub(crate) fn validate(&self, leaf_node: &LeafNode) -> Result<(), MlsError> {
+        self.validators
+            .iter()
+            .try_for_each(|validator| validator(leaf_node))
+    }
+
+    /// Leaf node extension types that depend on the group context extension
+    /// of type `group_extension`.
+    pub(crate) fn dependent_leaf_extensions(
+        &self,
+        group_extension: ExtensionType,
+    ) -> impl Iterator<Item = ExtensionType> + '_ {
+        self.group_extension_dependencies
+            .iter()
+            .filter(move |(_, group)| *group == group_extension)
+            .map(|(leaf, _)| *leaf)
+    }
+}
+
+impl Clone for LeafNodeExtensionValidators {
+    fn clone(&self) -> Self {
+        Self {
+            validators: self.validators.iter().map(|v| (**v).clone_box()).collect(),
+            group_extension_dependencies: self.group_extension_dependencies.clone(),
+        }
+    }
+}
+
+impl Debug for LeafNodeExtensionValidators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LeafNodeExtensionValidators")
+            .field("count", &self.validators.len())
+            .field(
+                "group_extension_dependencies",
+                &self.group_extension_dependencies,
+            )
+            .finish()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct LeafNodeValidator<'a, C, CP>
 where
@@ -47,6 +152,8 @@ where
     cipher_suite_provider: &'a CP,
     identity_provider: &'a C,
     context: MemberValidationContext<'a>,
+    extension_validators: Option<&'a LeafNodeExtensionValidators>,
+    skip_identity_validation: bool,
 }
 
 impl<'a, C: IdentityProvider, CP: CipherSuiteProvider> LeafNodeValidator<'a, C, CP> {
@@ -59,6 +166,28 @@ impl<'a, C: IdentityProvider, CP: CipherSuiteProvider> LeafNodeValidator<'a, C,
             cipher_suite_provider,
             identity_provider,
             context,
+            extension_validators: None,
+            skip_identity_validation: false,
+        }
+    }
+
+    pub fn with_extension_validators(
+        self,
+        extension_validators: &'a LeafNodeExtensionValidators,
+    ) -> Self {
+        Self {
+            extension_validators: Some(extension_validators),
+            ..self
+        }
+    }
+
+    /// Skip the validation of the credential by the identity provider, leaving
+    /// only the checks that do not depend on it.
+    #[cfg(feature = "external_client")]
+    pub fn without_identity_validation(self) -> Self {
+        Self {
+            skip_identity_validation: true,
+            ..self
         }
     }
 
@@ -181,14 +310,16 @@ impl<'a, C: IdentityProvider, CP: CipherSuiteProvider> LeafNodeValidator<'a, C,
         self.check_context(leaf_node, &context)?;
 
         // Verify the credential
-        self.identity_provider
-            .validate_member(
-                &leaf_node.signing_identity,
-                context.generation_time(),
-                self.context,
-            )
-            .await
-            .map_err(|e| MlsError::IdentityProviderError(e.into_any_error()))?;
+        if !self.skip_identity_validation {
+            self.identity_provider
+                .validate_member(
+                    &leaf_node.signing_identity,
+                    context.generation_time(),
+                    self.context,
+                )
+                .await
+                .map_err(|e| MlsError::IdentityProviderError(e.into_any_error()))?;
+        }
 
         // Verify that the credential signed the leaf node
         leaf_node
@@ -228,6 +359,10 @@ impl<'a, C: IdentityProvider, CP: CipherSuiteProvider> LeafNodeValidator<'a, C,
         #[cfg(feature = "by_ref_proposal")]
         self.validate_external_senders_ext_credentials(leaf_node)?;
 
+        if let Some(extension_validators) = self.extension_validators {
+            extension_validators.validate(leaf_node)?;
+        }
+
         Ok(())
     }
 }
@@ -267,6 +402,8 @@ mod tests {
                 cipher_suite_provider,
                 identity_provider,
                 context: MemberValidationContext::None,
+                extension_validators: None,
+                skip_identity_validation: false,
             }
         }
     }
@@ -22,18 +22,29 @@ impl Lifetime {
         }
     }
 
+    /// Lifetime of `s` seconds starting at the current system time.
+    ///
+    /// This reads the system clock. Use [`Lifetime::seconds_from`] to compute
+    /// a lifetime from the time of a custom [`Clock`](crate::time::Clock).
     pub fn seconds(s: u64) -> Result<Self, MlsError> {
         #[cfg(feature = "std")]
-        let not_before = MlsTime::now().seconds_since_epoch();
+        let now = MlsTime::now();
         #[cfg(not(feature = "std"))]
         // There is no clock on no_std, this is here just so that we can run tests.
-        let not_before = 3600u64;
+        let now = MlsTime::from(3600);
 
+        Self::seconds_from(now, s)
+    }
+
+    /// Same as [`Lifetime::seconds`], starting at `now` instead of the current
+    /// system time.
+    pub fn seconds_from(now: MlsTime, s: u64) -> Result<Self, MlsError> {
+        let not_before = now.seconds_since_epoch();
         let not_after = not_before.checked_add(s).ok_or(MlsError::TimeOverflow)?;
 
         Ok(Lifetime {
             // Subtract 1 hour to address time difference between machines
-            not_before: not_before - 3600,
+            not_before: not_before.saturating_sub(3600),
             not_after,
         })
     }
@@ -182,11 +182,54 @@ impl TreeKemPublic {
         self.nodes.total_leaf_count()
     }
 
-    #[cfg(any(test, all(feature = "custom_proposal", feature = "tree_index")))]
     pub fn occupied_leaf_count(&self) -> u32 {
         self.nodes.occupied_leaf_count()
     }
 
+    pub fn blank_leaf_count(&self) -> u32 {
+        self.nodes.blank_leaf_count()
+    }
+
+    /// Nodes receiving new keys from an update path sent by `sender`: its leaf
+    /// followed by its filtered direct path.
+    pub(crate) fn path_updated_nodes(&self, sender: LeafIndex) -> Result<Vec<NodeIndex>, MlsError> {
+        let filtered = self.nodes.filtered(sender)?;
+
+        let parents = self
+            .nodes
+            .direct_copath(sender)
+            .into_iter()
+            .zip(filtered)
+            .filter_map(|(pn, filtered)| (!filtered).then_some(pn.path));
+
+        Ok(core::iter::once(NodeIndex::from(sender))
+            .chain(parents)
+            .collect())
+    }
+
+    /// Occupied leaves below the nodes receiving new keys from an update path
+    /// sent by `sender`, in increasing order. The committer's leaf is always
+    /// included.
+    pub(crate) fn path_updated_leaves(
+        &self,
+        sender: LeafIndex,
+    ) -> Result<Vec<LeafIndex>, MlsError> {
+        let highest = self
+            .path_updated_nodes(sender)?
+            .last()
+            .copied()
+            .unwrap_or(NodeIndex::from(sender));
+
+        let (start, end) = tree_math::subtree(highest);
+
+        Ok(self
+            .nodes
+            .non_empty_leaves()
+            .map(|(index, _)| index)
+            .filter(|index| (start..end).contains(index))
+            .collect())
+    }
+
     pub fn get_leaf_node(&self, index: LeafIndex) -> Result<&LeafNode, MlsError> {
         self.nodes.borrow_as_leaf(index)
     }
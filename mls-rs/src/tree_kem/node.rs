@@ -175,7 +175,6 @@ impl DerefMut for NodeVec {
 }
 
 impl NodeVec {
-    #[cfg(any(test, all(feature = "custom_proposal", feature = "tree_index")))]
     pub fn occupied_leaf_count(&self) -> u32 {
         self.non_empty_leaves().count() as u32
     }
@@ -184,6 +183,10 @@ impl NodeVec {
         (self.len() as u32 / 2 + 1).next_power_of_two()
     }
 
+    pub fn blank_leaf_count(&self) -> u32 {
+        self.leaves().filter(|l| l.is_none()).count() as u32
+    }
+
     #[inline]
     pub fn borrow_node(&self, index: NodeIndex) -> Result<&Option<Node>, MlsError> {
         Ok(self.get(self.validate_index(index)?).unwrap_or(&None))
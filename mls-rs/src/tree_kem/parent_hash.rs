@@ -177,6 +177,12 @@ impl TreeKemPublic {
         self.update_hashes(&[index], cipher_suite_provider).await
     }
 
+    /// Validate that every non-blank parent node is covered by exactly one parent hash
+    /// chain, as described in RFC 9420 Section 7.9.2.
+    ///
+    /// A parent covered by a second chain fails immediately. Otherwise, the lowest parent
+    /// left uncovered once all chains are walked is reported as
+    /// [`MlsError::InvalidParentHash`].
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub(super) async fn validate_parent_hashes<P: CipherSuiteProvider>(
         &self,
@@ -200,12 +206,12 @@ impl TreeKemPublic {
         for (leaf_index, _) in self.nodes.non_empty_leaves() {
             let mut n = NodeIndex::from(leaf_index);
 
-            while let Some(mut ps) = n.parent_sibling(&num_leaves) {
+            'chain: while let Some(mut ps) = n.parent_sibling(&num_leaves) {
                 // Find the first non-blank ancestor p of n and p's co-path child s.
                 while self.nodes.is_blank(ps.parent)? {
                     // If we reached the root, we're done with this chain.
                     let Some(ps_parent) = ps.parent.parent_sibling(&num_leaves) else {
-                        return Ok(());
+                        break 'chain;
                     };
 
                     ps = ps_parent;
@@ -229,10 +235,12 @@ impl TreeKemPublic {
                 .await?;
 
                 if n_node.get_parent_hash() == Some(calculated) {
+                    let invalid = MlsError::InvalidParentHash { node: ps.parent };
+
                     // Check that "n is in the resolution of c, and the intersection of p's unmerged_leaves with the subtree
                     // under c is equal to the resolution of c with n removed".
                     let Some(cp) = ps.sibling.parent_sibling(&num_leaves) else {
-                        return Err(MlsError::ParentHashMismatch);
+                        return Err(invalid);
                     };
 
                     let c = cp.sibling;
@@ -262,7 +270,7 @@ impl TreeKemPublic {
                         n = ps.parent;
                     } else {
                         // If p is validated for the second time, the check fails ("all non-blank parent nodes are covered by exactly one such chain").
-                        return Err(MlsError::ParentHashMismatch);
+                        return Err(invalid);
                     }
                 } else {
                     // If n's parent_hash field doesn't match, we're done with this chain.
@@ -271,11 +279,14 @@ impl TreeKemPublic {
             }
         }
 
-        // The check passes iff all non-blank nodes are validated.
-        if nodes_to_validate.is_empty() {
-            Ok(())
-        } else {
-            Err(MlsError::ParentHashMismatch)
+        // The check passes iff all non-blank nodes are validated. The level of a node is the
+        // number of trailing ones of its index.
+        match nodes_to_validate
+            .into_iter()
+            .min_by_key(|node_index| (node_index.trailing_ones(), *node_index))
+        {
+            Some(node) => Err(MlsError::InvalidParentHash { node }),
+            None => Ok(()),
         }
     }
 }
@@ -426,6 +437,21 @@ mod tests {
             .validate_parent_hashes(&test_cipher_suite_provider(TEST_CIPHER_SUITE))
             .await;
 
-        assert_matches!(res, Err(MlsError::ParentHashMismatch));
+        assert_matches!(res, Err(MlsError::InvalidParentHash { node: 1 }));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn test_corrupted_parent_hash_names_lowest_invalid_node() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let mut test_tree = TreeWithSigners::make_full_tree(8, &cs).await.tree;
+
+        test_tree.nodes.borrow_as_parent_mut(5).unwrap().parent_hash =
+            ParentHash::from(hex!("f00d"));
+
+        let res = test_tree.validate_parent_hashes(&cs).await;
+
+        // Node 5 no longer matches the hash stored in its children, so no chain covers it. It
+        // is the lowest uncovered parent, so it is reported rather than any of its ancestors.
+        assert_matches!(res, Err(MlsError::InvalidParentHash { node: 5 }));
     }
 }
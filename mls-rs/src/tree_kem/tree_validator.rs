@@ -285,7 +285,7 @@ mod tests {
 
             let res = validator.validate(&mut test_tree).await;
 
-            assert_matches!(res, Err(MlsError::ParentHashMismatch));
+            assert_matches!(res, Err(MlsError::InvalidParentHash { node: 1 }));
         }
     }
 
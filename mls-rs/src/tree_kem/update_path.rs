@@ -190,6 +190,7 @@ mod tests {
             indexes_of_added_kpkgs: vec![],
             external_init_index: None,
             unused_proposals: vec![],
+            rejected_proposals: vec![],
         }
     }
 
@@ -731,6 +731,111 @@ async fn reinit_works() {
         .unwrap();
 }
 
+#[cfg(feature = "psk")]
+#[maybe_async::test(not(mls_build_async), async(mls_build_async, futures_test))]
+async fn complete_reinit_creates_new_group() {
+    let suite1 = CipherSuite::P256_AES128;
+
+    let Some(suite2) = CipherSuite::all()
+        .find(|cs| cs != &suite1 && TestCryptoProvider::all_supported_cipher_suites().contains(cs))
+    else {
+        return;
+    };
+
+    let version = ProtocolVersion::MLS_10;
+    let mut groups = get_test_groups(version, suite1, 3, false).await;
+
+    // Member 0 proposes reinit and commits it
+    let proposal = groups[0]
+        .propose_reinit(None, version, suite2, ExtensionList::default(), Vec::new())
+        .await
+        .unwrap();
+
+    all_process_message(&mut groups, &proposal, 0, false).await;
+
+    let commit = groups[0].commit(Vec::new()).await.unwrap().commit_message;
+    all_process_message(&mut groups, &commit, 0, true).await;
+
+    let mut new_identities = Vec::new();
+
+    for i in 0..3 {
+        let (secret_key, public_key) = TestCryptoProvider::default()
+            .cipher_suite_provider(suite2)
+            .unwrap()
+            .signature_key_generate()
+            .await
+            .unwrap();
+
+        let credential = get_test_basic_credential(format!("{i}").into_bytes());
+        new_identities.push((secret_key, SigningIdentity::new(credential, public_key)));
+    }
+
+    let mut groups = groups.into_iter();
+    let initiator = groups.next().unwrap();
+
+    // Other members generate key packages for the new group
+    let mut reinit_clients = Vec::new();
+    let mut key_packages = Vec::new();
+
+    for (group, (secret_key, identity)) in groups.zip(new_identities.drain(1..)) {
+        let reinit_client = group
+            .get_reinit_client(Some(secret_key), Some(identity))
+            .unwrap();
+
+        key_packages.push(reinit_client.generate_key_package().await.unwrap());
+        reinit_clients.push(reinit_client);
+    }
+
+    let (secret_key, identity) = new_identities.pop().unwrap();
+
+    let (mut new_group, welcome) = initiator
+        .complete_reinit(
+            Some(secret_key),
+            Some(identity),
+            key_packages,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(new_group.cipher_suite(), suite2);
+    assert_eq!(new_group.roster().members().len(), 3);
+
+    let mut new_groups = Vec::new();
+
+    for reinit_client in reinit_clients {
+        let (group, _) = reinit_client.join(&welcome[0], None).await.unwrap();
+        assert_eq!(group.cipher_suite(), suite2);
+        new_groups.push(group);
+    }
+
+    // The new group is functional
+    let commit = new_group.commit(Vec::new()).await.unwrap().commit_message;
+    new_group.apply_pending_commit().await.unwrap();
+
+    for group in &mut new_groups {
+        group
+            .process_incoming_message(commit.clone())
+            .await
+            .unwrap();
+    }
+}
+
+#[cfg(feature = "psk")]
+#[maybe_async::test(not(mls_build_async), async(mls_build_async, futures_test))]
+async fn complete_reinit_requires_pending_reinit() {
+    let mut groups =
+        get_test_groups(ProtocolVersion::MLS_10, CipherSuite::P256_AES128, 2, false).await;
+
+    let res = groups
+        .remove(0)
+        .complete_reinit(None, None, Vec::new(), Default::default())
+        .await
+        .map(|_| ());
+
+    assert_matches!(res, Err(MlsError::PendingReInitNotFound));
+}
+
 #[cfg(feature = "by_ref_proposal")]
 #[maybe_async::test(not(mls_build_async), async(mls_build_async, futures_test))]
 async fn external_joiner_can_process_siblings_update() {